@@ -0,0 +1,58 @@
+//! Compiles bundled name databases into static tables when `embedded-gmd` is enabled.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+/// Table names and their source files in `resources`.
+const RESOURCES: &[(&str, &str)] = &[
+    ("TD", "gmd.td.ini"),
+    ("RA", "gmd.ra.ini"),
+    ("TS", "gmd.ts.ini"),
+    ("YR", "gmd.yr.ini"),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_GMD").is_none() {
+        return;
+    }
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set");
+    let resources = Path::new(&manifest_dir).join("../resources");
+    let mut out = String::new();
+    for (table, file) in RESOURCES {
+        let path = resources.join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        let text = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
+        // Sections are ignored, every `ID=name` line is an entry. Further candidates
+        // of an ID are stored under `ID|N` keys, and all of them are kept.
+        let mut entries: Vec<(i32, usize, &str)> = text
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                let (key, index) = match key.trim().split_once('|') {
+                    Some((key, index)) => (key, index.parse().ok()?),
+                    None => (key.trim(), 0),
+                };
+                let id = u32::from_str_radix(key, 16).ok()? as i32;
+                let name = value.split(',').next().unwrap_or_default().trim();
+                Some((id, index, name))
+            })
+            .collect();
+        // Candidates of an ID end up next to each other, in file order.
+        entries.sort_by_key(|x| (x.0, x.1));
+        writeln!(out, "static BUILTIN_{table}: &[(i32, &str)] = &[").unwrap();
+        for (i, (id, _, name)) in entries.iter().enumerate() {
+            let repeated = entries[..i]
+                .iter()
+                .rev()
+                .take_while(|x| x.0 == *id)
+                .any(|x| x.2.eq_ignore_ascii_case(name));
+            if !repeated {
+                writeln!(out, "    ({id}, {name:?}),").unwrap();
+            }
+        }
+        writeln!(out, "];").unwrap();
+    }
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("builtin_gmd.rs"), out).expect("Failed to write tables");
+}
@@ -1,78 +1,334 @@
-use crate::{csf::CsfStringtable, ini::IniFile};
+//! Lossless conversion between CSF stringtables and INI files.
+//!
+//! Labels in `CATEGORY:NAME` format are grouped into `[CATEGORY]` sections with `NAME=string`
+//! entries, so ordinary stringtables look like hand-written INI files. Everything else is
+//! encoded so that converting back gives an identical stringtable:
+//!
+//! - Labels without a colon go into the [`NO_CATEGORY_SECTION`] section.
+//! - The header is stored in the [`HEADER_SECTION`] section.
+//! - Strings after the first are stored as `NAME|1`, `NAME|2` and so on, and extra values
+//!   (of `WRTS` strings) as `NAME|extra`, `NAME|1|extra` etc. Labels with no strings
+//!   are stored as `NAME|strings=0`.
+//! - Backslashes, line breaks and tabs are escaped as `\\`, `\n`, `\r` and `\t`. Semicolons,
+//!   other control characters and characters which would break the INI syntax are escaped
+//!   as `\uXXXX`.
+//! - Strings which are empty, start or end with whitespace, or are already in quotes are
+//!   put in double quotes.
+//! - If labels of one category are not next to each other, the following groups are stored
+//!   in `[CATEGORY:2]`, `[CATEGORY:3]`... sections to keep the label order.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::{
+    csf::{string_name, CsfLabel, CsfString, CsfStringtable},
+    ini::IniFile,
+};
+
+/// Section with labels not in `CATEGORY:NAME` format.
+pub const NO_CATEGORY_SECTION: &str = ":NoCategory";
+/// Section with the stringtable header.
+pub const HEADER_SECTION: &str = ":Header";
 
 /// The error type for CSF-INI conversions.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    /// The label has no strings.
-    #[error("Label {0} contains no strings")]
-    EmptyLabel(String),
-    /// The label name is not in CATEGORY:NAME format.
-    #[error("Label {0} is not in CATEGORY:NAME format, which is required")]
-    NoSplit(String),
+    /// A key in a stringtable section is not a label name with known suffixes.
+    #[error("Invalid key {0} in section {1}")]
+    InvalidKey(String, String),
+    /// A section name is neither a category nor a known special section.
+    #[error("Invalid section name {0}")]
+    InvalidSection(String),
+    /// An extra value contains characters which don't fit in a byte.
+    #[error("Extra value of {0} contains non-byte characters")]
+    InvalidExtraValue(String),
+    /// A header value is missing or not a number.
+    #[error("Invalid header value {0}")]
+    InvalidHeader(String),
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
 /// Convert a stringtable to an INI file, with CSF categories grouped into sections
-/// and strings/their values being entries.
-/// 
+/// and strings/their values being entries. Sections and entries keep the order of
+/// labels in the stringtable. Strings are escaped so that [`ini2csf`] restores them exactly.
+///
 /// # Examples
-/// 
+///
 /// ```ignore
-/// use rust_alert::{csf::CsfStringtable, ini::IniSection, converters::csf2ini};
-/// 
+/// use rust_alert::{csf::CsfStringtable, converters::csf2ini};
+///
 /// let mut csf = CsfStringtable::default();
 /// csf.create("BRIEF:ALL01", "Something");
+/// csf.create("NOCOLON", " Padded\n");
 /// let ini = csf2ini(csf);
 ///
-/// let mut expected = IniSection::new("BRIEF");
-/// expected.create_entry("ALL01", "Something");
-///
-/// assert!(ini.is_ok());
-/// let ini = ini.unwrap();
-/// assert_eq!(ini.get_section("BRIEF"), Some(&expected));
+/// assert_eq!(ini.get_str("BRIEF", "ALL01"), Some("Something"));
+/// assert_eq!(ini.get_str(":NoCategory", "NOCOLON"), Some("\" Padded\\n\""));
+/// assert_eq!(ini.get_str(":Header", "Version"), Some("3"));
 /// ```
-pub fn csf2ini(mut csf: CsfStringtable) -> Result<IniFile> {
+pub fn csf2ini(csf: CsfStringtable) -> IniFile {
     let mut ini = IniFile::default();
-    for label in csf.drain() {
-        let value = &label
-            .get_first()
-            .ok_or(Error::EmptyLabel(label.name.to_string()))?
-            .value
-            .replace('\n', "\\n");
-        let mut iter = label.name.split(':');
-        let kv = match (iter.next(), iter.next()) {
-            (Some(k), Some(v)) => Ok((k, v)),
-            _ => Err(Error::NoSplit(label.name.to_string())),
-        }?;
-        ini.add_to_section(kv.0, kv.1, value);
-    }
-    Ok(ini)
+    let version = u32::from(csf.version);
+    let language = u32::from(csf.language);
+    ini.add_to_section(HEADER_SECTION, "Version", version.to_string());
+    ini.add_to_section(HEADER_SECTION, "Language", language.to_string());
+    ini.add_to_section(HEADER_SECTION, "Extra", csf.extra.to_string());
+
+    let mut groups: HashMap<Option<&str>, usize> = HashMap::new();
+    let mut previous: Option<Option<&str>> = None;
+    let mut section = String::new();
+    for label in csf.iter() {
+        let (category, name) = match label.name.split_once(':') {
+            Some((category, name)) => (Some(category), name),
+            None => (None, label.name.as_str()),
+        };
+        if previous != Some(category) {
+            let count = groups.entry(category).or_default();
+            *count += 1;
+            let base = match category {
+                Some(category) => escape(category, "]"),
+                None => NO_CATEGORY_SECTION.to_string(),
+            };
+            section = match *count {
+                1 => base,
+                n => format!("{base}:{n}"),
+            };
+            previous = Some(category);
+        }
+        let key = escape_key(name);
+        if label.strings.is_empty() {
+            ini.add_to_section(&section, format!("{key}|strings"), "0");
+        }
+        for (i, string) in label.strings.iter().enumerate() {
+            let key = string_name(&key, i);
+            ini.add_to_section(&section, &key, quote(escape(&string.value, "")));
+            if !string.extra_value.is_empty() {
+                let extra: String = string.extra_value.iter().map(|b| *b as char).collect();
+                ini.add_to_section(&section, format!("{key}|extra"), quote(escape(&extra, "")));
+            }
+        }
+    }
+    ini
 }
 
 /// Convert an INI file to a stringtable, with CSF categories grouped into sections
-/// and strings/their values being entries.
-/// 
+/// and strings/their values being entries. Reverses [`csf2ini`]; the header is
+/// left default if the INI file has none.
+///
 /// # Examples
-/// 
+///
 /// ```ignore
 /// use rust_alert::{ini::IniFile, converters::ini2csf};
-/// 
+///
 /// let mut ini = IniFile::default();
 /// ini.add_to_section("BRIEF", "ALL01", "Something");
-/// let csf = ini2csf(ini);
+/// ini.add_to_section("BRIEF", "ALL01|1", "\"  Second\\n\"");
+/// let csf = ini2csf(ini).unwrap();
 ///
-/// assert_eq!(csf.get_str("BRIEF:ALL01"), Some("Something"));
+/// let label = csf.get("BRIEF:ALL01").unwrap();
+/// assert_eq!(label.strings[0].value, "Something");
+/// assert_eq!(label.strings[1].value, "  Second\n");
 /// ```
-pub fn ini2csf(mut ini: IniFile) -> CsfStringtable {
+pub fn ini2csf(mut ini: IniFile) -> Result<CsfStringtable> {
     let mut csf = CsfStringtable::default();
-    for (name, mut section) in ini.drain() {
+    if let Some(mut header) = ini.remove_section(HEADER_SECTION) {
+        let mut get = |key: &str| -> Result<u32> {
+            header
+                .remove_entry(key)
+                .and_then(|x| x.value.parse().ok())
+                .ok_or(Error::InvalidHeader(key.to_string()))
+        };
+        csf.version = get("Version")?.into();
+        csf.language = get("Language")?.into();
+        csf.extra = get("Extra")?;
+    }
+
+    // Values and extra values of strings by index, along with the section of every label.
+    // Keys can come in any order, like `NAME|10` before `NAME|2` in sorted files.
+    type Strings = Vec<(Option<String>, Vec<u8>)>;
+    let mut labels: IndexMap<String, (String, Strings)> = IndexMap::new();
+    for (section_name, mut section) in ini.drain() {
+        let category = parse_section(&section_name)?;
         for (key, entry) in section.drain() {
-            let value = entry.value.replace("\\n", "\n");
-            csf.create(format!("{name}:{key}"), value);
+            let invalid = || Error::InvalidKey(key.clone(), section_name.clone());
+            let mut parts = key.split('|');
+            let name = unescape_key(parts.next().unwrap_or_default());
+            let name = match category {
+                Some(ref category) => format!("{category}:{name}"),
+                None => name,
+            };
+            let (_, strings) = labels
+                .entry(name)
+                .or_insert_with(|| (section_name.clone(), vec![]));
+            let value = unescape(unquote(&entry.value));
+            let (index, extra) = match (parts.next(), parts.next(), parts.next()) {
+                (None, _, _) => (0, false),
+                (Some("strings"), None, _) => {
+                    if value != "0" {
+                        return Err(invalid());
+                    }
+                    continue;
+                }
+                (Some("extra"), None, _) => (0, true),
+                (Some(i), None, _) => (i.parse().map_err(|_| invalid())?, false),
+                (Some(i), Some("extra"), None) => (i.parse().map_err(|_| invalid())?, true),
+                _ => return Err(invalid()),
+            };
+            if strings.len() <= index {
+                strings.resize(index + 1, Default::default());
+            }
+            let string = &mut strings[index];
+            if extra {
+                string.1 = value
+                    .chars()
+                    .map(|c| u8::try_from(c).map_err(|_| Error::InvalidExtraValue(key.clone())))
+                    .collect::<Result<_>>()?;
+            } else {
+                string.0 = Some(value);
+            }
+        }
+    }
+    for (name, (section, strings)) in labels {
+        let strings = strings
+            .into_iter()
+            .enumerate()
+            .map(|(i, (value, extra_value))| match value {
+                Some(value) => Ok(CsfString { value, extra_value }),
+                // An extra value or a later string without the string itself.
+                None => Err(Error::InvalidKey(string_name(&name, i), section.clone())),
+            })
+            .collect::<Result<_>>()?;
+        csf.insert(CsfLabel { name, strings });
+    }
+    Ok(csf)
+}
+
+/// Escape a label name the way [`csf2ini`] does, so that it can be used as an INI key.
+/// Keys written this way are read back unchanged by [`IniReader`][crate::ini::io::IniReader],
+/// even if they have semicolons, `=`, `|`, a leading `[` or surrounding whitespace.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::converters::escape_key;
+///
+/// assert_eq!(escape_key("GUI:Quit"), "GUI:Quit");
+/// assert_eq!(escape_key("[A;B] "), "\"\\u005BA\\u003BB] \"");
+/// ```
+pub fn escape_key(name: &str) -> String {
+    quote(escape(name, "[=|"))
+}
+
+/// Reverse [`escape_key`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::converters::unescape_key;
+///
+/// assert_eq!(unescape_key("\"\\u005BA\\u003BB] \""), "[A;B] ");
+/// ```
+pub fn unescape_key(key: &str) -> String {
+    unescape(unquote(key))
+}
+
+/// Get the category of labels in given section, or `None` for labels with no category.
+fn parse_section(name: &str) -> Result<Option<String>> {
+    let base = match name.split_once(':') {
+        Some((base, n)) if n.parse::<usize>().is_ok() => base,
+        Some(_) if name.starts_with(NO_CATEGORY_SECTION) => {
+            let rest = &name[NO_CATEGORY_SECTION.len()..];
+            return match rest.strip_prefix(':').map(|x| x.parse::<usize>()) {
+                None if rest.is_empty() => Ok(None),
+                Some(Ok(_)) => Ok(None),
+                _ => Err(Error::InvalidSection(name.to_string())),
+            };
+        }
+        Some(_) => return Err(Error::InvalidSection(name.to_string())),
+        None => name,
+    };
+    Ok(Some(unescape(base)))
+}
+
+/// Escape a string, so that it can be safely put in an INI file. Characters in `special`
+/// are escaped too.
+fn escape(string: &str, special: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c == ';' || c.is_control() || special.contains(c) => {
+                result.push_str(&format!("\\u{:04X}", c as u32))
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Reverse [`escape`]. Unknown or malformed escapes are kept as they are.
+fn unescape(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('u') => {
+                let hex: String = chars.clone().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(x) if hex.len() == 4 => {
+                        result.push(x);
+                        chars.nth(3);
+                    }
+                    _ => result.push_str("\\u"),
+                }
+            }
+            Some(x) => {
+                result.push('\\');
+                result.push(x);
+            }
+            None => result.push('\\'),
         }
     }
-    csf
+    result
+}
+
+/// Put an escaped string in quotes if the INI reader would change it otherwise.
+fn quote(string: String) -> String {
+    if string.is_empty()
+        || string.starts_with(char::is_whitespace)
+        || string.ends_with(char::is_whitespace)
+        || is_quoted(&string)
+    {
+        format!("\"{string}\"")
+    } else {
+        string
+    }
+}
+
+/// Reverse [`quote`].
+fn unquote(string: &str) -> &str {
+    if is_quoted(string) {
+        &string[1..string.len() - 1]
+    } else {
+        string
+    }
+}
+
+fn is_quoted(string: &str) -> bool {
+    string.len() >= 2 && string.starts_with('"') && string.ends_with('"')
 }
 
 #[cfg(test)]
@@ -81,43 +337,228 @@ mod examples {
 
     #[test]
     fn _csf2ini() {
-        use rust_alert::{csf::CsfStringtable, ini::IniSection, converters::csf2ini};
+        use rust_alert::{converters::csf2ini, csf::CsfStringtable};
 
         let mut csf = CsfStringtable::default();
         csf.create("BRIEF:ALL01", "Something");
+        csf.create("NOCOLON", " Padded\n");
         let ini = csf2ini(csf);
 
-        let mut expected = IniSection::new("BRIEF");
-        expected.create_entry("ALL01", "Something");
-
-        assert!(ini.is_ok());
-        let ini = ini.unwrap();
-        assert_eq!(ini.get_section("BRIEF"), Some(&expected));
+        assert_eq!(ini.get_str("BRIEF", "ALL01"), Some("Something"));
+        assert_eq!(
+            ini.get_str(":NoCategory", "NOCOLON"),
+            Some("\" Padded\\n\"")
+        );
+        assert_eq!(ini.get_str(":Header", "Version"), Some("3"));
     }
 
     #[test]
     fn _ini2csf() {
-        use rust_alert::{ini::IniFile, converters::ini2csf};
+        use rust_alert::{converters::ini2csf, ini::IniFile};
 
         let mut ini = IniFile::default();
         ini.add_to_section("BRIEF", "ALL01", "Something");
-        let csf = ini2csf(ini);
+        ini.add_to_section("BRIEF", "ALL01|1", "\"  Second\\n\"");
+        let csf = ini2csf(ini).unwrap();
+
+        let label = csf.get("BRIEF:ALL01").unwrap();
+        assert_eq!(label.strings[0].value, "Something");
+        assert_eq!(label.strings[1].value, "  Second\n");
+    }
+
+    #[test]
+    fn escape_key() {
+        use rust_alert::converters::escape_key;
+
+        assert_eq!(escape_key("GUI:Quit"), "GUI:Quit");
+        assert_eq!(escape_key("[A;B] "), "\"\\u005BA\\u003BB] \"");
+    }
 
-        assert_eq!(csf.get_str("BRIEF:ALL01"), Some("Something"));
+    #[test]
+    fn unescape_key() {
+        use rust_alert::converters::unescape_key;
+
+        assert_eq!(unescape_key("\"\\u005BA\\u003BB] \""), "[A;B] ");
     }
 }
 
 #[cfg(test)]
-mod coverage {
-    use crate::csf::CsfStringtable;
-    use super::csf2ini;
+mod tests {
+    use crate::{
+        converters::csf2ini::{
+            csf2ini, escape, escape_key, ini2csf, quote, unescape, unescape_key, unquote, Error,
+        },
+        csf::{
+            io::{CsfReader, CsfWrite},
+            CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable, CsfVersionEnum,
+        },
+        ini::{
+            io::{IniReader, IniWriter},
+            IniFile,
+        },
+    };
+
+    fn round_trip(csf: &CsfStringtable) -> CsfStringtable {
+        let ini = csf2ini(csf.clone());
+        let mut text = vec![];
+        IniWriter::write_file(&ini, &mut text).unwrap();
+        let ini = IniReader::read_file(text.as_slice()).unwrap();
+        ini2csf(ini).unwrap()
+    }
+
+    fn to_bytes(csf: &CsfStringtable) -> Vec<u8> {
+        let mut buf = vec![];
+        CsfReader::new().write(csf, &mut buf).unwrap();
+        buf
+    }
 
     #[test]
-    fn _csf2ini() {
+    fn lossless() {
+        let mut csf = CsfStringtable::new(CsfVersionEnum::Nox, CsfLanguageEnum::DE, 7);
+        csf.create("GUI:Quit", "Quit");
+        csf.create("NoColon", "No category");
+        csf.create("A:B:C", "Colon in name");
+        csf.create(":Empty", "");
+        csf.create("GUI:Spaces", "  both ends\t");
+        csf.create("GUI:Special", "a;b=c\\d\r\n\"q\"[x]|y\u{1}");
+        csf.create("GUI:\"Quoted\"", "\"");
+        csf.create("GUI:[Odd=|key ", "value");
+        csf.create("Cat]x;:Name", "Special category");
+        csf.create("MAP:Other", "Interleaved");
+        csf.create("GUI:Again", "Same category later");
+        csf.insert(CsfLabel {
+            name: "GUI:Many".to_string(),
+            strings: vec![
+                CsfString {
+                    value: "First".to_string(),
+                    extra_value: b"extra; \x00\xFF".to_vec(),
+                },
+                CsfString::new("Second"),
+                CsfString {
+                    value: "".to_string(),
+                    extra_value: b"x".to_vec(),
+                },
+            ],
+        });
+        csf.insert(CsfLabel {
+            name: "GUI:None".to_string(),
+            strings: vec![],
+        });
+        csf.create("Other", "Second group of no category");
+
+        let actual = round_trip(&csf);
+        assert_eq!(to_bytes(&actual), to_bytes(&csf));
+    }
+
+    #[test]
+    fn many_strings_sorted() {
+        let mut csf = CsfStringtable::default();
+        csf.insert(CsfLabel {
+            name: "BRIEF:ALL01".to_string(),
+            strings: (0..12)
+                .map(|i| CsfString {
+                    value: format!("String {i}"),
+                    extra_value: if i == 10 { b"x".to_vec() } else { vec![] },
+                })
+                .collect(),
+        });
+        let mut ini = csf2ini(csf.clone());
+        // Sorting puts `ALL01|10` before `ALL01|2`.
+        ini.sort_nested();
+        assert_eq!(ini2csf(ini).unwrap(), csf);
+
+        let mut ini = IniFile::default();
+        ini.add_to_section("BRIEF", "ALL01", "First");
+        ini.add_to_section("BRIEF", "ALL01|2", "Third");
+        assert!(matches!(ini2csf(ini), Err(Error::InvalidKey(_, _))));
+    }
+
+    #[test]
+    fn groups() {
         let mut csf = CsfStringtable::default();
-        csf.create("BRIEFALL01", "Something");
+        csf.create("A:1", "x");
+        csf.create("B:1", "x");
+        csf.create("A:2", "x");
+        csf.create("X", "x");
+        csf.create("B:2", "x");
+        csf.create("Y", "x");
         let ini = csf2ini(csf);
-        assert!(ini.is_err());
-        matches!(ini, Err(super::Error::NoSplit(_)));
+        let sections: Vec<_> = ini.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            sections,
+            vec![
+                ":Header",
+                "A",
+                "B",
+                "A:2",
+                ":NoCategory",
+                "B:2",
+                ":NoCategory:2"
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_ini() {
+        let text = "[GUI]\nQuit=Quit the game\\n; comment\nPath=C:\\data\n";
+        let ini = IniReader::read_file(text.as_bytes()).unwrap();
+        let csf = ini2csf(ini).unwrap();
+        assert_eq!(csf.get_str("GUI:Quit"), Some("Quit the game\n"));
+        assert_eq!(csf.get_str("GUI:Path"), Some("C:\\data"));
+        assert_eq!(csf.version, CsfVersionEnum::Cnc);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a;b\\c\nd", "="), "a\\u003Bb\\\\c\\nd");
+        assert_eq!(escape("a=b", "="), "a\\u003Db");
+        assert_eq!(unescape("a\\u003Bb\\\\c\\nd"), "a;b\\c\nd");
+        assert_eq!(unescape("\\q\\u12\\uZZZZ\\"), "\\q\\u12\\uZZZZ\\");
+        assert_eq!(quote("".to_string()), "\"\"");
+        assert_eq!(quote(" a".to_string()), "\" a\"");
+        assert_eq!(quote("a\"".to_string()), "a\"");
+        assert_eq!(unquote("\"\""), "");
+        assert_eq!(unquote("\""), "\"");
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        let names = ["A;B", "A=B", "[A]", " A ", "\"A\"", "A|1", "", "A\\n"];
+        let mut ini = IniFile::default();
+        for name in names {
+            ini.add_to_section("Keys", escape_key(name), "1");
+        }
+        let mut text = vec![];
+        IniWriter::write_file(&ini, &mut text).unwrap();
+        let ini = IniReader::read_file(text.as_slice()).unwrap();
+        let section = ini.get_section("Keys").unwrap();
+        let keys: Vec<_> = section.iter().map(|(key, _)| unescape_key(key)).collect();
+        assert_eq!(keys, names);
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("[A]\nB|x=1", "InvalidKey"),
+            ("[A]\nB|2=1", "InvalidKey"),
+            ("[A]\nB|extra=1", "InvalidKey"),
+            ("[A]\nB|strings=1", "InvalidKey"),
+            ("[A]\nB=1\nB|extra=\u{100}", "InvalidExtraValue"),
+            ("[A:B]\nB=1", "InvalidSection"),
+            ("[:NoCategory:x]\nB=1", "InvalidSection"),
+            ("[:Header]\nVersion=3", "InvalidHeader"),
+        ];
+        for (text, expected) in cases {
+            let ini = IniReader::read_file(text.as_bytes()).unwrap();
+            let actual = match ini2csf(ini) {
+                Err(Error::InvalidKey(..)) => "InvalidKey",
+                Err(Error::InvalidSection(_)) => "InvalidSection",
+                Err(Error::InvalidExtraValue(_)) => "InvalidExtraValue",
+                Err(Error::InvalidHeader(_)) => "InvalidHeader",
+                Ok(_) => "Ok",
+            };
+            assert_eq!(actual, expected, "{text}");
+        }
+        assert_eq!(ini2csf(IniFile::default()).unwrap().len(), 0);
     }
 }
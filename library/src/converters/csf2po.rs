@@ -0,0 +1,328 @@
+//! Conversion between CSF stringtables and gettext PO files.
+//!
+//! Every string of a label becomes a PO entry, with the label name as its context
+//! (`msgctxt`) and the string of the base stringtable as its message (`msgid`).
+//! Strings after the first are stored with `LABEL|1`, `LABEL|2`... contexts, which are only
+//! read as such if they directly follow the previous string of the label.
+//! Extra values of `WRTS` strings are not stored.
+
+use indexmap::IndexMap;
+
+use crate::{
+    csf::{parse_string_name, string_name, CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable},
+    po::{PoEntry, PoFile},
+};
+
+/// The error type for CSF-PO conversions.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// More than one entry has the same context.
+    #[error("Duplicate context {0}")]
+    DuplicateContext(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Summary of a PO file import, listing contexts which were not imported as translations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoImportReport {
+    /// Contexts of fuzzy entries, which fell back to the base language.
+    pub fuzzy: Vec<String>,
+    /// Contexts of untranslated entries, which fell back to the base language.
+    pub missing: Vec<String>,
+    /// Messages of entries with no context, which were skipped.
+    pub skipped: Vec<String>,
+}
+
+/// Make a PO template from a base stringtable, with no translations.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{converters::csf2pot, csf::CsfStringtable};
+///
+/// let mut csf = CsfStringtable::default();
+/// csf.create("GUI:Quit", "Quit");
+/// let pot = csf2pot(&csf);
+///
+/// let entry = pot.get("GUI:Quit").unwrap();
+/// assert_eq!(entry.id, "Quit");
+/// assert_eq!(entry.string, "");
+/// ```
+pub fn csf2pot(base: &CsfStringtable) -> PoFile {
+    make_po(base, None)
+}
+
+/// Make a PO file from a base stringtable and its translation. Strings missing from
+/// the translation are left untranslated. Labels not in the base stringtable are skipped.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::csf2po,
+///     csf::{CsfLanguageEnum, CsfStringtable},
+/// };
+///
+/// let mut base = CsfStringtable::default();
+/// base.create("GUI:Quit", "Quit");
+/// base.create("GUI:Load", "Load");
+/// let mut translation = CsfStringtable::default();
+/// translation.language = CsfLanguageEnum::DE;
+/// translation.create("GUI:Quit", "Beenden");
+/// let po = csf2po(&base, &translation);
+///
+/// assert_eq!(po.header.get("Language").unwrap(), "de");
+/// assert_eq!(po.get("GUI:Quit").unwrap().string, "Beenden");
+/// assert_eq!(po.get("GUI:Load").unwrap().string, "");
+/// ```
+pub fn csf2po(base: &CsfStringtable, translation: &CsfStringtable) -> PoFile {
+    let mut po = make_po(base, Some(translation));
+    if let Some(code) = translation.language.code() {
+        po.header.insert("Language".to_string(), code.to_string());
+    }
+    po
+}
+
+/// Make a stringtable in given language from a PO file. Fuzzy and untranslated
+/// entries fall back to their message in the base language, and are listed in
+/// the returned report. Labels keep the order of entries.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::po2csf,
+///     csf::CsfLanguageEnum,
+///     po::{PoEntry, PoFile},
+/// };
+///
+/// let mut po = PoFile::default();
+/// po.entries.push(PoEntry::new("GUI:Quit", "Quit", "Beenden"));
+/// po.entries.push(PoEntry::new("GUI:Load", "Load", ""));
+/// let (csf, report) = po2csf(&po, CsfLanguageEnum::DE).unwrap();
+///
+/// assert_eq!(csf.language, CsfLanguageEnum::DE);
+/// assert_eq!(csf.get_str("GUI:Quit"), Some("Beenden"));
+/// assert_eq!(csf.get_str("GUI:Load"), Some("Load"));
+/// assert_eq!(report.missing, vec!["GUI:Load"]);
+/// ```
+pub fn po2csf(po: &PoFile, language: CsfLanguageEnum) -> Result<(CsfStringtable, PoImportReport)> {
+    let mut report = PoImportReport::default();
+    let mut labels: IndexMap<&str, CsfLabel> = IndexMap::new();
+    let mut previous = None;
+    for entry in &po.entries {
+        let Some(context) = &entry.context else {
+            report.skipped.push(entry.id.clone());
+            previous = None;
+            continue;
+        };
+        // A suffix is only a string index if it continues the label of the previous entry.
+        let (name, index) = match parse_string_name(context) {
+            (name, i) if is_next_index(previous.and_then(|x| labels.get(x)), name, i) => (name, i),
+            _ => (context.as_str(), 0),
+        };
+        if index == 0 && labels.contains_key(name) {
+            return Err(Error::DuplicateContext(context.clone()));
+        }
+        let value = if entry.is_translated() {
+            &entry.string
+        } else if entry.is_fuzzy() {
+            report.fuzzy.push(context.clone());
+            &entry.id
+        } else {
+            report.missing.push(context.clone());
+            &entry.id
+        };
+        let label = labels.entry(name).or_insert_with(|| CsfLabel {
+            name: name.to_string(),
+            strings: vec![],
+        });
+        label.strings.push(CsfString::new(value));
+        previous = Some(name);
+    }
+
+    let mut csf = CsfStringtable::new(Default::default(), language, 0);
+    csf.extend(labels.into_values());
+    Ok((csf, report))
+}
+
+/// Check if `index` is the index of the next string of the previously read label.
+fn is_next_index(previous: Option<&CsfLabel>, name: &str, index: usize) -> bool {
+    previous.is_some_and(|x| x.name == name && index > 0 && index == x.strings.len())
+}
+
+/// Make a PO file with entries for all strings of the base stringtable,
+/// and header fields required for PO files in UTF-8.
+fn make_po(base: &CsfStringtable, translation: Option<&CsfStringtable>) -> PoFile {
+    let mut po = PoFile::default();
+    for (key, value) in [
+        ("MIME-Version", "1.0"),
+        ("Content-Type", "text/plain; charset=UTF-8"),
+        ("Content-Transfer-Encoding", "8bit"),
+    ] {
+        po.header.insert(key.to_string(), value.to_string());
+    }
+    for label in base.iter() {
+        let translated = translation.and_then(|x| x.get(&label.name));
+        for (i, string) in label.strings.iter().enumerate() {
+            let value = translated
+                .and_then(|x| x.strings.get(i))
+                .map_or("", |x| x.value.as_str());
+            po.entries.push(PoEntry::new(
+                string_name(&label.name, i),
+                &string.value,
+                value,
+            ));
+        }
+    }
+    po
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn _csf2pot() {
+        use rust_alert::{converters::csf2pot, csf::CsfStringtable};
+
+        let mut csf = CsfStringtable::default();
+        csf.create("GUI:Quit", "Quit");
+        let pot = csf2pot(&csf);
+
+        let entry = pot.get("GUI:Quit").unwrap();
+        assert_eq!(entry.id, "Quit");
+        assert_eq!(entry.string, "");
+    }
+
+    #[test]
+    fn _csf2po() {
+        use rust_alert::{
+            converters::csf2po,
+            csf::{CsfLanguageEnum, CsfStringtable},
+        };
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.create("GUI:Load", "Load");
+        let mut translation = CsfStringtable::default();
+        translation.language = CsfLanguageEnum::DE;
+        translation.create("GUI:Quit", "Beenden");
+        let po = csf2po(&base, &translation);
+
+        assert_eq!(po.header.get("Language").unwrap(), "de");
+        assert_eq!(po.get("GUI:Quit").unwrap().string, "Beenden");
+        assert_eq!(po.get("GUI:Load").unwrap().string, "");
+    }
+
+    #[test]
+    fn _po2csf() {
+        use rust_alert::{
+            converters::po2csf,
+            csf::CsfLanguageEnum,
+            po::{PoEntry, PoFile},
+        };
+
+        let mut po = PoFile::default();
+        po.entries.push(PoEntry::new("GUI:Quit", "Quit", "Beenden"));
+        po.entries.push(PoEntry::new("GUI:Load", "Load", ""));
+        let (csf, report) = po2csf(&po, CsfLanguageEnum::DE).unwrap();
+
+        assert_eq!(csf.language, CsfLanguageEnum::DE);
+        assert_eq!(csf.get_str("GUI:Quit"), Some("Beenden"));
+        assert_eq!(csf.get_str("GUI:Load"), Some("Load"));
+        assert_eq!(report.missing, vec!["GUI:Load"]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        converters::csf2po::{csf2po, csf2pot, po2csf, Error},
+        csf::{CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable},
+        po::{
+            io::{PoReader, PoWriter},
+            PoEntry, PoFile,
+        },
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.insert(CsfLabel {
+            name: "Multi|1".to_string(),
+            strings: vec![CsfString::new("First\n\"Line\""), CsfString::new("Second")],
+        });
+        base.create("GUI:Fuzzy", "Fuzzy");
+        let mut translation = base.clone();
+        translation.language = CsfLanguageEnum::FR;
+        translation.create("GUI:Quit", "Quitter");
+        translation.insert(CsfLabel {
+            name: "Multi|1".to_string(),
+            strings: vec![
+                CsfString::new("First\n\"Line\""),
+                CsfString::new("Deuxième"),
+            ],
+        });
+
+        let mut po = csf2po(&base, &translation);
+        po.entries[3].flags.push("fuzzy".to_string());
+        let mut buf = vec![];
+        PoWriter::write_file(&po, &mut buf).unwrap();
+        let po = PoReader::read_file(buf.as_slice()).unwrap();
+        assert_eq!(po.header.get("Language").unwrap(), "fr");
+        let (csf, report) = po2csf(&po, CsfLanguageEnum::FR).unwrap();
+
+        let names: Vec<_> = csf.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["GUI:Quit", "Multi|1", "GUI:Fuzzy"]);
+        assert_eq!(csf.get_str("GUI:Quit"), Some("Quitter"));
+        let multi = csf.get("Multi|1").unwrap();
+        assert_eq!(multi.strings[0].value, "First\n\"Line\"");
+        assert_eq!(multi.strings[1].value, "Deuxième");
+        assert_eq!(csf.get_str("GUI:Fuzzy"), Some("Fuzzy"));
+        assert_eq!(report.fuzzy, vec!["GUI:Fuzzy"]);
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn pot() {
+        let mut base = CsfStringtable::default();
+        base.create("A", "1");
+        let pot = csf2pot(&base);
+        assert!(!pot.header.contains_key("Language"));
+        let (csf, report) = po2csf(&pot, CsfLanguageEnum::ENUS).unwrap();
+        assert_eq!(csf.get_str("A"), Some("1"));
+        assert_eq!(report.missing, vec!["A"]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut po = PoFile::default();
+        po.entries.push(PoEntry {
+            id: "No context".to_string(),
+            ..Default::default()
+        });
+        let (csf, report) = po2csf(&po, CsfLanguageEnum::ENUS).unwrap();
+        assert_eq!(csf.len(), 0);
+        assert_eq!(report.skipped, vec!["No context"]);
+
+        po.entries.push(PoEntry::new("A", "1", ""));
+        po.entries.push(PoEntry::new("A|2", "Gap", ""));
+        let (csf, _) = po2csf(&po, CsfLanguageEnum::ENUS).unwrap();
+        assert_eq!(csf.get_str("A|2"), Some("Gap"));
+
+        po.entries.push(PoEntry::new("B", "Other", ""));
+        po.entries.push(PoEntry::new("A|1", "Not adjacent", ""));
+        let (csf, _) = po2csf(&po, CsfLanguageEnum::ENUS).unwrap();
+        assert_eq!(csf.get("A").unwrap().strings.len(), 1);
+        assert_eq!(csf.get_str("A|1"), Some("Not adjacent"));
+
+        po.entries.push(PoEntry::new("A", "Again", ""));
+        assert!(matches!(
+            po2csf(&po, CsfLanguageEnum::ENUS),
+            Err(Error::DuplicateContext(_))
+        ));
+    }
+}
@@ -0,0 +1,513 @@
+//! Conversion between CSF stringtables and multi-language tables.
+//!
+//! The first row names the [`LABEL_COLUMN`] and one language per stringtable, as
+//! in XLIFF files. The [`VERSION_ROW`] and [`EXTRA_ROW`] rows hold header values of
+//! each stringtable. Every other row holds one string in all languages, with strings
+//! after the first named `LABEL|1`, `LABEL|2` and so on.
+//!
+//! The first language column is the base. Empty cells in it are empty strings, and empty
+//! cells in other columns are untranslated strings, which get the string of the base
+//! column, like untranslated PO entries do. Spreadsheet programs don't keep quotes
+//! of empty cells, so no other difference survives editing a table in them. Every
+//! stringtable read from a table has all of its labels. Extra values of strings are
+//! not kept.
+//!
+//! Labels with no strings get a `LABEL|strings` row with `0` in every column, like in
+//! INI files. Label names starting with `:` get another `:` in front, so that they are
+//! never mistaken for header rows.
+
+use std::collections::HashSet;
+
+use indexmap::IndexSet;
+
+use crate::{
+    csf::{parse_string_name, string_name, CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable},
+    table::TableFile,
+};
+
+/// Name of the first column, with label names.
+pub const LABEL_COLUMN: &str = "Label";
+/// Name of the row with stringtable versions.
+pub const VERSION_ROW: &str = ":Version";
+/// Name of the row with extra values of stringtable headers.
+pub const EXTRA_ROW: &str = ":Extra";
+
+/// The error type for conversions between CSF stringtables and tables.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Two stringtables or columns have the same language.
+    #[error("Language {0} appears more than once")]
+    DuplicateLanguage(String),
+    /// The table has no header row.
+    #[error("Table has no header row")]
+    MissingHeader,
+    /// A column name is not a language.
+    #[error("Column name {0:?} is not a language")]
+    InvalidLanguage(String),
+    /// A header row has a value which is not a number.
+    #[error("Value {1:?} in row {0} is not a number")]
+    InvalidHeaderValue(String, String),
+    /// A row has more cells than there are languages.
+    #[error("Row {0} has more cells than there are languages")]
+    TooManyCells(usize),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Make a table from stringtables, with one column per stringtable.
+/// Rows follow the order of labels in the first stringtable, followed by labels
+/// missing from it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::csf2table,
+///     csf::{CsfLanguageEnum, CsfStringtable},
+/// };
+///
+/// let mut base = CsfStringtable::default();
+/// base.create("GUI:Quit", "Quit");
+/// let mut translation = CsfStringtable::default();
+/// translation.language = CsfLanguageEnum::DE;
+/// translation.create("GUI:Quit", "Beenden");
+/// let table = csf2table(&[base, translation]).unwrap();
+///
+/// assert_eq!(table.get(0, 2), Some("de"));
+/// assert_eq!(table.get(3, 0), Some("GUI:Quit"));
+/// assert_eq!(table.get(3, 2), Some("Beenden"));
+/// ```
+pub fn csf2table(csfs: &[CsfStringtable]) -> Result<TableFile> {
+    let mut table = TableFile::default();
+    let mut languages = HashSet::new();
+    let mut header = vec![Some(LABEL_COLUMN.to_string())];
+    for csf in csfs {
+        let tag = csf.language.tag();
+        if !languages.insert(tag.clone()) {
+            return Err(Error::DuplicateLanguage(tag));
+        }
+        header.push(Some(tag));
+    }
+    table.rows.push(header);
+    let mut version = vec![Some(VERSION_ROW.to_string())];
+    version.extend(csfs.iter().map(|x| Some(u32::from(x.version).to_string())));
+    table.rows.push(version);
+    let mut extra = vec![Some(EXTRA_ROW.to_string())];
+    extra.extend(csfs.iter().map(|x| Some(x.extra.to_string())));
+    table.rows.push(extra);
+
+    let names: IndexSet<&str> = csfs
+        .iter()
+        .flat_map(|x| x.iter())
+        .map(|x| x.name.as_str())
+        .collect();
+    for name in names {
+        let labels: Vec<_> = csfs.iter().map(|x| x.get(name)).collect();
+        let count = labels.iter().flatten().map(|x| x.strings.len()).max();
+        let name = escape_name(name);
+        if count.unwrap_or_default() == 0 {
+            let mut row = vec![Some(format!("{name}|strings"))];
+            row.extend(csfs.iter().map(|_| Some("0".to_string())));
+            table.rows.push(row);
+            continue;
+        }
+        for i in 0..count.unwrap_or_default() {
+            let mut row = vec![Some(string_name(&name, i))];
+            row.extend(
+                labels
+                    .iter()
+                    .map(|x| x.and_then(|x| x.strings.get(i)).map(|x| x.value.clone())),
+            );
+            table.rows.push(row);
+        }
+    }
+    Ok(table)
+}
+
+/// Make one stringtable per language column of a table. Columns are named with any
+/// tag accepted by [`CsfLanguageEnum::from_tag`], such as `de` or `de-DE`.
+/// A row named `LABEL|N` is read as a string of the label in the row above it.
+/// Rows with an empty first cell are skipped. Empty cells get the string of the first
+/// language column, if it has one.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::table2csf,
+///     csf::CsfLanguageEnum,
+///     table::{io::TableReader, TableFormatEnum},
+/// };
+///
+/// let input = "Label,en-US,de\n:Extra,0,7\nGUI:Quit,Quit,Beenden\nGUI:Load,Load,\n";
+/// let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+/// let csfs = table2csf(&table).unwrap();
+///
+/// assert_eq!(csfs[1].language, CsfLanguageEnum::DE);
+/// assert_eq!(csfs[1].extra, 7);
+/// assert_eq!(csfs[1].get_str("GUI:Quit"), Some("Beenden"));
+/// assert_eq!(csfs[1].get_str("GUI:Load"), Some("Load"));
+/// ```
+pub fn table2csf(table: &TableFile) -> Result<Vec<CsfStringtable>> {
+    let mut rows = table.rows.iter().enumerate();
+    let (_, header) = rows.next().ok_or(Error::MissingHeader)?;
+    let mut languages = HashSet::new();
+    let mut csfs = vec![];
+    for tag in header.iter().skip(1) {
+        let tag = tag.as_deref().unwrap_or_default();
+        let language = CsfLanguageEnum::from_tag(tag).ok_or(Error::InvalidLanguage(tag.into()))?;
+        if !languages.insert(language) {
+            return Err(Error::DuplicateLanguage(tag.into()));
+        }
+        csfs.push(CsfStringtable::new(Default::default(), language, 0));
+    }
+
+    // Strings of the current label in every language.
+    let mut current: Option<(String, Vec<Vec<Option<&str>>>)> = None;
+    for (n, row) in rows {
+        if row.len() > csfs.len() + 1 {
+            return Err(Error::TooManyCells(n + 1));
+        }
+        let Some(name) = row.first().and_then(|x| x.as_deref()) else {
+            continue;
+        };
+        let cells = (0..csfs.len()).map(|i| row.get(i + 1).and_then(|x| x.as_deref()));
+        if name == VERSION_ROW || name == EXTRA_ROW {
+            for (csf, cell) in csfs.iter_mut().zip(cells) {
+                let Some(cell) = cell else {
+                    continue;
+                };
+                let value: u32 = cell
+                    .parse()
+                    .map_err(|_| Error::InvalidHeaderValue(name.into(), cell.into()))?;
+                match name {
+                    VERSION_ROW => csf.version = value.into(),
+                    _ => csf.extra = value,
+                }
+            }
+            continue;
+        }
+        let name = unescape_name(name);
+        if let Some(label) = name.strip_suffix("|strings") {
+            if cells.clone().all(|x| matches!(x, None | Some("0"))) {
+                if let Some((label, strings)) = current.take() {
+                    insert_label(&mut csfs, label, strings);
+                }
+                for csf in csfs.iter_mut() {
+                    csf.insert(CsfLabel {
+                        name: label.to_string(),
+                        strings: vec![],
+                    });
+                }
+                continue;
+            }
+        }
+        let index = match (&current, parse_string_name(name)) {
+            (Some((label, _)), (base, i)) if i > 0 && base == label => Some(i),
+            _ => None,
+        };
+        let index = match index {
+            Some(i) => i,
+            None => {
+                if let Some((label, strings)) = current.take() {
+                    insert_label(&mut csfs, label, strings);
+                }
+                current = Some((name.to_string(), vec![vec![]; csfs.len()]));
+                0
+            }
+        };
+        if let Some((_, strings)) = &mut current {
+            for (strings, cell) in strings.iter_mut().zip(cells) {
+                if strings.len() <= index {
+                    strings.resize(index + 1, None);
+                }
+                strings[index] = cell;
+            }
+        }
+    }
+    if let Some((label, strings)) = current {
+        insert_label(&mut csfs, label, strings);
+    }
+    Ok(csfs)
+}
+
+/// Put another `:` before label names starting with one, as header rows do.
+fn escape_name(name: &str) -> String {
+    if name.starts_with(':') {
+        format!(":{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Reverse [`escape_name`].
+fn unescape_name(name: &str) -> &str {
+    name.strip_prefix(':')
+        .filter(|x| x.starts_with(':'))
+        .unwrap_or(name)
+}
+
+/// Insert a label into every stringtable. Empty strings of the base stringtable stay
+/// empty, empty strings of other stringtables get the string of the base stringtable.
+fn insert_label(csfs: &mut [CsfStringtable], name: String, strings: Vec<Vec<Option<&str>>>) {
+    let Some(base) = strings.first().cloned() else {
+        return;
+    };
+    for (csf, strings) in csfs.iter_mut().zip(strings) {
+        csf.insert(CsfLabel {
+            name: name.clone(),
+            strings: strings
+                .iter()
+                .zip(&base)
+                .map(|(x, base)| {
+                    let value = x.filter(|x| !x.is_empty()).or(*base);
+                    CsfString::new(value.unwrap_or_default())
+                })
+                .collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn _csf2table() {
+        use rust_alert::{
+            converters::csf2table,
+            csf::{CsfLanguageEnum, CsfStringtable},
+        };
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        let mut translation = CsfStringtable::default();
+        translation.language = CsfLanguageEnum::DE;
+        translation.create("GUI:Quit", "Beenden");
+        let table = csf2table(&[base, translation]).unwrap();
+
+        assert_eq!(table.get(0, 2), Some("de"));
+        assert_eq!(table.get(3, 0), Some("GUI:Quit"));
+        assert_eq!(table.get(3, 2), Some("Beenden"));
+    }
+
+    #[test]
+    fn _table2csf() {
+        use rust_alert::{
+            converters::table2csf,
+            csf::CsfLanguageEnum,
+            table::{io::TableReader, TableFormatEnum},
+        };
+
+        let input = "Label,en-US,de\n:Extra,0,7\nGUI:Quit,Quit,Beenden\nGUI:Load,Load,\n";
+        let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+        let csfs = table2csf(&table).unwrap();
+
+        assert_eq!(csfs[1].language, CsfLanguageEnum::DE);
+        assert_eq!(csfs[1].extra, 7);
+        assert_eq!(csfs[1].get_str("GUI:Quit"), Some("Beenden"));
+        assert_eq!(csfs[1].get_str("GUI:Load"), Some("Load"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        converters::csf2table::{csf2table, table2csf, Error, VERSION_ROW},
+        csf::{CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable, CsfVersionEnum},
+        table::{
+            io::{TableReader, TableWriter},
+            TableFile, TableFormatEnum,
+        },
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut base = CsfStringtable::new(CsfVersionEnum::Nox, CsfLanguageEnum::ENUS, 1);
+        base.create("Empty", "");
+        base.insert(CsfLabel {
+            name: "Multi".to_string(),
+            strings: vec![
+                CsfString::new("Say \"Hi\",\r\nthen go"),
+                CsfString::new(""),
+                CsfString::new("Third\t"),
+            ],
+        });
+        base.create("A|1", "Not a string of A");
+        base.insert(CsfLabel {
+            name: "No strings".to_string(),
+            strings: vec![],
+        });
+        base.create(VERSION_ROW, "Not a header");
+        base.create("::Extra", "Not a header either");
+        let mut translation = CsfStringtable::new(
+            CsfVersionEnum::Unknown(9),
+            CsfLanguageEnum::Unknown(100),
+            0xFFFFFFFF,
+        );
+        translation.create("Multi", "Sag \"Hallo\"");
+        translation.create("Only", "Nur hier");
+        let csfs = vec![base, translation];
+
+        // Every stringtable gets all labels, with base strings where it had none.
+        let mut expected = csfs.clone();
+        expected[0].create("Only", "");
+        expected[1] = expected[0].clone();
+        expected[1].version = csfs[1].version;
+        expected[1].language = csfs[1].language;
+        expected[1].extra = csfs[1].extra;
+        let multi = expected[1].get("Multi").unwrap().clone();
+        expected[1].insert(CsfLabel {
+            name: multi.name,
+            strings: [
+                vec![CsfString::new("Sag \"Hallo\"")],
+                multi.strings[1..].to_vec(),
+            ]
+            .concat(),
+        });
+        expected[1].create("Only", "Nur hier");
+
+        let table = csf2table(&csfs).unwrap();
+        let names: Vec<_> = table.rows.iter().skip(7).map(|x| x[0].as_deref()).collect();
+        assert_eq!(
+            names,
+            vec![
+                Some("A|1"),
+                Some("No strings|strings"),
+                Some("::Version"),
+                Some(":::Extra"),
+                Some("Only")
+            ]
+        );
+        assert_eq!(table.get(8, 2), Some("0"));
+
+        for format in [TableFormatEnum::Csv, TableFormatEnum::Tsv] {
+            let table = csf2table(&csfs).unwrap();
+            let mut buf = vec![];
+            TableWriter::write_file(&table, &mut buf, format).unwrap();
+            let table = TableReader::read_file(buf.as_slice(), format).unwrap();
+            let actual = table2csf(&table).unwrap();
+            assert_eq!(actual, expected);
+            assert_eq!(contents(&actual), contents(&expected));
+        }
+    }
+
+    #[test]
+    fn resaved() {
+        let mut base = CsfStringtable::default();
+        base.create("A", "1");
+        base.create("Empty", "");
+        let mut translation = CsfStringtable::default();
+        translation.language = CsfLanguageEnum::DE;
+        translation.create("Empty", "");
+        translation.create("Only", "Nur hier");
+        let csfs = vec![base, translation];
+
+        let table = csf2table(&csfs).unwrap();
+        let mut buf = vec![];
+        TableWriter::write_file(&table, &mut buf, TableFormatEnum::Csv).unwrap();
+        let quoted = String::from_utf8(buf).unwrap();
+        // Spreadsheet programs write empty cells without quotes.
+        let resaved = quoted.replace("\"\"", "");
+        assert_ne!(quoted, resaved);
+
+        for input in [quoted, resaved] {
+            let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+            let actual = table2csf(&table).unwrap();
+            assert_eq!(
+                contents(&actual),
+                vec![
+                    vec![("A", vec!["1"]), ("Empty", vec![""]), ("Only", vec![""])],
+                    vec![
+                        ("A", vec!["1"]),
+                        ("Empty", vec![""]),
+                        ("Only", vec!["Nur hier"])
+                    ],
+                ]
+            );
+        }
+    }
+
+    fn contents(csfs: &[CsfStringtable]) -> Vec<Vec<(&str, Vec<&str>)>> {
+        csfs.iter()
+            .map(|csf| {
+                csf.iter()
+                    .map(|x| {
+                        let strings = x.strings.iter().map(|x| x.value.as_str()).collect();
+                        (x.name.as_str(), strings)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gaps() {
+        let input = "Label,de\nA,\nA|2,Zwei\nB|1,Eins\n\n,Skipped\n";
+        let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+        let csfs = table2csf(&table).unwrap();
+        assert_eq!(csfs.len(), 1);
+        let a = csfs[0].get("A").unwrap();
+        assert_eq!(
+            a.strings,
+            vec![
+                CsfString::new(""),
+                CsfString::new(""),
+                CsfString::new("Zwei")
+            ]
+        );
+        assert_eq!(csfs[0].get_str("B|1"), Some("Eins"));
+        assert_eq!(csfs[0].len(), 2);
+    }
+
+    #[test]
+    fn languages() {
+        let input = "Label,en-GB,de-DE,fr_FR.UTF-8\nA,1,Eins,Un\n";
+        let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+        let languages: Vec<_> = table2csf(&table)
+            .unwrap()
+            .iter()
+            .map(|x| x.language)
+            .collect();
+        assert_eq!(
+            languages,
+            vec![
+                CsfLanguageEnum::ENUK,
+                CsfLanguageEnum::DE,
+                CsfLanguageEnum::FR
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut csf = CsfStringtable::default();
+        csf.create("A", "1");
+        let result = csf2table(&[csf.clone(), csf]);
+        assert!(matches!(result, Err(Error::DuplicateLanguage(x)) if x == "en-US"));
+
+        let cases = [
+            ("", "MissingHeader"),
+            ("Label,en-US,en_US\n", "DuplicateLanguage"),
+            ("Label,de,de-AT\n", "DuplicateLanguage"),
+            ("Label,en-AU\n", "InvalidLanguage"),
+            ("Label,English\n", "InvalidLanguage"),
+            ("Label,\n", "InvalidLanguage"),
+            ("Label,de\n:Version,three\n", "InvalidHeaderValue"),
+            ("Label,de\nA,1,2\n", "TooManyCells"),
+        ];
+        for (input, expected) in cases {
+            let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+            let result = match table2csf(&table) {
+                Err(Error::MissingHeader) => "MissingHeader",
+                Err(Error::DuplicateLanguage(_)) => "DuplicateLanguage",
+                Err(Error::InvalidLanguage(_)) => "InvalidLanguage",
+                Err(Error::InvalidHeaderValue(..)) => "InvalidHeaderValue",
+                Err(Error::TooManyCells(_)) => "TooManyCells",
+                Ok(_) => "Ok",
+            };
+            assert_eq!(result, expected, "{input:?}");
+        }
+        assert!(table2csf(&TableFile::default()).is_err());
+    }
+}
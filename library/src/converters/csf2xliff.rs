@@ -0,0 +1,352 @@
+//! Conversion between CSF stringtables and XLIFF files.
+//!
+//! Every string of a label becomes a unit named after the label, with strings after
+//! the first named `LABEL|1`, `LABEL|2` and so on. Languages are written as BCP 47 tags,
+//! and languages without one as private use tags, such as `x-csf-7`.
+
+use crate::{
+    csf::{parse_string_name, string_name, CsfLabel, CsfLanguageEnum, CsfStringtable},
+    xliff::{XliffFile, XliffUnit, XliffVersionEnum},
+};
+
+/// The error type for CSF-XLIFF conversions.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The target language of the XLIFF file is not a CSF language.
+    #[error("Target language {0:?} is not a CSF language")]
+    InvalidLanguage(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Summary of an XLIFF file import.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct XliffImportReport {
+    /// Units whose source text is different from the base stringtable,
+    /// so their translation may be outdated. Their targets are still imported.
+    pub changed: Vec<String>,
+    /// Units with no translated target, which were skipped.
+    pub untranslated: Vec<String>,
+    /// Units with no matching string in the base stringtable, which were skipped.
+    pub unknown: Vec<String>,
+}
+
+/// Make an XLIFF file from a base stringtable and, optionally, its translation.
+/// Units with a translated string have the `translated` state.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::csf2xliff,
+///     csf::{CsfLanguageEnum, CsfStringtable},
+///     xliff::XliffVersionEnum,
+/// };
+///
+/// let mut base = CsfStringtable::default();
+/// base.create("GUI:Quit", "Quit");
+/// let mut translation = CsfStringtable::default();
+/// translation.language = CsfLanguageEnum::DE;
+/// translation.create("GUI:Quit", "Beenden");
+/// let xliff = csf2xliff(&base, Some(&translation), XliffVersionEnum::V12);
+///
+/// assert_eq!(xliff.source_language, "en-US");
+/// assert_eq!(xliff.target_language.as_deref(), Some("de"));
+/// let unit = xliff.get("GUI:Quit").unwrap();
+/// assert_eq!(unit.target.as_deref(), Some("Beenden"));
+/// assert_eq!(unit.state.as_deref(), Some("translated"));
+/// ```
+pub fn csf2xliff(
+    base: &CsfStringtable,
+    translation: Option<&CsfStringtable>,
+    version: XliffVersionEnum,
+) -> XliffFile {
+    let mut xliff = XliffFile {
+        version,
+        source_language: base.language.tag(),
+        target_language: translation.map(|x| x.language.tag()),
+        ..Default::default()
+    };
+    for label in base.iter() {
+        let translated = translation.and_then(|x| x.get(&label.name));
+        for (i, string) in label.strings.iter().enumerate() {
+            let name = string_name(&label.name, i);
+            let mut unit = XliffUnit::new((xliff.units.len() + 1).to_string(), name, &string.value);
+            unit.target = translated
+                .and_then(|x| x.strings.get(i))
+                .map(|x| x.value.clone());
+            if unit.target.is_some() {
+                unit.state = Some("translated".to_string());
+            }
+            xliff.units.push(unit);
+        }
+    }
+    xliff
+}
+
+/// Merge translated targets of an XLIFF file into a stringtable. Units are matched
+/// with strings of the base stringtable by their names.
+///
+/// If `target` is `None`, targets are merged into a copy of the base stringtable,
+/// with the target language of the XLIFF file, failing if that language is not known.
+/// Otherwise, base labels missing from `target` are copied into it, even if no unit
+/// translates them, so the result always has every base label.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     converters::xliff2csf,
+///     csf::{CsfLanguageEnum, CsfStringtable},
+///     xliff::{XliffFile, XliffUnit},
+/// };
+///
+/// let mut base = CsfStringtable::default();
+/// base.create("GUI:Quit", "Quit");
+/// base.create("GUI:Load", "Load game");
+/// let mut xliff = XliffFile {
+///     target_language: Some("de".to_string()),
+///     ..Default::default()
+/// };
+/// let mut unit = XliffUnit::new("1", "GUI:Quit", "Quit");
+/// unit.target = Some("Beenden".to_string());
+/// xliff.units.push(unit);
+/// let mut unit = XliffUnit::new("2", "GUI:Load", "Load");
+/// unit.target = Some("Laden".to_string());
+/// xliff.units.push(unit);
+/// let (csf, report) = xliff2csf(&xliff, &base, None).unwrap();
+///
+/// assert_eq!(csf.language, CsfLanguageEnum::DE);
+/// assert_eq!(csf.get_str("GUI:Quit"), Some("Beenden"));
+/// assert_eq!(csf.get_str("GUI:Load"), Some("Laden"));
+/// assert_eq!(report.changed, vec!["GUI:Load"]);
+/// ```
+pub fn xliff2csf(
+    xliff: &XliffFile,
+    base: &CsfStringtable,
+    target: Option<CsfStringtable>,
+) -> Result<(CsfStringtable, XliffImportReport)> {
+    let mut report = XliffImportReport::default();
+    let mut csf = match target {
+        Some(target) => target,
+        None => {
+            let mut csf = base.clone();
+            if let Some(tag) = &xliff.target_language {
+                csf.language = CsfLanguageEnum::from_tag(tag)
+                    .ok_or_else(|| Error::InvalidLanguage(tag.clone()))?;
+            }
+            csf
+        }
+    };
+    for unit in &xliff.units {
+        let Some((base_label, index)) = find_string(base, &unit.name) else {
+            report.unknown.push(unit.name.clone());
+            continue;
+        };
+        if base_label.strings[index].value != unit.source {
+            report.changed.push(unit.name.clone());
+        }
+        let Some(value) = unit.target.as_ref().filter(|_| unit.is_translated()) else {
+            report.untranslated.push(unit.name.clone());
+            continue;
+        };
+        let mut label = csf
+            .get(&base_label.name)
+            .cloned()
+            .unwrap_or_else(|| base_label.clone());
+        while label.strings.len() <= index {
+            label
+                .strings
+                .push(base_label.strings[label.strings.len()].clone());
+        }
+        label.strings[index].value.clone_from(value);
+        csf.insert(label);
+    }
+    for label in base.iter() {
+        if csf.get(&label.name).is_none() {
+            csf.insert(label.clone());
+        }
+    }
+    Ok((csf, report))
+}
+
+/// Find the label and the string index a unit name refers to.
+fn find_string<'a>(base: &'a CsfStringtable, name: &str) -> Option<(&'a CsfLabel, usize)> {
+    if let Some(label) = base.get(name).filter(|x| !x.strings.is_empty()) {
+        return Some((label, 0));
+    }
+    let (name, index) = parse_string_name(name);
+    base.get(name)
+        .filter(|x| index > 0 && index < x.strings.len())
+        .map(|x| (x, index))
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn _csf2xliff() {
+        use rust_alert::{
+            converters::csf2xliff,
+            csf::{CsfLanguageEnum, CsfStringtable},
+            xliff::XliffVersionEnum,
+        };
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        let mut translation = CsfStringtable::default();
+        translation.language = CsfLanguageEnum::DE;
+        translation.create("GUI:Quit", "Beenden");
+        let xliff = csf2xliff(&base, Some(&translation), XliffVersionEnum::V12);
+
+        assert_eq!(xliff.source_language, "en-US");
+        assert_eq!(xliff.target_language.as_deref(), Some("de"));
+        let unit = xliff.get("GUI:Quit").unwrap();
+        assert_eq!(unit.target.as_deref(), Some("Beenden"));
+        assert_eq!(unit.state.as_deref(), Some("translated"));
+    }
+
+    #[test]
+    fn _xliff2csf() {
+        use rust_alert::{
+            converters::xliff2csf,
+            csf::{CsfLanguageEnum, CsfStringtable},
+            xliff::{XliffFile, XliffUnit},
+        };
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.create("GUI:Load", "Load game");
+        let mut xliff = XliffFile {
+            target_language: Some("de".to_string()),
+            ..Default::default()
+        };
+        let mut unit = XliffUnit::new("1", "GUI:Quit", "Quit");
+        unit.target = Some("Beenden".to_string());
+        xliff.units.push(unit);
+        let mut unit = XliffUnit::new("2", "GUI:Load", "Load");
+        unit.target = Some("Laden".to_string());
+        xliff.units.push(unit);
+        let (csf, report) = xliff2csf(&xliff, &base, None).unwrap();
+
+        assert_eq!(csf.language, CsfLanguageEnum::DE);
+        assert_eq!(csf.get_str("GUI:Quit"), Some("Beenden"));
+        assert_eq!(csf.get_str("GUI:Load"), Some("Laden"));
+        assert_eq!(report.changed, vec!["GUI:Load"]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        converters::csf2xliff::{csf2xliff, xliff2csf, Error},
+        csf::{CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable},
+        xliff::{
+            io::{XliffReader, XliffWriter},
+            XliffVersionEnum,
+        },
+    };
+
+    fn base() -> CsfStringtable {
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.insert(CsfLabel {
+            name: "GUI:Multi".to_string(),
+            strings: vec![
+                CsfString {
+                    value: "First\r\n".to_string(),
+                    extra_value: b"extra".to_vec(),
+                },
+                CsfString::new("Second"),
+            ],
+        });
+        base.create("GUI:Other", "Other");
+        base
+    }
+
+    #[test]
+    fn round_trip() {
+        let base = base();
+        let mut translation = CsfStringtable::default();
+        translation.language = CsfLanguageEnum::Unknown(42);
+        translation.insert(CsfLabel {
+            name: "GUI:Multi".to_string(),
+            strings: vec![CsfString::new("Erste\r\n"), CsfString::new("Zweite")],
+        });
+
+        for version in [XliffVersionEnum::V12, XliffVersionEnum::V20] {
+            let mut xliff = csf2xliff(&base, Some(&translation), version);
+            assert_eq!(xliff.target_language.as_deref(), Some("x-csf-42"));
+            xliff.units[0].target = Some("Beenden".to_string());
+            xliff.units[0].notes.push("Short".to_string());
+            xliff.units[3].source = "Changed".to_string();
+            let mut buf = vec![];
+            XliffWriter::write_file(&xliff, &mut buf).unwrap();
+            let xliff = XliffReader::read_file(buf.as_slice()).unwrap();
+            assert_eq!(xliff.units[0].notes, vec!["Short"]);
+
+            let (csf, report) = xliff2csf(&xliff, &base, None).unwrap();
+            assert_eq!(csf.language, CsfLanguageEnum::Unknown(42));
+            assert_eq!(csf.get_str("GUI:Quit"), Some("Beenden"));
+            let multi = csf.get("GUI:Multi").unwrap();
+            assert_eq!(multi.strings[0].value, "Erste\r\n");
+            assert_eq!(multi.strings[0].extra_value, b"extra");
+            assert_eq!(multi.strings[1].value, "Zweite");
+            assert_eq!(csf.get_str("GUI:Other"), Some("Other"));
+            assert_eq!(report.changed, vec!["GUI:Other"]);
+            assert_eq!(report.untranslated, vec!["GUI:Other"]);
+            assert!(report.unknown.is_empty());
+        }
+    }
+
+    #[test]
+    fn merge_into_target() {
+        let base = base();
+        let mut xliff = csf2xliff(&base, None, XliffVersionEnum::V20);
+        assert!(xliff.target_language.is_none());
+        xliff.units[2].target = Some("Zweite".to_string());
+        xliff.units.push(xliff.units[0].clone());
+        xliff.units[4].name = "GUI:Multi|2".to_string();
+
+        let mut target = CsfStringtable::default();
+        target.language = CsfLanguageEnum::DE;
+        target.create("GUI:Multi", "Erste");
+        target.create("Mine", "Only in target");
+        let (csf, report) = xliff2csf(&xliff, &base, Some(target)).unwrap();
+
+        assert_eq!(csf.language, CsfLanguageEnum::DE);
+        let names: Vec<_> = csf.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["GUI:Multi", "Mine", "GUI:Quit", "GUI:Other"]);
+        assert_eq!(csf.get_str("GUI:Other"), Some("Other"));
+        let multi = csf.get("GUI:Multi").unwrap();
+        assert_eq!(multi.strings[0].value, "Erste");
+        assert_eq!(multi.strings[1].value, "Zweite");
+        assert_eq!(report.unknown, vec!["GUI:Multi|2"]);
+        assert_eq!(report.untranslated.len(), 3);
+    }
+
+    #[test]
+    fn languages() {
+        let base = base();
+        let mut xliff = csf2xliff(&base, None, XliffVersionEnum::V12);
+        for (tag, language) in [
+            ("fr-FR", CsfLanguageEnum::FR),
+            ("en-GB", CsfLanguageEnum::ENUK),
+        ] {
+            xliff.target_language = Some(tag.to_string());
+            let (csf, _) = xliff2csf(&xliff, &base, None).unwrap();
+            assert_eq!(csf.language, language);
+        }
+
+        xliff.target_language = Some("pl-PL".to_string());
+        assert!(matches!(
+            xliff2csf(&xliff, &base, None),
+            Err(Error::InvalidLanguage(x)) if x == "pl-PL"
+        ));
+        let mut target = CsfStringtable::default();
+        target.language = CsfLanguageEnum::Unknown(42);
+        let (csf, _) = xliff2csf(&xliff, &base, Some(target)).unwrap();
+        assert_eq!(csf.language, CsfLanguageEnum::Unknown(42));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
+    core::GameEnum,
     ini::{IniFile, IniSection},
-    mix::db::MixDatabase,
+    mix::db::{GlobalMixDatabase, MixDatabase, MixDatabaseEntry, NameSourceEnum},
     utils::hex2int,
 };
 
@@ -15,29 +16,91 @@ pub enum Error {
 type Result<T> = std::result::Result<T, Error>;
 
 /// Convert a Mix DB file to an INI file, writing filenames and hashes as entries.
+/// Descriptions are written after the filename, separated with a comma.
+/// The primary name of every ID is written under the ID, and other candidates under
+/// `ID|1`, `ID|2` and so on, in order.
+/// The section is named after the game of the database (like `[TD]`), or `[MixDatabase]`
+/// if it has none.
 /// 
 /// # Examples
 /// 
 /// ```ignore
-/// use rust_alert::{mix::db::MixDatabase, converters::db2ini};
+/// use rust_alert::{mix::db::{MixDatabase, MixDatabaseEntry}, converters::db2ini};
 /// 
 /// let mut db = MixDatabase::default();
-/// db.names.insert(1, "a".to_string());
+/// db.insert(1, "a");
+/// db.insert_entry(2, MixDatabaseEntry::with_description("b", "Desc"));
+/// db.add(2, "c");
 /// let ini = db2ini(db);
 ///
 /// assert_eq!(ini.get_str("MixDatabase", "00000001"), Some("a"));
+/// assert_eq!(ini.get_str("MixDatabase", "00000002"), Some("b,Desc"));
+/// assert_eq!(ini.get_str("MixDatabase", "00000002|1"), Some("c"));
 /// ```
-pub fn db2ini(mut db: MixDatabase) -> IniFile {
+pub fn db2ini(db: MixDatabase) -> IniFile {
     let mut ini = IniFile::default();
-    let mut section = IniSection::new("MixDatabase");
-    for (id, name) in db.names.drain() {
-        section.create_entry(format!("{:0>8X}", id), name);
+    let name = section_name(db.game, "MixDatabase");
+    ini.add_section(db2section(db, name));
+    ini
+}
+
+/// Convert a global Mix DB file to an INI file, writing every database to its own section.
+/// Sections are named after games of the databases, or `MixDatabase0`, `MixDatabase1` and so on
+/// for untagged databases and repeated games. Entries are sorted by ID.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{mix::db::{GlobalMixDatabase, MixDatabase}, converters::gmd2ini};
+///
+/// let mut db = MixDatabase::default();
+/// db.insert(1, "a");
+/// let gmd = GlobalMixDatabase { dbs: vec![MixDatabase::default(), db] };
+/// let ini = gmd2ini(gmd);
+///
+/// assert_eq!(ini.len(), 2);
+/// assert_eq!(ini.get_str("MixDatabase1", "00000001"), Some("a"));
+/// ```
+pub fn gmd2ini(gmd: GlobalMixDatabase) -> IniFile {
+    let mut ini = IniFile::default();
+    for (i, db) in gmd.dbs.into_iter().enumerate() {
+        let mut name = section_name(db.game, format!("MixDatabase{i}"));
+        if ini.get_section(&name).is_some() {
+            name = format!("MixDatabase{i}");
+        }
+        let mut section = db2section(db, name);
+        section.sort();
+        ini.add_section(section);
     }
-    ini.add_section(section);
     ini
 }
 
-/// Convert an INI file to a Mix DB file.
+fn section_name(game: Option<GameEnum>, default: impl Into<String>) -> String {
+    game.map_or_else(|| default.into(), |x| format!("{:?}", x))
+}
+
+fn db2section(mut db: MixDatabase, name: impl Into<String>) -> IniSection {
+    let mut section = IniSection::new(name);
+    for (id, entries) in db.entries.drain() {
+        for (i, entry) in entries.into_iter().enumerate() {
+            let key = match i {
+                0 => format!("{:0>8X}", id),
+                i => format!("{:0>8X}|{i}", id),
+            };
+            let value = match entry.description {
+                Some(description) => format!("{},{}", entry.name, description),
+                None => entry.name,
+            };
+            section.create_entry(key, value);
+        }
+    }
+    section
+}
+
+/// Convert an INI file to a Mix DB file. Anything after the first comma in a value
+/// is read as the description. Keys like `ID|N` are further candidates for the ID,
+/// added in order of `N` after the one under the plain ID. If all sections are named
+/// after the same game (like `[TD]`), the database is tagged with that game.
 /// 
 /// # Examples
 /// 
@@ -46,36 +109,99 @@ pub fn db2ini(mut db: MixDatabase) -> IniFile {
 /// 
 /// let mut ini = IniFile::default();
 /// ini.add_to_section("MixDatabase", "00000001", "a");
+/// ini.add_to_section("MixDatabase", "00000002", "b,Desc");
 /// let db = ini2db(ini);
 ///
 /// assert!(db.is_ok());
 /// let db = db.unwrap();
-/// assert_eq!(db.names.get(&1), Some(&"a".to_string()));
+/// assert_eq!(db.get_name(1), Some(&"a".to_string()));
+/// assert_eq!(db.get_description(2), Some(&"Desc".to_string()));
 /// ```
 pub fn ini2db(mut ini: IniFile) -> Result<MixDatabase> {
     let mut db = MixDatabase::default();
-    for (_, mut section) in ini.drain() {
-        for (key, entry) in section.drain() {
-            let id = hex2int(key.as_str())?;
-            db.names.insert(id, entry.value);
-        }
+    let mut games = vec![];
+    for (name, section) in ini.drain() {
+        games.push(name.parse::<GameEnum>().ok());
+        section2db(section, &mut db)?;
+    }
+    if games.windows(2).all(|x| x[0] == x[1]) {
+        db.game = games.first().copied().flatten();
     }
     Ok(db)
 }
 
+/// Convert an INI file to a global Mix DB file, making a database from every section.
+/// Databases from sections named after a game (like `[TD]`) are tagged with that game.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{core::GameEnum, ini::IniFile, converters::ini2gmd};
+///
+/// let mut ini = IniFile::default();
+/// ini.add_to_section("A", "00000001", "a");
+/// ini.add_to_section("TS", "00000002", "b,Desc");
+/// let gmd = ini2gmd(ini);
+///
+/// assert!(gmd.is_ok());
+/// let gmd = gmd.unwrap();
+/// assert_eq!(gmd.dbs.len(), 2);
+/// assert_eq!(gmd.dbs[1].game, Some(GameEnum::TS));
+/// assert_eq!(gmd.dbs[1].get_description(2), Some(&"Desc".to_string()));
+/// ```
+pub fn ini2gmd(mut ini: IniFile) -> Result<GlobalMixDatabase> {
+    let mut gmd = GlobalMixDatabase::default();
+    for (name, section) in ini.drain() {
+        let mut db = MixDatabase {
+            game: name.parse().ok(),
+            ..Default::default()
+        };
+        section2db(section, &mut db)?;
+        gmd.dbs.push(db);
+    }
+    Ok(gmd)
+}
+
+fn section2db(mut section: IniSection, db: &mut MixDatabase) -> Result<()> {
+    let mut entries = vec![];
+    for (key, entry) in section.drain() {
+        let (id, index) = match key.split_once('|') {
+            Some((id, index)) => (id, index.parse().ok()),
+            None => (key.as_str(), Some(0)),
+        };
+        let id = hex2int(id)?;
+        let entry = match entry.value.split_once(',') {
+            Some((name, description)) => MixDatabaseEntry::with_description(name, description),
+            None => MixDatabaseEntry::new(entry.value),
+        };
+        // Keys with a suffix that isn't a number go last.
+        entries.push((id, index.unwrap_or(usize::MAX), entry));
+    }
+    // Sorting is stable, so entries with the same key stay in file order.
+    entries.sort_by_key(|x| (x.0, x.1));
+    for (id, _, entry) in entries {
+        db.add_entry(id, entry.with_source(NameSourceEnum::Ini));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod examples {
     use crate as rust_alert;
 
     #[test]
     fn _db2ini() {
-        use rust_alert::{mix::db::MixDatabase, converters::db2ini};
+        use rust_alert::{mix::db::{MixDatabase, MixDatabaseEntry}, converters::db2ini};
 
         let mut db = MixDatabase::default();
-        db.names.insert(1, "a".to_string());
+        db.insert(1, "a");
+        db.insert_entry(2, MixDatabaseEntry::with_description("b", "Desc"));
+        db.add(2, "c");
         let ini = db2ini(db);
 
         assert_eq!(ini.get_str("MixDatabase", "00000001"), Some("a"));
+        assert_eq!(ini.get_str("MixDatabase", "00000002"), Some("b,Desc"));
+        assert_eq!(ini.get_str("MixDatabase", "00000002|1"), Some("c"));
     }
 
     #[test]
@@ -84,10 +210,127 @@ mod examples {
 
         let mut ini = IniFile::default();
         ini.add_to_section("MixDatabase", "00000001", "a");
+        ini.add_to_section("MixDatabase", "00000002", "b,Desc");
         let db = ini2db(ini);
 
         assert!(db.is_ok());
         let db = db.unwrap();
-        assert_eq!(db.names.get(&1), Some(&"a".to_string()));
+        assert_eq!(db.get_name(1), Some(&"a".to_string()));
+        assert_eq!(db.get_description(2), Some(&"Desc".to_string()));
+    }
+
+    #[test]
+    fn _gmd2ini() {
+        use rust_alert::{mix::db::{GlobalMixDatabase, MixDatabase}, converters::gmd2ini};
+
+        let mut db = MixDatabase::default();
+        db.insert(1, "a");
+        let gmd = GlobalMixDatabase { dbs: vec![MixDatabase::default(), db] };
+        let ini = gmd2ini(gmd);
+
+        assert_eq!(ini.len(), 2);
+        assert_eq!(ini.get_str("MixDatabase1", "00000001"), Some("a"));
+    }
+
+    #[test]
+    fn _ini2gmd() {
+        use rust_alert::{core::GameEnum, ini::IniFile, converters::ini2gmd};
+
+        let mut ini = IniFile::default();
+        ini.add_to_section("A", "00000001", "a");
+        ini.add_to_section("TS", "00000002", "b,Desc");
+        let gmd = ini2gmd(ini);
+
+        assert!(gmd.is_ok());
+        let gmd = gmd.unwrap();
+        assert_eq!(gmd.dbs.len(), 2);
+        assert_eq!(gmd.dbs[1].game, Some(GameEnum::TS));
+        assert_eq!(gmd.dbs[1].get_description(2), Some(&"Desc".to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        converters::{db2ini, gmd2ini, ini2db, ini2gmd},
+        core::{crc, GameEnum},
+        ini::{
+            io::{IniReader, IniWriter},
+            IniFile,
+        },
+        mix::db::{GlobalMixDatabase, MixDatabase, MixDatabaseEntry},
+    };
+
+    #[test]
+    fn game_sections() {
+        let mut td = MixDatabase::with_game(GameEnum::TD);
+        td.insert(1, "a");
+        let gmd = GlobalMixDatabase {
+            dbs: vec![td.clone(), MixDatabase::default(), td.clone()],
+        };
+        let ini = gmd2ini(gmd);
+        let names: Vec<_> = ini.iter().map(|(x, _)| x.as_str()).collect();
+        assert_eq!(names, vec!["TD", "MixDatabase1", "MixDatabase2"]);
+
+        let ini = db2ini(td);
+        assert_eq!(ini.get_str("TD", "00000001"), Some("a"));
+        assert_eq!(ini2db(ini).unwrap().game, Some(GameEnum::TD));
+    }
+
+    #[test]
+    fn collisions_round_trip() {
+        let mut db = MixDatabase::with_game(GameEnum::TS);
+        db.insert(1, "a.shp");
+        db.add(2, "b.shp");
+        db.add_entry(2, MixDatabaseEntry::with_description("c.shp", "C, with comma"));
+        for i in 0..10 {
+            db.add(2, format!("d{i}.shp"));
+        }
+
+        let mut ini = db2ini(db.clone());
+        // Lexical order puts `|10` before `|2`, which must not change the order.
+        ini.sort_nested();
+        let mut buf = vec![];
+        IniWriter::write_file(&ini, &mut buf).unwrap();
+        let out = ini2db(IniReader::read_file(buf.as_slice()).unwrap()).unwrap();
+
+        assert_eq!(out.game, Some(GameEnum::TS));
+        assert_eq!(out.len(), 2);
+        assert!(out.has_collision(2));
+        let names = |db: &MixDatabase| -> Vec<_> {
+            db.get_all(2).iter().map(|x| x.name.clone()).collect()
+        };
+        assert_eq!(names(&out), names(&db));
+        assert_eq!(out.get_all(2)[1].description.as_deref(), Some("C, with comma"));
+        let gmd = ini2gmd(ini).unwrap();
+        assert_eq!(names(&gmd.dbs[0]), names(&db));
+    }
+
+    #[test]
+    fn mixed_sections() {
+        let mut ini = IniFile::default();
+        ini.add_to_section("TD", "00000001", "a");
+        ini.add_to_section("RA", "00000002", "b");
+        let db = ini2db(ini).unwrap();
+        assert_eq!(db.game, None);
+        assert_eq!(db.len(), 2);
+    }
+
+    #[test]
+    fn bundled_databases() {
+        for (text, game) in [
+            (include_str!("../../../resources/gmd.td.ini"), GameEnum::TD),
+            (include_str!("../../../resources/gmd.ra.ini"), GameEnum::RA),
+            (include_str!("../../../resources/gmd.ts.ini"), GameEnum::TS),
+            (include_str!("../../../resources/gmd.yr.ini"), GameEnum::YR),
+        ] {
+            let ini = IniReader::read_file(text.as_bytes()).unwrap();
+            let gmd = ini2gmd(ini).unwrap();
+            assert_eq!(gmd.dbs.len(), 1);
+            let db = &gmd.dbs[0];
+            assert_eq!(db.game, Some(game));
+            assert!(!db.is_empty());
+            assert!(db.iter().all(|(id, x)| crc(&x.name, game) == *id));
+        }
     }
 }
@@ -1,8 +1,25 @@
 mod csf2ini;
+mod csf2po;
+mod csf2table;
+#[cfg(feature = "xliff")]
+mod csf2xliff;
 mod db2ini;
 
 pub use csf2ini::Error as CSFConversionError;
-pub use csf2ini::{csf2ini, ini2csf};
+pub use csf2ini::{
+    csf2ini, escape_key, ini2csf, unescape_key, HEADER_SECTION, NO_CATEGORY_SECTION,
+};
+
+pub use csf2po::Error as POConversionError;
+pub use csf2po::{csf2po, csf2pot, po2csf, PoImportReport};
+
+pub use csf2table::Error as TableConversionError;
+pub use csf2table::{csf2table, table2csf, EXTRA_ROW, LABEL_COLUMN, VERSION_ROW};
+
+#[cfg(feature = "xliff")]
+pub use csf2xliff::Error as XliffConversionError;
+#[cfg(feature = "xliff")]
+pub use csf2xliff::{csf2xliff, xliff2csf, XliffImportReport};
 
 pub use db2ini::Error as DBConversionError;
-pub use db2ini::{db2ini, ini2db};
+pub use db2ini::{db2ini, gmd2ini, ini2db, ini2gmd};
@@ -1,10 +1,19 @@
 //! C&C CRC functions used for file indexing.
+//!
+//! The games uppercase names with C `toupper` in the "C" locale, which only changes ASCII
+//! letters, so all functions here do the same byte-wise and leave bytes above 0x7F as they are.
 
 use std::mem::size_of;
 
 use crc32fast;
 
-use crate::core::general::GameEnum;
+use crate::core::{CrcAlgorithmEnum, GameEnum};
+
+/// Size of the stack buffer used for uppercasing names.
+const CRC_BUFFER_SIZE: usize = 256;
+
+/// Below this many names, batch hashing doesn't spawn threads.
+const CRC_BATCH_THRESHOLD: usize = 4096;
 
 /// General CRC function that picks implementation depending on game version.
 /// 
@@ -17,14 +26,61 @@ use crate::core::general::GameEnum;
 /// assert_eq!(crc("bomb.shp", GameEnum::YR), 0x50F0D1EFu32 as i32);
 /// ```
 pub fn crc(value: impl AsRef<str>, game: GameEnum) -> i32 {
-    match game {
-        GameEnum::TD => crc_td(value),
-        GameEnum::RA => crc_td(value),
-        GameEnum::TS => crc_ts(value),
-        GameEnum::FS => crc_ts(value),
-        GameEnum::RA2 => crc_ts(value),
-        GameEnum::YR => crc_ts(value),
+    crc_bytes(value.as_ref().as_bytes(), game)
+}
+
+/// General CRC function for raw bytes that picks implementation depending on game version.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::core::{crc_bytes, GameEnum};
+///
+/// assert_eq!(crc_bytes(b"a10.shp", GameEnum::TD), 0x5CB0AAD5u32 as i32);
+/// assert_eq!(crc_bytes(b"bomb.shp", GameEnum::YR), 0x50F0D1EFu32 as i32);
+/// ```
+pub fn crc_bytes(bytes: &[u8], game: GameEnum) -> i32 {
+    match game.profile().crc {
+        CrcAlgorithmEnum::TD => crc_td_bytes(bytes),
+        CrcAlgorithmEnum::TS => crc_ts_bytes(bytes),
+    }
+}
+
+/// Calculates CRCs of many names at once, in parallel for large inputs.
+/// Results are in the same order as the names.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::core::{crc, crc_batch, GameEnum};
+///
+/// let names = ["a10.shp", "bomb.shp"];
+/// let ids = crc_batch(&names, GameEnum::YR);
+/// assert_eq!(ids, vec![crc("a10.shp", GameEnum::YR), crc("bomb.shp", GameEnum::YR)]);
+/// ```
+pub fn crc_batch<T: AsRef<[u8]> + Sync>(names: &[T], game: GameEnum) -> Vec<i32> {
+    let threads = std::thread::available_parallelism().map_or(1, |x| x.get());
+    if threads == 1 || names.len() < CRC_BATCH_THRESHOLD {
+        return names.iter().map(|x| crc_bytes(x.as_ref(), game)).collect();
     }
+    let chunk = names.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = names
+            .chunks(chunk)
+            .map(|names| {
+                scope.spawn(move || {
+                    names
+                        .iter()
+                        .map(|x| crc_bytes(x.as_ref(), game))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|x| x.join().unwrap_or_else(|_| unreachable!()))
+            .collect()
+    })
 }
 
 /// "CRC" function used in TD and RA.
@@ -37,22 +93,29 @@ pub fn crc(value: impl AsRef<str>, game: GameEnum) -> i32 {
 /// assert_eq!(crc_td("a10.shp"), 0x5CB0AAD5u32 as i32);
 /// ```
 pub fn crc_td(string: impl AsRef<str>) -> i32 {
-    let string = string.as_ref();
-    if string.is_empty() {
-        return 0;
-    }
-    let mut string_upper = string.to_uppercase().into_bytes();
-    // Pad the string so that its length is a multiple of 4.
-    let missing = match string_upper.len() % 4 {
-        1 => 3,
-        3 => 1,
-        x => x,
-    };
-    string_upper.extend_from_slice(&[0u8, 0, 0, 0][0..missing]);
-    // Algorithm proper; Read 32bit chunks, rotate and sum.
-    string_upper
-        .chunks_exact(size_of::<u32>())
-        .map(|b| u32::from_le_bytes(b.try_into().unwrap_or_else(|_| unreachable!())))
+    crc_td_bytes(string.as_ref().as_bytes())
+}
+
+/// "CRC" function used in TD and RA, working on raw bytes.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::core::crc_td_bytes;
+///
+/// assert_eq!(crc_td_bytes(b"a10.shp"), 0x5CB0AAD5u32 as i32);
+/// ```
+pub fn crc_td_bytes(bytes: &[u8]) -> i32 {
+    // Algorithm proper; Read 32bit chunks (zero-padded), rotate and sum.
+    bytes
+        .chunks(size_of::<u32>())
+        .map(|chunk| {
+            let mut buf = [0u8; size_of::<u32>()];
+            for (b, x) in buf.iter_mut().zip(chunk) {
+                *b = x.to_ascii_uppercase();
+            }
+            u32::from_le_bytes(buf)
+        })
         .fold(0u32, |acc, x| x.wrapping_add(acc.rotate_left(1))) as i32
 }
 
@@ -66,26 +129,39 @@ pub fn crc_td(string: impl AsRef<str>) -> i32 {
 /// assert_eq!(crc_ts("bomb.shp"), 0x50F0D1EFu32 as i32);
 /// ```
 pub fn crc_ts(string: impl AsRef<str>) -> i32 {
-    let string = string.as_ref();
-    let len = string.len();
-    if len == 0 {
-        return 0;
+    crc_ts_bytes(string.as_ref().as_bytes())
+}
+
+/// CRC function used in TS, FS, RA2 and YR, working on raw bytes.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::core::crc_ts_bytes;
+///
+/// assert_eq!(crc_ts_bytes(b"bomb.shp"), 0x50F0D1EFu32 as i32);
+/// ```
+pub fn crc_ts_bytes(bytes: &[u8]) -> i32 {
+    let len = bytes.len();
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = [0u8; CRC_BUFFER_SIZE];
+    for chunk in bytes.chunks(CRC_BUFFER_SIZE) {
+        for (b, x) in buf.iter_mut().zip(chunk) {
+            *b = x.to_ascii_uppercase();
+        }
+        hasher.update(&buf[..chunk.len()]);
     }
-    let mut string_upper = string.to_uppercase().into_bytes();
     // Magic WW padding.
     let remainder = len % 4;
     if remainder != 0 {
-        // First pad with the pad size.
-        string_upper.push(remainder as u8);
-        // Then pad with the beginning of the last 4-byte chunk.
-        let padding_idx = (len >> 2) << 2;
-        let padding = string_upper[padding_idx];
-        for _ in 0..(3 - remainder) {
-            string_upper.push(padding);
-        }
+        // First pad with the pad size, then with the beginning of the last 4-byte chunk.
+        let padding = bytes[(len >> 2) << 2].to_ascii_uppercase();
+        let mut pad = [padding; 3];
+        pad[0] = remainder as u8;
+        hasher.update(&pad[..4 - remainder]);
     }
     // Standard CRC32.
-    crc32fast::hash(&string_upper) as i32
+    hasher.finalize() as i32
 }
 
 #[cfg(test)]
@@ -113,12 +189,46 @@ mod examples {
 
         assert_eq!(crc_ts("bomb.shp"), 0x50F0D1EFu32 as i32);
     }
+
+    #[test]
+    fn crc_bytes() {
+        use rust_alert::core::{crc_bytes, GameEnum};
+
+        assert_eq!(crc_bytes(b"a10.shp", GameEnum::TD), 0x5CB0AAD5u32 as i32);
+        assert_eq!(crc_bytes(b"bomb.shp", GameEnum::YR), 0x50F0D1EFu32 as i32);
+    }
+
+    #[test]
+    fn crc_td_bytes() {
+        use rust_alert::core::crc_td_bytes;
+
+        assert_eq!(crc_td_bytes(b"a10.shp"), 0x5CB0AAD5u32 as i32);
+    }
+
+    #[test]
+    fn crc_ts_bytes() {
+        use rust_alert::core::crc_ts_bytes;
+
+        assert_eq!(crc_ts_bytes(b"bomb.shp"), 0x50F0D1EFu32 as i32);
+    }
+
+    #[test]
+    fn crc_batch() {
+        use rust_alert::core::{crc, crc_batch, GameEnum};
+
+        let names = ["a10.shp", "bomb.shp"];
+        let ids = crc_batch(&names, GameEnum::YR);
+        assert_eq!(
+            ids,
+            vec![crc("a10.shp", GameEnum::YR), crc("bomb.shp", GameEnum::YR)]
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::core::GameEnum;
-    use crate::core::{crc, crc_td, crc_ts};
+    use crate::core::{crc, crc_batch, crc_td, crc_td_bytes, crc_ts, crc_ts_bytes};
 
     #[test]
     /// Test TD CRC function.
@@ -179,4 +289,42 @@ mod tests {
             crc("cache.mix", GameEnum::TS)
         );
     }
+
+    #[test]
+    /// Test that only ASCII letters are uppercased.
+    fn test_crc_ascii_only() {
+        // Bytes above 0x7F are left as they are.
+        assert_eq!(
+            crc_ts_bytes(b"\xE9t\xE9.shp"),
+            crc_ts_bytes(b"\xE9T\xE9.SHP")
+        );
+        assert_ne!(crc_ts_bytes(b"\xE9.shp"), crc_ts_bytes(b"\xC9.shp"));
+        assert_ne!(crc_td_bytes(b"\xE9.shp"), crc_td_bytes(b"\xC9.shp"));
+        // Strings are hashed by their UTF-8 bytes.
+        assert_eq!(crc_ts("\u{e9}.shp"), crc_ts_bytes("\u{e9}.SHP".as_bytes()));
+        assert_ne!(crc_ts("\u{e9}.shp"), crc_ts("\u{c9}.shp"));
+    }
+
+    #[test]
+    /// Test names longer than the uppercasing buffer.
+    fn test_crc_long() {
+        let name = "a".repeat(1001);
+        let mut padded = name.to_uppercase().into_bytes();
+        padded.extend_from_slice(&[1, b'A', b'A']);
+        assert_eq!(crc_ts(&name), crc32fast::hash(&padded) as i32);
+    }
+
+    #[test]
+    /// Test that batch hashing matches hashing one by one, above the threading threshold.
+    fn test_crc_batch() {
+        let names: Vec<String> = (0..10000).map(|x| format!("{x:05}.shp")).collect();
+        for game in [GameEnum::TD, GameEnum::YR] {
+            let ids = crc_batch(&names, game);
+            assert_eq!(ids.len(), names.len());
+            assert!(names
+                .iter()
+                .zip(ids)
+                .all(|(name, id)| crc(name, game) == id));
+        }
+    }
 }
@@ -1,7 +1,19 @@
 //! General data definitions used by other modules.
 
+use std::str::FromStr;
+
+/// The error type for parsing general data definitions.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The string doesn't name any supported game.
+    #[error("Unknown game: {0}")]
+    UnknownGame(String),
+}
+
 /// Supported C&C games.
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameEnum {
     /// Command & Conquer, also called Tiberian Dawn or C&C1. Old MIX format, old CRC algo.
     TD,
@@ -17,3 +29,50 @@ pub enum GameEnum {
     #[default]
     YR,
 }
+
+impl std::fmt::Display for GameEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for GameEnum {
+    type Err = Error;
+
+    /// Parse a game from its short name, ignoring case.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::core::GameEnum;
+    ///
+    /// assert_eq!("TD".parse::<GameEnum>().unwrap(), GameEnum::TD);
+    /// assert_eq!("ra2".parse::<GameEnum>().unwrap(), GameEnum::RA2);
+    /// assert!("C&C".parse::<GameEnum>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "td" => Ok(GameEnum::TD),
+            "ra" => Ok(GameEnum::RA),
+            "ts" => Ok(GameEnum::TS),
+            "fs" => Ok(GameEnum::FS),
+            "ra2" => Ok(GameEnum::RA2),
+            "yr" => Ok(GameEnum::YR),
+            _ => Err(Error::UnknownGame(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn from_str() {
+        use rust_alert::core::GameEnum;
+
+        assert_eq!("TD".parse::<GameEnum>().unwrap(), GameEnum::TD);
+        assert_eq!("ra2".parse::<GameEnum>().unwrap(), GameEnum::RA2);
+        assert!("C&C".parse::<GameEnum>().is_err());
+    }
+}
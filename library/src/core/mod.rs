@@ -2,6 +2,14 @@
 
 mod crc;
 mod general;
+mod preimage;
+mod profile;
 
 pub use crc::*;
-pub use general::*;
+pub use profile::*;
+
+pub use general::Error as ParseGameError;
+pub use general::GameEnum;
+
+pub use preimage::Error as PreimageError;
+pub use preimage::{crc_ts_preimage, MAX_PREIMAGE_LENGTH};
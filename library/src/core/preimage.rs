@@ -0,0 +1,196 @@
+//! Preimage search for the TS CRC function.
+//!
+//! CRC32 is affine over GF(2), so for a fixed name length the ID is an affine function of
+//! the bits of the unknown part of the name. Finding all names with a given ID comes down to
+//! solving a system of 32 linear equations and enumerating its solution space.
+
+use std::ops::RangeInclusive;
+
+use crate::core::crc_ts_bytes;
+
+/// The error type for preimage search.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The unknown part is too long to enumerate all solutions.
+    #[error("Unknown part can be at most {MAX_PREIMAGE_LENGTH} characters long, got {0}")]
+    LengthTooLarge(usize),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Maximum length of the unknown part of a name.
+///
+/// Every character past the 4th adds 8 free bits to the solution space, so 6 characters
+/// means checking 65536 solutions per length.
+pub const MAX_PREIMAGE_LENGTH: usize = 6;
+
+/// Finds all names in form of `prefix` + middle + `suffix` whose [`crc_ts`][crate::core::crc_ts]
+/// is equal to `id`, where middle is a string of filename-legal characters with length in
+/// `lengths`. The middle part is reported in lowercase.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::core::{crc_ts, crc_ts_preimage};
+///
+/// let names = crc_ts_preimage(0x50F0D1EFu32 as i32, "b", ".shp", 3..=3).unwrap();
+/// assert!(names.contains(&"bomb.shp".to_string()));
+/// assert!(names.iter().all(|x| crc_ts(x) == 0x50F0D1EFu32 as i32));
+/// ```
+pub fn crc_ts_preimage(
+    id: i32,
+    prefix: &str,
+    suffix: &str,
+    lengths: RangeInclusive<usize>,
+) -> Result<Vec<String>> {
+    if *lengths.end() > MAX_PREIMAGE_LENGTH {
+        return Err(Error::LengthTooLarge(*lengths.end()));
+    }
+    let mut results = vec![];
+    for length in lengths {
+        // Single bits are never lowercase letters, so uppercasing doesn't break linearity here.
+        let message = |middle: &[u8]| {
+            crc_ts_bytes(&[prefix.as_bytes(), middle, suffix.as_bytes()].concat()) as u32
+        };
+        // ID of the all-zero middle and the contribution of every single bit.
+        let mut middle = vec![0u8; length];
+        let base = message(&middle);
+        let columns: Vec<u32> = (0..length * 8)
+            .map(|bit| {
+                middle[bit / 8] = 1 << (bit % 8);
+                let column = message(&middle) ^ base;
+                middle[bit / 8] = 0;
+                column
+            })
+            .collect();
+
+        solve(&columns, id as u32 ^ base, |solution| {
+            let middle: Vec<u8> = (0..length).map(|i| (solution >> (i * 8)) as u8).collect();
+            if middle.iter().copied().all(is_legal) {
+                let middle = String::from_utf8_lossy(&middle).to_ascii_lowercase();
+                results.push(format!("{prefix}{middle}{suffix}"));
+            }
+        });
+    }
+    Ok(results)
+}
+
+/// Checks if an (uppercased) character can appear in a filename.
+fn is_legal(x: u8) -> bool {
+    x.is_ascii_graphic()
+        && !x.is_ascii_lowercase()
+        && !matches!(
+            x,
+            b'\\' | b'/' | b':' | b'*' | b'?' | b'"' | b'<' | b'>' | b'|'
+        )
+}
+
+/// Solves `columns * x = target` over GF(2), calling `f` for every solution.
+/// Every column is an equation coefficient for one bit of `x`, so at most 64 columns are supported.
+fn solve(columns: &[u32], target: u32, mut f: impl FnMut(u64)) {
+    // One row per bit of the CRC, with coefficients packed into a u64.
+    let mut rows: Vec<(u64, bool)> = (0..32)
+        .map(|bit| {
+            let coefficients = columns
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, c)| acc | ((((c >> bit) & 1) as u64) << i));
+            (coefficients, (target >> bit) & 1 == 1)
+        })
+        .collect();
+
+    // Gauss-Jordan elimination.
+    let mut pivots = vec![];
+    for column in 0..columns.len() {
+        let mask = 1u64 << column;
+        let rank = pivots.len();
+        let Some(found) = (rank..rows.len()).find(|i| rows[*i].0 & mask != 0) else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot = rows[rank];
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != rank && row.0 & mask != 0 {
+                row.0 ^= pivot.0;
+                row.1 ^= pivot.1;
+            }
+        }
+        pivots.push(column);
+    }
+    // Leftover rows are all zero, so they must have a zero target too.
+    if rows[pivots.len()..].iter().any(|row| row.1) {
+        return;
+    }
+
+    let free: Vec<usize> = (0..columns.len()).filter(|x| !pivots.contains(x)).collect();
+    for assignment in 0..(1u64 << free.len()) {
+        let mut solution = free.iter().enumerate().fold(0u64, |acc, (i, column)| {
+            acc | (((assignment >> i) & 1) << column)
+        });
+        for (row, column) in rows.iter().zip(&pivots) {
+            if row.1 ^ ((row.0 & solution).count_ones() & 1 == 1) {
+                solution |= 1 << column;
+            }
+        }
+        f(solution);
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn crc_ts_preimage() {
+        use rust_alert::core::{crc_ts, crc_ts_preimage};
+
+        let names = crc_ts_preimage(0x50F0D1EFu32 as i32, "b", ".shp", 3..=3).unwrap();
+        assert!(names.contains(&"bomb.shp".to_string()));
+        assert!(names.iter().all(|x| crc_ts(x) == 0x50F0D1EFu32 as i32));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{crc_ts, crc_ts_preimage, PreimageError, MAX_PREIMAGE_LENGTH};
+
+    #[test]
+    fn preimage_all_lengths() {
+        for (prefix, middle, suffix) in [
+            ("", "a", ""),
+            ("xx", "12", ".shp"),
+            ("gtclock", "abc", ""),
+            ("xxx", "1234", ".shp"),
+            ("ab", "c_d-e", ".vxl"),
+            ("", "abcdef", ".shp"),
+        ] {
+            let name = format!("{prefix}{middle}{suffix}");
+            let id = crc_ts(&name);
+            let names = crc_ts_preimage(id, prefix, suffix, 1..=MAX_PREIMAGE_LENGTH).unwrap();
+            assert!(names.contains(&name), "{name} not in {names:?}");
+            assert!(names.iter().all(|x| crc_ts(x) == id));
+        }
+    }
+
+    #[test]
+    fn preimage_padding_in_middle() {
+        // Last chunk starts inside the unknown part, so padding depends on it.
+        let id = crc_ts("abcde1");
+        let names = crc_ts_preimage(id, "abcd", "", 2..=2).unwrap();
+        assert_eq!(names, vec!["abcde1".to_string()]);
+    }
+
+    #[test]
+    fn preimage_legal_only() {
+        let names = crc_ts_preimage(crc_ts("a?"), "a", "", 1..=1).unwrap();
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn preimage_err() {
+        assert!(matches!(
+            crc_ts_preimage(0, "", "", 1..=MAX_PREIMAGE_LENGTH + 1),
+            Err(PreimageError::LengthTooLarge(_))
+        ));
+    }
+}
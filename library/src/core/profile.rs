@@ -0,0 +1,271 @@
+//! Static per-game data.
+
+use std::cmp::Ordering;
+
+use crate::{
+    core::GameEnum,
+    mix::{db::LMDVersionEnum, LMD_KEY_TD, LMD_KEY_TS},
+};
+
+/// CRC algorithms used to calculate MIX file IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrcAlgorithmEnum {
+    /// Rotate-and-add "CRC" used in TD and RA. See [`crc_td`][crate::core::crc_td].
+    TD,
+    /// CRC32 with Westwood padding used since TS. See [`crc_ts`][crate::core::crc_ts].
+    TS,
+}
+
+/// How IDs are compared when the game binary-searches a MIX index.
+/// The index has to be sorted the same way, or files silently fail to load.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexOrderEnum {
+    /// IDs compared as signed integers, used in TD and RA.
+    Signed,
+    /// IDs compared as unsigned integers, used since TS.
+    #[default]
+    Unsigned,
+}
+
+impl IndexOrderEnum {
+    /// Compare two IDs the way the game does.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use std::cmp::Ordering;
+    /// use rust_alert::core::IndexOrderEnum;
+    ///
+    /// assert_eq!(IndexOrderEnum::Signed.cmp(-1, 1), Ordering::Less);
+    /// assert_eq!(IndexOrderEnum::Unsigned.cmp(-1, 1), Ordering::Greater);
+    /// ```
+    pub fn cmp(&self, a: i32, b: i32) -> Ordering {
+        match self {
+            IndexOrderEnum::Signed => a.cmp(&b),
+            IndexOrderEnum::Unsigned => (a as u32).cmp(&(b as u32)),
+        }
+    }
+}
+
+/// A map theater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TheaterProfile {
+    /// Theater name, as used in maps.
+    pub name: &'static str,
+    /// Extension of theater-specific files, without the dot.
+    pub extension: &'static str,
+    /// Name of the theater palette.
+    pub palette: &'static str,
+}
+
+/// Facts about a game, used to pick the right formats and algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameProfile {
+    /// The game this profile describes.
+    pub game: GameEnum,
+    /// CRC algorithm for MIX file IDs.
+    pub crc: CrcAlgorithmEnum,
+    /// Order of IDs in the MIX index.
+    pub index_order: IndexOrderEnum,
+    /// Whether MIXes of this game use the new (>=RA) format by default.
+    pub new_mix_format: bool,
+    /// MIX index key of the LMD.
+    pub lmd_key: i32,
+    /// LMD version written for this game.
+    pub lmd_version: LMDVersionEnum,
+    /// Whether the game reads CSF stringtables.
+    pub has_csf: bool,
+    /// Theaters available in the base game.
+    pub theaters: &'static [TheaterProfile],
+    /// Main top-level MIXes, in the order the game loads them.
+    /// Expansion MIXes (`expand*.mix`, `ecache*.mix`) are loaded before these.
+    pub mix_load_order: &'static [&'static str],
+}
+
+const fn theater(
+    name: &'static str,
+    extension: &'static str,
+    palette: &'static str,
+) -> TheaterProfile {
+    TheaterProfile {
+        name,
+        extension,
+        palette,
+    }
+}
+
+const TD_THEATERS: &[TheaterProfile] = &[
+    theater("TEMPERATE", "tem", "temperat.pal"),
+    theater("WINTER", "win", "winter.pal"),
+    theater("DESERT", "des", "desert.pal"),
+];
+
+const RA_THEATERS: &[TheaterProfile] = &[
+    theater("TEMPERATE", "tem", "temperat.pal"),
+    theater("SNOW", "sno", "snow.pal"),
+    theater("INTERIOR", "int", "interior.pal"),
+];
+
+const TS_THEATERS: &[TheaterProfile] = &[
+    theater("TEMPERATE", "tem", "isotem.pal"),
+    theater("SNOW", "sno", "isosno.pal"),
+];
+
+const RA2_THEATERS: &[TheaterProfile] = &[
+    theater("TEMPERATE", "tem", "isotem.pal"),
+    theater("SNOW", "sno", "isosno.pal"),
+    theater("URBAN", "urb", "isourb.pal"),
+];
+
+const YR_THEATERS: &[TheaterProfile] = &[
+    theater("TEMPERATE", "tem", "isotem.pal"),
+    theater("SNOW", "sno", "isosno.pal"),
+    theater("URBAN", "urb", "isourb.pal"),
+    theater("NEWURBAN", "ubn", "isoubn.pal"),
+    theater("DESERT", "des", "isodes.pal"),
+    theater("LUNAR", "lun", "isolun.pal"),
+];
+
+static PROFILE_TD: GameProfile = GameProfile {
+    game: GameEnum::TD,
+    crc: CrcAlgorithmEnum::TD,
+    index_order: IndexOrderEnum::Signed,
+    new_mix_format: false,
+    lmd_key: LMD_KEY_TD,
+    lmd_version: LMDVersionEnum::TD,
+    has_csf: false,
+    theaters: TD_THEATERS,
+    mix_load_order: &["local.mix", "conquer.mix", "general.mix"],
+};
+
+static PROFILE_RA: GameProfile = GameProfile {
+    game: GameEnum::RA,
+    crc: CrcAlgorithmEnum::TD,
+    index_order: IndexOrderEnum::Signed,
+    new_mix_format: true,
+    lmd_key: LMD_KEY_TD,
+    lmd_version: LMDVersionEnum::RA,
+    has_csf: false,
+    theaters: RA_THEATERS,
+    mix_load_order: &["redalert.mix", "main.mix"],
+};
+
+static PROFILE_TS: GameProfile = GameProfile {
+    game: GameEnum::TS,
+    crc: CrcAlgorithmEnum::TS,
+    index_order: IndexOrderEnum::Unsigned,
+    new_mix_format: true,
+    lmd_key: LMD_KEY_TS,
+    lmd_version: LMDVersionEnum::TS,
+    has_csf: false,
+    theaters: TS_THEATERS,
+    mix_load_order: &["tibsun.mix"],
+};
+
+static PROFILE_FS: GameProfile = GameProfile {
+    game: GameEnum::FS,
+    crc: CrcAlgorithmEnum::TS,
+    index_order: IndexOrderEnum::Unsigned,
+    new_mix_format: true,
+    lmd_key: LMD_KEY_TS,
+    lmd_version: LMDVersionEnum::TS,
+    has_csf: false,
+    theaters: TS_THEATERS,
+    mix_load_order: &["tibsun.mix"],
+};
+
+static PROFILE_RA2: GameProfile = GameProfile {
+    game: GameEnum::RA2,
+    crc: CrcAlgorithmEnum::TS,
+    index_order: IndexOrderEnum::Unsigned,
+    new_mix_format: true,
+    lmd_key: LMD_KEY_TS,
+    lmd_version: LMDVersionEnum::RA2,
+    has_csf: true,
+    theaters: RA2_THEATERS,
+    mix_load_order: &["language.mix", "ra2.mix"],
+};
+
+static PROFILE_YR: GameProfile = GameProfile {
+    game: GameEnum::YR,
+    crc: CrcAlgorithmEnum::TS,
+    index_order: IndexOrderEnum::Unsigned,
+    new_mix_format: true,
+    lmd_key: LMD_KEY_TS,
+    lmd_version: LMDVersionEnum::YR,
+    has_csf: true,
+    theaters: YR_THEATERS,
+    mix_load_order: &["langmd.mix", "language.mix", "ra2md.mix", "ra2.mix"],
+};
+
+impl GameEnum {
+    /// Returns static facts about the game.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::core::{CrcAlgorithmEnum, GameEnum};
+    ///
+    /// let profile = GameEnum::RA2.profile();
+    /// assert_eq!(profile.crc, CrcAlgorithmEnum::TS);
+    /// assert!(profile.has_csf);
+    /// assert_eq!(profile.theaters[2].extension, "urb");
+    /// ```
+    pub fn profile(&self) -> &'static GameProfile {
+        match self {
+            GameEnum::TD => &PROFILE_TD,
+            GameEnum::RA => &PROFILE_RA,
+            GameEnum::TS => &PROFILE_TS,
+            GameEnum::FS => &PROFILE_FS,
+            GameEnum::RA2 => &PROFILE_RA2,
+            GameEnum::YR => &PROFILE_YR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn profile() {
+        use rust_alert::core::{CrcAlgorithmEnum, GameEnum};
+
+        let profile = GameEnum::RA2.profile();
+        assert_eq!(profile.crc, CrcAlgorithmEnum::TS);
+        assert!(profile.has_csf);
+        assert_eq!(profile.theaters[2].extension, "urb");
+    }
+
+    #[test]
+    fn index_order_cmp() {
+        use rust_alert::core::IndexOrderEnum;
+        use std::cmp::Ordering;
+
+        assert_eq!(IndexOrderEnum::Signed.cmp(-1, 1), Ordering::Less);
+        assert_eq!(IndexOrderEnum::Unsigned.cmp(-1, 1), Ordering::Greater);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{crc, GameEnum};
+
+    #[test]
+    fn profile_consistency() {
+        for game in [
+            GameEnum::TD,
+            GameEnum::RA,
+            GameEnum::TS,
+            GameEnum::FS,
+            GameEnum::RA2,
+            GameEnum::YR,
+        ] {
+            let profile = game.profile();
+            assert_eq!(profile.game, game);
+            assert_eq!(profile.lmd_key, crc("local mix database.dat", game));
+            assert!(!profile.theaters.is_empty());
+            assert!(!profile.mix_load_order.is_empty());
+        }
+    }
+}
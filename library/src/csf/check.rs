@@ -0,0 +1,355 @@
+//! Checks of translated stringtables against their base stringtable.
+
+use crate::csf::{string_name, CsfStringtable};
+
+/// Conversions accepted in placeholders, including the wide `%S` and `%C` of MSVC.
+const CONVERSIONS: &[u8] = b"diouxXeEfFgGaAcCsSpn";
+/// Length modifiers accepted in placeholders, longest first.
+const LENGTHS: [&str; 5] = ["hh", "ll", "h", "l", "L"];
+
+/// A printf-style placeholder, such as `%s`, `%-5d` or `%1$s`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsfPlaceholder {
+    /// Placeholder as written in the string.
+    pub text: String,
+    /// Argument position, for placeholders like `%1$s`.
+    pub position: Option<usize>,
+    /// The part of the placeholder that decides which arguments it consumes:
+    /// `*` width and precision, length modifier and conversion, with `%i` written as `%d`.
+    pub spec: String,
+}
+
+impl std::fmt::Display for CsfPlaceholder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A problem found in a translation by [`check_translation`]. Strings are referred
+/// to by their label name and index in the label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsfTranslationIssueEnum {
+    /// Placeholders of a translated string don't match the base string,
+    /// so the game reads wrong arguments or crashes.
+    PlaceholderMismatch(String, usize, Vec<CsfPlaceholder>, Vec<CsfPlaceholder>),
+    /// A translated string has a `%` which doesn't start a placeholder and isn't written as `%%`.
+    /// Contains the character offset of the `%`.
+    UnbalancedEscape(String, usize, usize),
+    /// A translated string has a different number of newlines than the base string.
+    NewlineMismatch(String, usize, usize, usize),
+}
+
+impl std::fmt::Display for CsfTranslationIssueEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |x: &[CsfPlaceholder]| {
+            let x: Vec<_> = x.iter().map(|x| x.text.as_str()).collect();
+            format!("[{}]", x.join(", "))
+        };
+        match self {
+            Self::PlaceholderMismatch(label, i, expected, found) => write!(
+                f,
+                "{}: placeholders {} don't match base placeholders {}",
+                string_name(label, *i),
+                join(found),
+                join(expected)
+            ),
+            Self::UnbalancedEscape(label, i, offset) => write!(
+                f,
+                "{}: unescaped % at character {}",
+                string_name(label, *i),
+                offset
+            ),
+            Self::NewlineMismatch(label, i, expected, found) => write!(
+                f,
+                "{}: {} newline(s), but base has {}",
+                string_name(label, *i),
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+/// A part of a string found by [`scan`].
+enum Token {
+    Placeholder(CsfPlaceholder),
+    /// Character offset of a `%` starting no placeholder.
+    Invalid(usize),
+}
+
+/// Find all placeholders in a string. `%%` is a literal `%` and isn't a placeholder.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::placeholders;
+///
+/// let result = placeholders("%s has %-3d%% of %2$s");
+/// let result: Vec<_> = result.iter().map(|x| x.text.as_str()).collect();
+/// assert_eq!(result, vec!["%s", "%-3d", "%2$s"]);
+/// ```
+pub fn placeholders(value: &str) -> Vec<CsfPlaceholder> {
+    scan(value)
+        .into_iter()
+        .filter_map(|x| match x {
+            Token::Placeholder(x) => Some(x),
+            Token::Invalid(_) => None,
+        })
+        .collect()
+}
+
+/// Compare placeholders, newlines and escapes of every translated string with its base string.
+/// Labels missing from either stringtable are not checked.
+///
+/// Placeholders are compared in order, unless all of them have positions.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::{check_translation, CsfStringtable, CsfTranslationIssueEnum};
+///
+/// let mut base = CsfStringtable::default();
+/// base.create("Txt:Money", "%s has $%d");
+/// base.create("Txt:Order", "%1$s then %2$s");
+/// let mut translation = CsfStringtable::default();
+/// translation.create("Txt:Money", "%d$ for %s");
+/// translation.create("Txt:Order", "%2$s after %1$s");
+/// let issues = check_translation(&base, &translation);
+///
+/// assert_eq!(issues.len(), 1);
+/// assert!(matches!(issues[0], CsfTranslationIssueEnum::PlaceholderMismatch(..)));
+/// ```
+pub fn check_translation(
+    base: &CsfStringtable,
+    translation: &CsfStringtable,
+) -> Vec<CsfTranslationIssueEnum> {
+    let mut issues = vec![];
+    for label in translation.iter() {
+        let Some(base_label) = base.get(&label.name) else {
+            continue;
+        };
+        let pairs = base_label.strings.iter().zip(&label.strings);
+        for (i, (base_string, string)) in pairs.enumerate() {
+            let expected = placeholders(&base_string.value);
+            let mut found = vec![];
+            for token in scan(&string.value) {
+                match token {
+                    Token::Placeholder(x) => found.push(x),
+                    Token::Invalid(x) => issues.push(CsfTranslationIssueEnum::UnbalancedEscape(
+                        label.name.clone(),
+                        i,
+                        x,
+                    )),
+                }
+            }
+            if !same_placeholders(&expected, &found) {
+                issues.push(CsfTranslationIssueEnum::PlaceholderMismatch(
+                    label.name.clone(),
+                    i,
+                    expected,
+                    found,
+                ));
+            }
+            let expected = base_string.value.matches('\n').count();
+            let found = string.value.matches('\n').count();
+            if expected != found {
+                issues.push(CsfTranslationIssueEnum::NewlineMismatch(
+                    label.name.clone(),
+                    i,
+                    expected,
+                    found,
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// Check if two lists of placeholders consume the same arguments.
+fn same_placeholders(a: &[CsfPlaceholder], b: &[CsfPlaceholder]) -> bool {
+    fn key(x: &[CsfPlaceholder]) -> Vec<(Option<usize>, &str)> {
+        let mut key: Vec<_> = x.iter().map(|x| (x.position, x.spec.as_str())).collect();
+        if key.iter().all(|x| x.0.is_some()) {
+            key.sort();
+        }
+        key
+    }
+    key(a) == key(b)
+}
+
+/// Split a string into placeholders and invalid escapes.
+fn scan(value: &str) -> Vec<Token> {
+    let bytes = value.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            i += 1;
+        } else if bytes.get(i + 1) == Some(&b'%') {
+            i += 2;
+        } else if let Some(placeholder) = parse_placeholder(&value[i..]) {
+            i += placeholder.text.len();
+            tokens.push(Token::Placeholder(placeholder));
+        } else {
+            tokens.push(Token::Invalid(value[..i].chars().count()));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// Parse a placeholder at the start of a string, which starts with `%`.
+fn parse_placeholder(value: &str) -> Option<CsfPlaceholder> {
+    let bytes = value.as_bytes();
+    let digits = |i: usize| bytes[i..].iter().take_while(|x| x.is_ascii_digit()).count();
+    let mut i = 1;
+    let mut position = None;
+    let n = digits(i);
+    if n > 0 && bytes.get(i + n) == Some(&b'$') {
+        position = Some(value[i..i + n].parse().ok()?);
+        i += n + 1;
+    }
+    i += bytes[i..]
+        .iter()
+        .take_while(|x| b"-+ #0".contains(x))
+        .count();
+    let mut spec = String::new();
+    if bytes.get(i) == Some(&b'*') {
+        spec.push('*');
+        i += 1;
+    } else {
+        i += digits(i);
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if bytes.get(i) == Some(&b'*') {
+            spec.push_str(".*");
+            i += 1;
+        } else {
+            i += digits(i);
+        }
+    }
+    if let Some(length) = LENGTHS.iter().find(|x| value[i..].starts_with(**x)) {
+        spec.push_str(length);
+        i += length.len();
+    }
+    let conversion = *bytes.get(i).filter(|x| CONVERSIONS.contains(x))?;
+    spec.push(match conversion {
+        b'i' => 'd',
+        x => x as char,
+    });
+    Some(CsfPlaceholder {
+        text: value[..=i].to_string(),
+        position,
+        spec,
+    })
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn placeholders() {
+        use rust_alert::csf::placeholders;
+
+        let result = placeholders("%s has %-3d%% of %2$s");
+        let result: Vec<_> = result.iter().map(|x| x.text.as_str()).collect();
+        assert_eq!(result, vec!["%s", "%-3d", "%2$s"]);
+    }
+
+    #[test]
+    fn check_translation() {
+        use rust_alert::csf::{check_translation, CsfStringtable, CsfTranslationIssueEnum};
+
+        let mut base = CsfStringtable::default();
+        base.create("Txt:Money", "%s has $%d");
+        base.create("Txt:Order", "%1$s then %2$s");
+        let mut translation = CsfStringtable::default();
+        translation.create("Txt:Money", "%d$ for %s");
+        translation.create("Txt:Order", "%2$s after %1$s");
+        let issues = check_translation(&base, &translation);
+
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            CsfTranslationIssueEnum::PlaceholderMismatch(..)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csf::{
+        check::{parse_placeholder, same_placeholders},
+        check_translation, placeholders, CsfLabel, CsfString, CsfStringtable,
+        CsfTranslationIssueEnum,
+    };
+
+    #[test]
+    fn parse() {
+        let specs = [
+            ("%d", None, "d"),
+            ("%i", None, "d"),
+            ("%-08.3f", None, "f"),
+            ("%*.*ls", None, "*.*ls"),
+            ("%12$hhu", Some(12), "hhu"),
+            ("%S", None, "S"),
+        ];
+        for (text, position, spec) in specs {
+            let placeholder = parse_placeholder(text).unwrap();
+            assert_eq!(placeholder.text, text);
+            assert_eq!(placeholder.position, position);
+            assert_eq!(placeholder.spec, spec);
+        }
+        assert_eq!(parse_placeholder("%3d!").unwrap().text, "%3d");
+        for text in ["%", "%y", "%5", "%.", "%1$", "%l"] {
+            assert_eq!(parse_placeholder(text), None);
+        }
+    }
+
+    #[test]
+    fn compare() {
+        let same = |a: &str, b: &str| same_placeholders(&placeholders(a), &placeholders(b));
+        assert!(same("%d %s", "%i: %s"));
+        assert!(same("%5d", "%d"));
+        assert!(!same("%d %s", "%s %d"));
+        assert!(!same("%d", "%d %d"));
+        assert!(!same("%d", "%ld"));
+        assert!(!same("%*d", "%d"));
+        assert!(same("%1$d %2$s", "%2$s %1$d"));
+        assert!(!same("%1$d %s", "%s %1$d"));
+        assert!(same("", "No placeholders"));
+    }
+
+    #[test]
+    fn issues() {
+        let mut base = CsfStringtable::default();
+        base.insert(CsfLabel {
+            name: "A".to_string(),
+            strings: vec![
+                CsfString::new("Won 100%%, %d"),
+                CsfString::new("Line\nLine"),
+            ],
+        });
+        base.create("B", "Base only");
+        let mut translation = CsfStringtable::default();
+        translation.insert(CsfLabel {
+            name: "A".to_string(),
+            strings: vec![CsfString::new("Won 100%, %d"), CsfString::new("Line")],
+        });
+        translation.create("C", "Translation only %");
+
+        let issues = check_translation(&base, &translation);
+        assert_eq!(
+            issues,
+            vec![
+                CsfTranslationIssueEnum::UnbalancedEscape("A".to_string(), 0, 7),
+                CsfTranslationIssueEnum::NewlineMismatch("A".to_string(), 1, 1, 0),
+            ]
+        );
+        assert_eq!(issues[0].to_string(), "A: unescaped % at character 7");
+        assert_eq!(issues[1].to_string(), "A|1: 0 newline(s), but base has 1");
+        assert!(check_translation(&base, &base).is_empty());
+    }
+}
@@ -1,35 +1,35 @@
 //! CSF (stringtable) structure definitions and manipulation methods.
 
-use std::collections::HashSet;
+use std::{cmp::Ordering, collections::HashMap, sync::OnceLock};
 
-use crate::csf::{enums::*, iters::*};
-
-/// The error type for operations on CSF stringtables and related constructs.
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    /// The supplied number doesn't match any known CSF version variants.
-    #[error("Unknown version number {0}")]
-    UnknownVersion(u32),
-    /// The supplied number doesn't match any known CSF language variants.
-    #[error("Unknown language number {0}")]
-    UnknownLanguage(u32),
-}
+use indexmap::IndexSet;
 
-#[doc(hidden)]
-pub type Result<T> = std::result::Result<T, Error>;
+use crate::csf::{enums::*, iters::*};
 
 /// A stringtable containing key-value pairs for game text.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Labels keep the order they were inserted in, so reading and writing a stringtable
+/// keeps its original order.
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CsfStringtable {
-    /// Set of labels.
-    labels: HashSet<CsfLabel>,
+    /// Set of labels, in insertion order.
+    labels: IndexSet<CsfLabel>,
     /// Format version of the stringtable.
     pub version: CsfVersionEnum,
     /// Language of the stringtable.
     pub language: CsfLanguageEnum,
     /// Extra data attached to the header.
     pub extra: u32,
+    /// How [`get`][Self::get], [`get_str`][Self::get_str] and [`remove`][Self::remove]
+    /// match label names. Inserting labels always compares names exactly.
+    /// This is a setting of the stringtable rather than its content, so it's not compared
+    /// or serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub lookup: CsfLookupEnum,
+    /// Label indices by ASCII lowercase name, for [`CsfLookupEnum::IgnoreCase`].
+    /// Built on the first such lookup and dropped whenever labels change.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    folded: OnceLock<HashMap<String, usize>>,
 }
 
 impl CsfStringtable {
@@ -55,7 +55,7 @@ impl CsfStringtable {
         }
     }
 
-    /// Creates an iterator visiting all labels in an arbitrary order.
+    /// Creates an iterator visiting all labels in order.
     ///
     /// # Examples
     ///
@@ -78,7 +78,7 @@ impl CsfStringtable {
         self.labels.iter().into()
     }
 
-    /// Creates a draining iterator visiting all labels in an arbitrary order.
+    /// Creates a draining iterator visiting all labels in order.
     /// Allocated memory is not freed.
     ///
     /// If the draining iterator is dropped before being fully consumed, all
@@ -102,7 +102,8 @@ impl CsfStringtable {
     /// assert_eq!(csf.len(), 0);
     /// ```
     pub fn drain(&mut self) -> Drain {
-        self.labels.drain().into()
+        self.folded.take();
+        self.labels.drain(..).into()
     }
 
     /// Creates a new label from a name and a string, then adds (or replaces)
@@ -138,7 +139,7 @@ impl CsfStringtable {
     /// Inserts (or replaces) a label to the stringtable.
     ///
     /// Returns the old label with the same name if overwritten, otherwise
-    /// `None`.
+    /// `None`. An overwritten label keeps its position, a new one is appended.
     /// Also see [`create`][Self::create] to create and put a new
     /// [`CsfLabel`] into the stringtable directly.
     ///
@@ -159,17 +160,19 @@ impl CsfStringtable {
     /// assert_eq!(csf.len(), 1);
     /// ```
     pub fn insert(&mut self, label: CsfLabel) -> Option<CsfLabel> {
+        self.folded.take();
         self.labels.replace(label)
     }
 
-    /// Removes a label with given name from the stringtable.
+    /// Removes a label with given name from the stringtable, keeping the order
+    /// of remaining labels.
     ///
     /// Returns removed [`CsfLabel`] or `None` if nothing was removed.
     ///
     /// # Examples
     ///
     /// ```ignore
-    /// use rust_alert::csf::{CsfStringtable, CsfLabel};
+    /// use rust_alert::csf::{CsfLabel, CsfLookupEnum, CsfStringtable};
     ///
     /// let mut csf = CsfStringtable::default();
     /// csf.create("A", "1");
@@ -184,12 +187,16 @@ impl CsfStringtable {
     /// let result = csf.remove("A");
     /// assert_eq!(result, None);
     /// assert_eq!(csf.len(), 1);
+    ///
+    /// csf.lookup = CsfLookupEnum::IgnoreCase;
+    /// let result = csf.remove("b");
+    /// assert_eq!(result, Some(CsfLabel::new("B", "2")));
+    /// assert_eq!(csf.len(), 0);
     /// ```
     pub fn remove(&mut self, name: impl Into<String>) -> Option<CsfLabel> {
-        self.labels.take(&CsfLabel {
-            name: name.into(),
-            strings: vec![],
-        })
+        let index = self.find(name.into())?;
+        self.folded.take();
+        self.labels.shift_remove_index(index)
     }
 
     /// Looks up the [`CsfLabel`] with given name.
@@ -200,7 +207,7 @@ impl CsfStringtable {
     /// # Examples
     ///
     /// ```ignore
-    /// use rust_alert::csf::CsfStringtable;
+    /// use rust_alert::csf::{CsfLookupEnum, CsfStringtable};
     ///
     /// let mut csf = CsfStringtable::default();
     /// csf.create("A", "1");
@@ -210,12 +217,17 @@ impl CsfStringtable {
     ///
     /// let result = csf.get("B");
     /// assert_eq!(result, None);
+    ///
+    /// let result = csf.get("a");
+    /// assert_eq!(result, None);
+    ///
+    /// csf.lookup = CsfLookupEnum::IgnoreCase;
+    /// let result = csf.get("a");
+    /// assert_eq!(result, csf.iter().next());
     /// ```
     pub fn get(&self, name: impl Into<String>) -> Option<&CsfLabel> {
-        self.labels.get(&CsfLabel {
-            name: name.into(),
-            strings: vec![],
-        })
+        let index = self.find(name.into())?;
+        self.labels.get_index(index)
     }
 
     /// Looks up the first string of a label with given name.
@@ -239,15 +251,31 @@ impl CsfStringtable {
     /// assert_eq!(result, None);
     /// ```
     pub fn get_str(&self, name: impl Into<String>) -> Option<&str> {
-        self.labels
-            .get(&CsfLabel {
-                name: name.into(),
-                strings: vec![],
-            })
+        self.get(name)
             .and_then(|l| l.get_first())
             .map(|s| s.value.as_str())
     }
 
+    /// Find the index of a label with given name, following [`lookup`][Self::lookup].
+    fn find(&self, name: String) -> Option<usize> {
+        match self.lookup {
+            CsfLookupEnum::Exact => self.labels.get_index_of(&CsfLabel {
+                name,
+                strings: vec![],
+            }),
+            CsfLookupEnum::IgnoreCase => {
+                let folded = self.folded.get_or_init(|| {
+                    let mut folded = HashMap::with_capacity(self.labels.len());
+                    for (i, label) in self.labels.iter().enumerate() {
+                        folded.entry(label.name.to_ascii_lowercase()).or_insert(i);
+                    }
+                    folded
+                });
+                folded.get(&name.to_ascii_lowercase()).copied()
+            }
+        }
+    }
+
     /// Count all labels in the stringtable.
     ///
     /// # Examples
@@ -317,15 +345,73 @@ impl CsfStringtable {
     pub fn reserve(&mut self, additional: usize) {
         self.labels.reserve(additional);
     }
+
+    /// Sorts all labels by their names.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfStringtable;
+    ///
+    /// let mut csf = CsfStringtable::default();
+    /// csf.create("B", "1");
+    /// csf.create("A", "2");
+    ///
+    /// csf.sort();
+    ///
+    /// let names: Vec<_> = csf.iter().map(|l| l.name.as_str()).collect();
+    /// assert_eq!(names, vec!["A", "B"]);
+    /// ```
+    pub fn sort(&mut self) {
+        self.folded.take();
+        self.labels.sort();
+    }
+
+    /// Sorts all labels with a comparator function.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfStringtable;
+    ///
+    /// let mut csf = CsfStringtable::default();
+    /// csf.create("a", "1");
+    /// csf.create("B", "2");
+    ///
+    /// csf.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    ///
+    /// let names: Vec<_> = csf.iter().map(|l| l.name.as_str()).collect();
+    /// assert_eq!(names, vec!["a", "B"]);
+    /// ```
+    pub fn sort_by<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&CsfLabel, &CsfLabel) -> Ordering,
+    {
+        self.folded.take();
+        self.labels.sort_by(cmp);
+    }
+}
+
+impl PartialEq for CsfStringtable {
+    /// Stringtables are equal if their labels and headers are equal.
+    /// [`lookup`][CsfStringtable::lookup] is not compared.
+    fn eq(&self, other: &Self) -> bool {
+        self.labels == other.labels
+            && self.version == other.version
+            && self.language == other.language
+            && self.extra == other.extra
+    }
 }
 
+impl Eq for CsfStringtable {}
+
 impl IntoIterator for CsfStringtable {
     type Item = CsfLabel;
 
     type IntoIter = IntoIter;
 
     /// Creates a consuming iterator that will move labels out of the
-    /// stringtable in arbitrary order.
+    /// stringtable in order.
     ///
     /// # Examples
     ///
@@ -348,6 +434,7 @@ impl IntoIterator for CsfStringtable {
 
 impl Extend<CsfLabel> for CsfStringtable {
     fn extend<T: IntoIterator<Item = CsfLabel>>(&mut self, iter: T) {
+        self.folded.take();
         self.labels.extend(iter);
     }
 }
@@ -437,6 +524,27 @@ impl CsfLabel {
     pub fn get_first_str(&self) -> Option<&str> {
         self.strings.first().and_then(|s| Some(s.value.as_str()))
     }
+
+    /// Returns a CRC32 checksum of the label's strings. Only string values
+    /// are checksummed, so renaming a label or changing extra values keeps it.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLabel;
+    ///
+    /// let label = CsfLabel::new("A", "1");
+    /// assert_eq!(label.fingerprint(), CsfLabel::new("B", "1").fingerprint());
+    /// assert_ne!(label.fingerprint(), CsfLabel::new("A", "2").fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        for string in &self.strings {
+            hasher.update(string.value.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize()
+    }
 }
 
 impl PartialEq for CsfLabel {
@@ -662,6 +770,47 @@ impl AsRef<str> for CsfString {
     }
 }
 
+/// Get the name of a string of a label, as used by converters and reports.
+/// The first string is named after the label, later ones are `LABEL|N`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::string_name;
+///
+/// assert_eq!(string_name("GUI:Quit", 0), "GUI:Quit");
+/// assert_eq!(string_name("GUI:Quit", 2), "GUI:Quit|2");
+/// ```
+pub fn string_name(label: &str, index: usize) -> String {
+    match index {
+        0 => label.to_string(),
+        i => format!("{label}|{i}"),
+    }
+}
+
+/// Reverse [`string_name`], returning the label name and the string index.
+/// Names without a `|N` suffix, where `N` is a positive number written without
+/// leading zeros, are the first string of a label of that name.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::parse_string_name;
+///
+/// assert_eq!(parse_string_name("GUI:Quit|2"), ("GUI:Quit", 2));
+/// assert_eq!(parse_string_name("GUI:Quit"), ("GUI:Quit", 0));
+/// assert_eq!(parse_string_name("GUI:Quit|0"), ("GUI:Quit|0", 0));
+/// assert_eq!(parse_string_name("GUI:Quit|02"), ("GUI:Quit|02", 0));
+/// ```
+pub fn parse_string_name(name: &str) -> (&str, usize) {
+    name.rsplit_once('|')
+        .and_then(|(label, i)| {
+            let index = i.parse::<usize>().ok().filter(|x| *x > 0)?;
+            (index.to_string() == i).then_some((label, index))
+        })
+        .unwrap_or((name, 0))
+}
+
 #[cfg(test)]
 mod examples {
     mod csf_stringtable {
@@ -744,7 +893,7 @@ mod examples {
 
         #[test]
         fn remove() {
-            use rust_alert::csf::{CsfLabel, CsfStringtable};
+            use rust_alert::csf::{CsfLabel, CsfLookupEnum, CsfStringtable};
 
             let mut csf = CsfStringtable::default();
             csf.create("A", "1");
@@ -759,11 +908,16 @@ mod examples {
             let result = csf.remove("A");
             assert_eq!(result, None);
             assert_eq!(csf.len(), 1);
+
+            csf.lookup = CsfLookupEnum::IgnoreCase;
+            let result = csf.remove("b");
+            assert_eq!(result, Some(CsfLabel::new("B", "2")));
+            assert_eq!(csf.len(), 0);
         }
 
         #[test]
         fn get() {
-            use rust_alert::csf::CsfStringtable;
+            use rust_alert::csf::{CsfLookupEnum, CsfStringtable};
 
             let mut csf = CsfStringtable::default();
             csf.create("A", "1");
@@ -773,6 +927,13 @@ mod examples {
 
             let result = csf.get("B");
             assert_eq!(result, None);
+
+            let result = csf.get("a");
+            assert_eq!(result, None);
+
+            csf.lookup = CsfLookupEnum::IgnoreCase;
+            let result = csf.get("a");
+            assert_eq!(result, csf.iter().next());
         }
 
         #[test]
@@ -833,6 +994,34 @@ mod examples {
             csf.reserve(42);
         }
 
+        #[test]
+        fn sort() {
+            use rust_alert::csf::CsfStringtable;
+
+            let mut csf = CsfStringtable::default();
+            csf.create("B", "1");
+            csf.create("A", "2");
+
+            csf.sort();
+
+            let names: Vec<_> = csf.iter().map(|l| l.name.as_str()).collect();
+            assert_eq!(names, vec!["A", "B"]);
+        }
+
+        #[test]
+        fn sort_by() {
+            use rust_alert::csf::CsfStringtable;
+
+            let mut csf = CsfStringtable::default();
+            csf.create("a", "1");
+            csf.create("B", "2");
+
+            csf.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+            let names: Vec<_> = csf.iter().map(|l| l.name.as_str()).collect();
+            assert_eq!(names, vec!["a", "B"]);
+        }
+
         #[test]
         fn into_iter() {
             use rust_alert::csf::{CsfLabel, CsfStringtable};
@@ -901,6 +1090,15 @@ mod examples {
             assert_eq!(result, Some("1"));
         }
 
+        #[test]
+        fn fingerprint() {
+            use rust_alert::csf::CsfLabel;
+
+            let label = CsfLabel::new("A", "1");
+            assert_eq!(label.fingerprint(), CsfLabel::new("B", "1").fingerprint());
+            assert_ne!(label.fingerprint(), CsfLabel::new("A", "2").fingerprint());
+        }
+
         #[test]
         fn eq() {
             use rust_alert::csf::CsfLabel;
@@ -1018,6 +1216,28 @@ mod examples {
             assert_eq!(format!("{string}"), "B");
         }
     }
+
+    mod string_names {
+        use crate as rust_alert;
+
+        #[test]
+        fn string_name() {
+            use rust_alert::csf::string_name;
+
+            assert_eq!(string_name("GUI:Quit", 0), "GUI:Quit");
+            assert_eq!(string_name("GUI:Quit", 2), "GUI:Quit|2");
+        }
+
+        #[test]
+        fn parse_string_name() {
+            use rust_alert::csf::parse_string_name;
+
+            assert_eq!(parse_string_name("GUI:Quit|2"), ("GUI:Quit", 2));
+            assert_eq!(parse_string_name("GUI:Quit"), ("GUI:Quit", 0));
+            assert_eq!(parse_string_name("GUI:Quit|0"), ("GUI:Quit|0", 0));
+            assert_eq!(parse_string_name("GUI:Quit|02"), ("GUI:Quit|02", 0));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1066,3 +1286,80 @@ mod coverage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::csf::{CsfLabel, CsfLanguageEnum, CsfLookupEnum, CsfStringtable};
+
+    fn names(csf: &CsfStringtable) -> Vec<&str> {
+        csf.iter().map(|l| l.name.as_str()).collect()
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut csf = CsfStringtable::default();
+        csf.create("C", "1");
+        csf.create("A", "2");
+        csf.create("B", "3");
+        assert_eq!(names(&csf), vec!["C", "A", "B"]);
+
+        // Replacing keeps the position, removing keeps the order of the rest.
+        csf.create("A", "4");
+        assert_eq!(names(&csf), vec!["C", "A", "B"]);
+        assert_eq!(csf.get_str("A"), Some("4"));
+        csf.remove("C");
+        assert_eq!(names(&csf), vec!["A", "B"]);
+
+        csf.extend([CsfLabel::new("D", "5"), CsfLabel::new("C", "6")]);
+        assert_eq!(names(&csf), vec!["A", "B", "D", "C"]);
+        let all: Vec<_> = csf.into_iter().map(|l| l.name).collect();
+        assert_eq!(all, vec!["A", "B", "D", "C"]);
+    }
+
+    #[test]
+    fn ignore_case_lookup() {
+        let mut csf = CsfStringtable::default();
+        csf.create("GUI:Quit", "1");
+        csf.create("GUI:QUIT", "2");
+        csf.create("Name:Äb", "3");
+        assert_eq!(csf.get_str("gui:quit"), None);
+
+        // Which of the labels differing only in case is found is unspecified.
+        csf.lookup = CsfLookupEnum::IgnoreCase;
+        assert!(matches!(csf.get_str("GUI:QUIT"), Some("1" | "2")));
+        assert!(matches!(csf.get_str("gui:quit"), Some("1" | "2")));
+        // Only ASCII letters are folded.
+        assert_eq!(csf.get_str("NAME:ÄB"), Some("3"));
+        assert_eq!(csf.get_str("name:äb"), None);
+
+        // Inserting still compares names exactly.
+        csf.create("gui:quit", "4");
+        assert_eq!(csf.len(), 4);
+        let removed = csf.remove("Gui:Quit").unwrap();
+        assert!(removed.name.eq_ignore_ascii_case("gui:quit"));
+        assert_eq!(csf.len(), 3);
+
+        // Lookups see labels added, removed and moved after earlier lookups.
+        assert_eq!(csf.get_str("name:Äb"), Some("3"));
+        csf.create("New", "5");
+        assert_eq!(csf.get_str("NEW"), Some("5"));
+        csf.remove("name:Äb");
+        assert_eq!(csf.get_str("new"), Some("5"));
+        csf.sort();
+        assert_eq!(csf.get_str("new"), Some("5"));
+        csf.drain();
+        assert_eq!(csf.get_str("new"), None);
+    }
+
+    #[test]
+    fn lookup_not_compared() {
+        let mut a = CsfStringtable::default();
+        a.create("A", "1");
+        let mut b = a.clone();
+        b.lookup = CsfLookupEnum::IgnoreCase;
+        assert_eq!(b.get_str("a"), Some("1"));
+        assert_eq!(a, b);
+        b.language = CsfLanguageEnum::DE;
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,415 @@
+//! Comparison of translated stringtables with their base stringtable.
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::csf::{CsfLabel, CsfStringtable};
+
+/// Fingerprints of base labels, by label name, as returned by
+/// [`CsfLabel::fingerprint`]. Stored when a translation is updated,
+/// they show which base labels changed since.
+pub type CsfFingerprints = IndexMap<String, u32>;
+
+/// Make fingerprints of every label in a stringtable.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::{fingerprints, CsfStringtable};
+///
+/// let mut csf = CsfStringtable::default();
+/// csf.create("GUI:Quit", "Quit");
+/// let result = fingerprints(&csf);
+///
+/// assert_eq!(result["GUI:Quit"], csf.get("GUI:Quit").unwrap().fingerprint());
+/// ```
+pub fn fingerprints(csf: &CsfStringtable) -> CsfFingerprints {
+    csf.iter()
+        .map(|x| (x.name.clone(), x.fingerprint()))
+        .collect()
+}
+
+/// Get the category of a label, the part of its name before the first `:`.
+/// Labels without a category return an empty string.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::csf::{category, CsfLabel};
+///
+/// assert_eq!(category(&CsfLabel::new("GUI:Quit", "Quit")), "GUI");
+/// assert_eq!(category(&CsfLabel::new("Quit", "Quit")), "");
+/// ```
+pub fn category(label: &CsfLabel) -> &str {
+    label.name.split_once(':').map_or("", |x| x.0)
+}
+
+/// Differences between a base stringtable and its translation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsfDiff {
+    /// Base labels missing from the translation.
+    pub missing: Vec<String>,
+    /// Translation labels missing from the base stringtable.
+    pub extra: Vec<String>,
+    /// Translated labels whose base strings changed since their fingerprints were stored.
+    pub changed: Vec<String>,
+}
+
+impl CsfDiff {
+    /// Compare a translation with its base stringtable. Changed labels are only
+    /// reported for labels with a stored fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::{fingerprints, CsfDiff, CsfStringtable};
+    ///
+    /// let mut base = CsfStringtable::default();
+    /// base.create("GUI:Quit", "Quit");
+    /// base.create("GUI:Load", "Load");
+    /// let stored = fingerprints(&base);
+    /// base.create("GUI:Quit", "Exit");
+    /// let mut translation = CsfStringtable::default();
+    /// translation.create("GUI:Quit", "Beenden");
+    /// translation.create("GUI:Old", "Alt");
+    /// let diff = CsfDiff::new(&base, &translation, Some(&stored));
+    ///
+    /// assert_eq!(diff.missing, vec!["GUI:Load"]);
+    /// assert_eq!(diff.extra, vec!["GUI:Old"]);
+    /// assert_eq!(diff.changed, vec!["GUI:Quit"]);
+    /// ```
+    pub fn new(
+        base: &CsfStringtable,
+        translation: &CsfStringtable,
+        fingerprints: Option<&CsfFingerprints>,
+    ) -> Self {
+        let mut diff = Self::default();
+        for label in base.iter() {
+            if translation.get(&label.name).is_none() {
+                diff.missing.push(label.name.clone());
+            } else if is_changed(label, fingerprints) {
+                diff.changed.push(label.name.clone());
+            }
+        }
+        diff.extra = translation
+            .iter()
+            .filter(|x| base.get(&x.name).is_none())
+            .map(|x| x.name.clone())
+            .collect();
+        diff
+    }
+
+    /// Returns `true` if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.changed.is_empty()
+    }
+
+    /// Bring the translation the diff was made for in line with the base stringtable.
+    /// Missing labels are copied from the base stringtable, after all other labels,
+    /// and extra labels are removed. Changed labels are kept as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::{CsfDiff, CsfStringtable};
+    ///
+    /// let mut base = CsfStringtable::default();
+    /// base.create("GUI:Quit", "Quit");
+    /// base.create("GUI:Load", "Load");
+    /// let mut translation = CsfStringtable::default();
+    /// translation.create("GUI:Quit", "Beenden");
+    /// translation.create("GUI:Old", "Alt");
+    /// let diff = CsfDiff::new(&base, &translation, None);
+    /// diff.merge(&base, &mut translation);
+    ///
+    /// assert_eq!(translation.get_str("GUI:Quit"), Some("Beenden"));
+    /// assert_eq!(translation.get_str("GUI:Load"), Some("Load"));
+    /// assert!(translation.get("GUI:Old").is_none());
+    /// ```
+    pub fn merge(&self, base: &CsfStringtable, translation: &mut CsfStringtable) {
+        for name in &self.extra {
+            translation.remove(name);
+        }
+        for name in &self.missing {
+            translation.extend(base.get(name).cloned());
+        }
+    }
+}
+
+/// Counts of base labels in a translation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CsfCoverageCount {
+    /// Number of labels in the base stringtable.
+    pub total: usize,
+    /// Number of base labels present in the translation.
+    pub translated: usize,
+    /// Number of translated labels whose base strings changed.
+    pub changed: usize,
+}
+
+impl CsfCoverageCount {
+    /// Number of labels translated and up to date.
+    pub fn complete(&self) -> usize {
+        self.translated - self.changed
+    }
+
+    /// Percentage of labels translated and up to date.
+    /// An empty base stringtable is fully translated.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfCoverageCount;
+    ///
+    /// let count = CsfCoverageCount { total: 4, translated: 3, changed: 1 };
+    /// assert_eq!(count.percentage(), 50.0);
+    /// assert_eq!(CsfCoverageCount::default().percentage(), 100.0);
+    /// ```
+    pub fn percentage(&self) -> f64 {
+        match self.total {
+            0 => 100.0,
+            total => self.complete() as f64 * 100.0 / total as f64,
+        }
+    }
+}
+
+/// Translation coverage of a base stringtable, in total and per category.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsfCoverage {
+    /// Counts for all labels.
+    pub total: CsfCoverageCount,
+    /// Counts for each category, in order of first appearance in the base stringtable.
+    /// Labels without a category are counted under an empty string.
+    pub categories: IndexMap<String, CsfCoverageCount>,
+}
+
+impl CsfCoverage {
+    /// Count translated and changed labels of a base stringtable.
+    /// Changed labels are only counted for labels with a stored fingerprint.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::{CsfCoverage, CsfStringtable};
+    ///
+    /// let mut base = CsfStringtable::default();
+    /// base.create("GUI:Quit", "Quit");
+    /// base.create("GUI:Load", "Load");
+    /// base.create("Name:E1", "GI");
+    /// let mut translation = CsfStringtable::default();
+    /// translation.create("GUI:Quit", "Beenden");
+    /// translation.create("Name:E1", "GI");
+    /// let coverage = CsfCoverage::new(&base, &translation, None);
+    ///
+    /// assert_eq!(coverage.total.translated, 2);
+    /// assert_eq!(coverage.categories["GUI"].percentage(), 50.0);
+    /// assert_eq!(coverage.categories["Name"].percentage(), 100.0);
+    /// ```
+    pub fn new(
+        base: &CsfStringtable,
+        translation: &CsfStringtable,
+        fingerprints: Option<&CsfFingerprints>,
+    ) -> Self {
+        let translated: HashSet<_> = translation.iter().map(|x| x.name.as_str()).collect();
+        let mut coverage = Self::default();
+        for label in base.iter() {
+            let category = coverage
+                .categories
+                .entry(category(label).to_string())
+                .or_default();
+            let counts = [&mut coverage.total, category];
+            for count in counts {
+                count.total += 1;
+                if translated.contains(label.name.as_str()) {
+                    count.translated += 1;
+                    if is_changed(label, fingerprints) {
+                        count.changed += 1;
+                    }
+                }
+            }
+        }
+        coverage
+    }
+}
+
+/// Check if a stored fingerprint differs from the label's fingerprint.
+fn is_changed(label: &CsfLabel, fingerprints: Option<&CsfFingerprints>) -> bool {
+    fingerprints
+        .and_then(|x| x.get(&label.name))
+        .is_some_and(|x| *x != label.fingerprint())
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn fingerprints() {
+        use rust_alert::csf::{fingerprints, CsfStringtable};
+
+        let mut csf = CsfStringtable::default();
+        csf.create("GUI:Quit", "Quit");
+        let result = fingerprints(&csf);
+
+        assert_eq!(
+            result["GUI:Quit"],
+            csf.get("GUI:Quit").unwrap().fingerprint()
+        );
+    }
+
+    #[test]
+    fn category() {
+        use rust_alert::csf::{category, CsfLabel};
+
+        assert_eq!(category(&CsfLabel::new("GUI:Quit", "Quit")), "GUI");
+        assert_eq!(category(&CsfLabel::new("Quit", "Quit")), "");
+    }
+
+    #[test]
+    fn diff_new() {
+        use rust_alert::csf::{fingerprints, CsfDiff, CsfStringtable};
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.create("GUI:Load", "Load");
+        let stored = fingerprints(&base);
+        base.create("GUI:Quit", "Exit");
+        let mut translation = CsfStringtable::default();
+        translation.create("GUI:Quit", "Beenden");
+        translation.create("GUI:Old", "Alt");
+        let diff = CsfDiff::new(&base, &translation, Some(&stored));
+
+        assert_eq!(diff.missing, vec!["GUI:Load"]);
+        assert_eq!(diff.extra, vec!["GUI:Old"]);
+        assert_eq!(diff.changed, vec!["GUI:Quit"]);
+    }
+
+    #[test]
+    fn diff_merge() {
+        use rust_alert::csf::{CsfDiff, CsfStringtable};
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.create("GUI:Load", "Load");
+        let mut translation = CsfStringtable::default();
+        translation.create("GUI:Quit", "Beenden");
+        translation.create("GUI:Old", "Alt");
+        let diff = CsfDiff::new(&base, &translation, None);
+        diff.merge(&base, &mut translation);
+
+        assert_eq!(translation.get_str("GUI:Quit"), Some("Beenden"));
+        assert_eq!(translation.get_str("GUI:Load"), Some("Load"));
+        assert!(translation.get("GUI:Old").is_none());
+    }
+
+    #[test]
+    fn percentage() {
+        use rust_alert::csf::CsfCoverageCount;
+
+        let count = CsfCoverageCount {
+            total: 4,
+            translated: 3,
+            changed: 1,
+        };
+        assert_eq!(count.percentage(), 50.0);
+        assert_eq!(CsfCoverageCount::default().percentage(), 100.0);
+    }
+
+    #[test]
+    fn coverage_new() {
+        use rust_alert::csf::{CsfCoverage, CsfStringtable};
+
+        let mut base = CsfStringtable::default();
+        base.create("GUI:Quit", "Quit");
+        base.create("GUI:Load", "Load");
+        base.create("Name:E1", "GI");
+        let mut translation = CsfStringtable::default();
+        translation.create("GUI:Quit", "Beenden");
+        translation.create("Name:E1", "GI");
+        let coverage = CsfCoverage::new(&base, &translation, None);
+
+        assert_eq!(coverage.total.translated, 2);
+        assert_eq!(coverage.categories["GUI"].percentage(), 50.0);
+        assert_eq!(coverage.categories["Name"].percentage(), 100.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csf::{fingerprints, CsfCoverage, CsfCoverageCount, CsfDiff, CsfStringtable};
+
+    #[test]
+    fn unchanged() {
+        let mut base = CsfStringtable::default();
+        base.create("A", "1");
+        base.create("B:A", "2");
+        let stored = fingerprints(&base);
+        let diff = CsfDiff::new(&base, &base, Some(&stored));
+        assert!(diff.is_empty());
+
+        let coverage = CsfCoverage::new(&base, &base, Some(&stored));
+        let expected = CsfCoverageCount {
+            total: 1,
+            translated: 1,
+            changed: 0,
+        };
+        assert_eq!(coverage.total.complete(), 2);
+        assert_eq!(coverage.categories.len(), 2);
+        assert_eq!(coverage.categories[""], expected);
+        assert_eq!(coverage.categories["B"], expected);
+    }
+
+    #[test]
+    fn changed() {
+        let mut base = CsfStringtable::default();
+        base.create("A:1", "1");
+        base.create("A:2", "2");
+        base.create("A:3", "3");
+        let mut stored = fingerprints(&base);
+        stored.shift_remove("A:3");
+        base.create("A:1", "One");
+        base.create("A:2", "Two");
+        base.create("A:3", "Three");
+        let mut translation = base.clone();
+        translation.remove("A:2");
+
+        // Missing labels are not reported as changed, and labels without
+        // a fingerprint are never changed.
+        let diff = CsfDiff::new(&base, &translation, Some(&stored));
+        assert_eq!(diff.changed, vec!["A:1"]);
+        assert_eq!(diff.missing, vec!["A:2"]);
+        assert!(CsfDiff::new(&base, &translation, None).changed.is_empty());
+
+        let coverage = CsfCoverage::new(&base, &translation, Some(&stored));
+        assert_eq!(coverage.total, coverage.categories["A"]);
+        assert_eq!(coverage.total.translated, 2);
+        assert_eq!(coverage.total.changed, 1);
+        assert_eq!(coverage.total.complete(), 1);
+    }
+
+    #[test]
+    fn merge() {
+        let mut base = CsfStringtable::default();
+        base.create("A", "1");
+        base.create("B", "2");
+        base.create("C", "3");
+        let stored = fingerprints(&base);
+        base.create("A", "One");
+        let mut translation = CsfStringtable::default();
+        translation.create("Extra", "Weg");
+        translation.create("C", "Drei");
+        translation.create("A", "Eins");
+
+        let diff = CsfDiff::new(&base, &translation, Some(&stored));
+        diff.merge(&base, &mut translation);
+        let names: Vec<_> = translation.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, vec!["C", "A", "B"]);
+        assert_eq!(translation.get_str("A"), Some("Eins"));
+        assert_eq!(translation.get_str("B"), Some("2"));
+
+        let diff = CsfDiff::new(&base, &translation, Some(&stored));
+        assert!(diff.missing.is_empty() && diff.extra.is_empty());
+        assert_eq!(diff.changed, vec!["A"]);
+    }
+}
@@ -2,8 +2,6 @@
 
 use std::fmt::Display;
 
-use crate::csf::{Error, Result};
-
 /// CSF format version. "Nothing is known about the actual difference between the versions."
 ///
 /// Read more at
@@ -11,77 +9,98 @@ use crate::csf::{Error, Result};
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u32)]
 pub enum CsfVersionEnum {
     /// Used in Nox (2000).
-    Nox = 2,
+    Nox,
     /// Used in all C&C games with CSF support (so RA2/YR) and Lord of the
     /// Rings: Battle for the Middle-earth.
     #[default]
-    Cnc = 3,
+    Cnc,
+    /// Any other version number, kept as is.
+    #[cfg_attr(feature = "clap", value(skip))]
+    Unknown(u32),
 }
 
 impl Display for CsfVersionEnum {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
-            Self::Nox => "Nox",
-            Self::Cnc => "Cnc",
-        };
-        write!(f, "{}", string)
+        match self {
+            Self::Nox => write!(f, "Nox"),
+            Self::Cnc => write!(f, "Cnc"),
+            Self::Unknown(x) => write!(f, "Unknown ({x})"),
+        }
     }
 }
 
-impl TryFrom<u32> for CsfVersionEnum {
-    type Error = Error;
-
-    fn try_from(value: u32) -> Result<Self> {
+impl From<u32> for CsfVersionEnum {
+    /// Convert a version number. Numbers of known versions never become
+    /// [`Unknown`][Self::Unknown].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfVersionEnum;
+    ///
+    /// assert_eq!(CsfVersionEnum::from(3), CsfVersionEnum::Cnc);
+    /// assert_eq!(CsfVersionEnum::from(4), CsfVersionEnum::Unknown(4));
+    /// assert_eq!(u32::from(CsfVersionEnum::Unknown(4)), 4);
+    /// ```
+    fn from(value: u32) -> Self {
         match value {
-            x if x == CsfVersionEnum::Nox as u32 => Ok(CsfVersionEnum::Nox),
-            x if x == CsfVersionEnum::Cnc as u32 => Ok(CsfVersionEnum::Cnc),
-            x => Err(Error::UnknownVersion(x)),
+            2 => Self::Nox,
+            3 => Self::Cnc,
+            x => Self::Unknown(x),
         }
     }
 }
 
-impl TryFrom<CsfVersionEnum> for u32 {
-    type Error = Error;
-
-    fn try_from(value: CsfVersionEnum) -> Result<Self> {
-        Ok(value as u32)
+impl From<CsfVersionEnum> for u32 {
+    fn from(value: CsfVersionEnum) -> Self {
+        match value {
+            CsfVersionEnum::Nox => 2,
+            CsfVersionEnum::Cnc => 3,
+            CsfVersionEnum::Unknown(x) => x,
+        }
     }
 }
 
 /// CSF language ID used for localisation.
 ///
+/// IDs 0-8 are shared by all games using the CSF format. ID 9 is Chinese in RA2/YR,
+/// but marks an unknown language in Generals and Zero Hour, whose language list ends there.
+/// No game is known to define IDs above 9, so they are read as [`Unknown`][Self::Unknown]
+/// and written back unchanged instead of getting named variants.
+///
 /// Read more at
 /// [ModEnc](https://modenc.renegadeprojects.com/CSF_File_Format#Language).
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[repr(u32)]
 pub enum CsfLanguageEnum {
     /// English (United States)
     #[default]
-    ENUS = 0,
+    ENUS,
     /// English (United Kingdom)
-    ENUK = 1,
+    ENUK,
     /// German
-    DE = 2,
+    DE,
     /// French
-    FR = 3,
+    FR,
     /// Spanish
-    ES = 4,
+    ES,
     /// Italian
-    IT = 5,
+    IT,
     /// Japanese
-    JA = 6,
+    JA,
     /// Joke WW entry - allegedly Jabberwockie (sic)
-    XX = 7,
+    XX,
     /// Korean
-    KO = 8,
+    KO,
     /// Chinese
-    ZHCN = 9,
+    ZHCN,
+    /// Any other language ID, kept as is.
+    #[cfg_attr(feature = "clap", value(skip))]
+    Unknown(u32),
 }
 
 impl Display for CsfLanguageEnum {
@@ -97,36 +116,248 @@ impl Display for CsfLanguageEnum {
             Self::XX => "Unknown",
             Self::KO => "KO",
             Self::ZHCN => "ZHCN",
+            Self::Unknown(x) => return write!(f, "{:?}", format!("Unknown ({x})")),
         };
         write!(f, "{:?}", string)
     }
 }
 
-impl TryFrom<u32> for CsfLanguageEnum {
-    type Error = Error;
+impl CsfLanguageEnum {
+    /// Returns the gettext language code of the language, if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLanguageEnum;
+    ///
+    /// assert_eq!(CsfLanguageEnum::ENUK.code(), Some("en_GB"));
+    /// assert_eq!(CsfLanguageEnum::XX.code(), None);
+    /// ```
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Self::ENUS => Some("en_US"),
+            Self::ENUK => Some("en_GB"),
+            Self::DE => Some("de"),
+            Self::FR => Some("fr"),
+            Self::ES => Some("es"),
+            Self::IT => Some("it"),
+            Self::JA => Some("ja"),
+            Self::KO => Some("ko"),
+            Self::ZHCN => Some("zh_CN"),
+            Self::XX | Self::Unknown(_) => None,
+        }
+    }
+
+    /// Find the language with given gettext or BCP 47 code, ignoring case, and encoding
+    /// or modifier of gettext codes. Codes with an unknown region or other subtags,
+    /// like `de-DE`, fall back to the language of their primary subtag, except for
+    /// English and Chinese, whose languages are told apart only by their region.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLanguageEnum;
+    ///
+    /// assert_eq!(CsfLanguageEnum::from_code("en-GB"), Some(CsfLanguageEnum::ENUK));
+    /// assert_eq!(CsfLanguageEnum::from_code("zh_cn"), Some(CsfLanguageEnum::ZHCN));
+    /// assert_eq!(CsfLanguageEnum::from_code("de-DE"), Some(CsfLanguageEnum::DE));
+    /// assert_eq!(CsfLanguageEnum::from_code("en-AU"), None);
+    /// assert_eq!(CsfLanguageEnum::from_code("pl"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('-', "_");
+        let find = |code: &str| {
+            (0..=9)
+                .map(Self::from)
+                .find(|x| x.code().is_some_and(|x| x.eq_ignore_ascii_case(code)))
+        };
+        find(&code).or_else(|| find(code.split('_').next()?))
+    }
 
-    fn try_from(value: u32) -> Result<Self> {
+    /// Returns the BCP 47 tag of the language. Languages without a code get
+    /// a private use tag with their ID.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLanguageEnum;
+    ///
+    /// assert_eq!(CsfLanguageEnum::ENUK.tag(), "en-GB");
+    /// assert_eq!(CsfLanguageEnum::XX.tag(), "x-csf-7");
+    /// ```
+    pub fn tag(&self) -> String {
+        match self.code() {
+            Some(code) => code.replace('_', "-"),
+            None => format!("x-csf-{}", u32::from(*self)),
+        }
+    }
+
+    /// Reverse [`tag`][Self::tag]. Also accepts gettext codes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLanguageEnum;
+    ///
+    /// assert_eq!(CsfLanguageEnum::from_tag("en-GB"), Some(CsfLanguageEnum::ENUK));
+    /// assert_eq!(CsfLanguageEnum::from_tag("x-csf-7"), Some(CsfLanguageEnum::XX));
+    /// assert_eq!(CsfLanguageEnum::from_tag("x-csf-"), None);
+    /// ```
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::from_code(tag).or_else(|| {
+            tag.strip_prefix("x-csf-")
+                .and_then(|x| x.parse::<u32>().ok())
+                .map(Self::from)
+        })
+    }
+}
+
+impl From<u32> for CsfLanguageEnum {
+    /// Convert a language ID. IDs of known languages never become
+    /// [`Unknown`][Self::Unknown].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::CsfLanguageEnum;
+    ///
+    /// assert_eq!(CsfLanguageEnum::from(2), CsfLanguageEnum::DE);
+    /// assert_eq!(CsfLanguageEnum::from(42), CsfLanguageEnum::Unknown(42));
+    /// assert_eq!(u32::from(CsfLanguageEnum::Unknown(42)), 42);
+    /// ```
+    fn from(value: u32) -> Self {
         match value {
-            x if x == CsfLanguageEnum::ENUS as u32 => Ok(CsfLanguageEnum::ENUS),
-            x if x == CsfLanguageEnum::ENUK as u32 => Ok(CsfLanguageEnum::ENUK),
-            x if x == CsfLanguageEnum::DE as u32 => Ok(CsfLanguageEnum::DE),
-            x if x == CsfLanguageEnum::FR as u32 => Ok(CsfLanguageEnum::FR),
-            x if x == CsfLanguageEnum::ES as u32 => Ok(CsfLanguageEnum::ES),
-            x if x == CsfLanguageEnum::IT as u32 => Ok(CsfLanguageEnum::IT),
-            x if x == CsfLanguageEnum::JA as u32 => Ok(CsfLanguageEnum::JA),
-            x if x == CsfLanguageEnum::XX as u32 => Ok(CsfLanguageEnum::XX),
-            x if x == CsfLanguageEnum::KO as u32 => Ok(CsfLanguageEnum::KO),
-            x if x == CsfLanguageEnum::ZHCN as u32 => Ok(CsfLanguageEnum::ZHCN),
-            x => Err(Error::UnknownLanguage(x)),
+            0 => Self::ENUS,
+            1 => Self::ENUK,
+            2 => Self::DE,
+            3 => Self::FR,
+            4 => Self::ES,
+            5 => Self::IT,
+            6 => Self::JA,
+            7 => Self::XX,
+            8 => Self::KO,
+            9 => Self::ZHCN,
+            x => Self::Unknown(x),
         }
     }
 }
 
-impl TryFrom<CsfLanguageEnum> for u32 {
-    type Error = Error;
+impl From<CsfLanguageEnum> for u32 {
+    fn from(value: CsfLanguageEnum) -> Self {
+        match value {
+            CsfLanguageEnum::ENUS => 0,
+            CsfLanguageEnum::ENUK => 1,
+            CsfLanguageEnum::DE => 2,
+            CsfLanguageEnum::FR => 3,
+            CsfLanguageEnum::ES => 4,
+            CsfLanguageEnum::IT => 5,
+            CsfLanguageEnum::JA => 6,
+            CsfLanguageEnum::XX => 7,
+            CsfLanguageEnum::KO => 8,
+            CsfLanguageEnum::ZHCN => 9,
+            CsfLanguageEnum::Unknown(x) => x,
+        }
+    }
+}
 
-    fn try_from(value: CsfLanguageEnum) -> Result<Self> {
-        Ok(value as u32)
+/// How a [`CsfStringtable`][crate::csf::CsfStringtable] matches label names when
+/// looking labels up.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CsfLookupEnum {
+    /// Names must be equal.
+    #[default]
+    Exact,
+    /// Names are compared ignoring ASCII case, like the game does. Which of several
+    /// labels differing only in case is found is unspecified, as in the game.
+    IgnoreCase,
+}
+
+impl Display for CsfLookupEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact => write!(f, "Exact"),
+            Self::IgnoreCase => write!(f, "IgnoreCase"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn code() {
+        use rust_alert::csf::CsfLanguageEnum;
+
+        assert_eq!(CsfLanguageEnum::ENUK.code(), Some("en_GB"));
+        assert_eq!(CsfLanguageEnum::XX.code(), None);
+    }
+
+    #[test]
+    fn from_code() {
+        use rust_alert::csf::CsfLanguageEnum;
+
+        assert_eq!(
+            CsfLanguageEnum::from_code("en-GB"),
+            Some(CsfLanguageEnum::ENUK)
+        );
+        assert_eq!(
+            CsfLanguageEnum::from_code("zh_cn"),
+            Some(CsfLanguageEnum::ZHCN)
+        );
+        assert_eq!(
+            CsfLanguageEnum::from_code("de-DE"),
+            Some(CsfLanguageEnum::DE)
+        );
+        assert_eq!(CsfLanguageEnum::from_code("en-AU"), None);
+        assert_eq!(CsfLanguageEnum::from_code("pl"), None);
+    }
+
+    #[test]
+    fn tag() {
+        use rust_alert::csf::CsfLanguageEnum;
+
+        assert_eq!(CsfLanguageEnum::ENUK.tag(), "en-GB");
+        assert_eq!(CsfLanguageEnum::XX.tag(), "x-csf-7");
+    }
+
+    #[test]
+    fn from_tag() {
+        use rust_alert::csf::CsfLanguageEnum;
+
+        assert_eq!(
+            CsfLanguageEnum::from_tag("en-GB"),
+            Some(CsfLanguageEnum::ENUK)
+        );
+        assert_eq!(
+            CsfLanguageEnum::from_tag("x-csf-7"),
+            Some(CsfLanguageEnum::XX)
+        );
+        assert_eq!(CsfLanguageEnum::from_tag("x-csf-"), None);
+    }
+
+    #[test]
+    fn version_from() {
+        use rust_alert::csf::CsfVersionEnum;
+
+        assert_eq!(CsfVersionEnum::from(3), CsfVersionEnum::Cnc);
+        assert_eq!(CsfVersionEnum::from(4), CsfVersionEnum::Unknown(4));
+        assert_eq!(u32::from(CsfVersionEnum::Unknown(4)), 4);
+    }
+
+    #[test]
+    fn language_from() {
+        use rust_alert::csf::CsfLanguageEnum;
+
+        assert_eq!(CsfLanguageEnum::from(2), CsfLanguageEnum::DE);
+        assert_eq!(CsfLanguageEnum::from(42), CsfLanguageEnum::Unknown(42));
+        assert_eq!(u32::from(CsfLanguageEnum::Unknown(42)), 42);
     }
 }
 
@@ -136,23 +367,26 @@ mod coverage {
         use crate::csf::CsfVersionEnum;
 
         #[test]
-        fn try_from() {
+        fn from() {
             for (e, i) in [(CsfVersionEnum::Cnc, 3), (CsfVersionEnum::Nox, 2)] {
-                let result: Result<u32, _> = e.try_into();
-                assert!(result.is_ok());
-                assert_eq!(result.unwrap(), i);
-                let result: Result<CsfVersionEnum, _> = i.try_into();
-                assert!(result.is_ok());
-                assert_eq!(result.unwrap(), e);
+                assert_eq!(u32::from(e), i);
+                assert_eq!(CsfVersionEnum::from(i), e);
             }
 
-            let result: Result<CsfVersionEnum, _> = 255.try_into();
-            assert!(result.is_err());
+            for i in [0, 1, 4, 255, u32::MAX] {
+                let e = CsfVersionEnum::from(i);
+                assert_eq!(e, CsfVersionEnum::Unknown(i));
+                assert_eq!(u32::from(e), i);
+            }
         }
 
         #[test]
         fn display() {
-            for e in [CsfVersionEnum::Cnc, CsfVersionEnum::Nox] {
+            for e in [
+                CsfVersionEnum::Cnc,
+                CsfVersionEnum::Nox,
+                CsfVersionEnum::Unknown(255),
+            ] {
                 assert!(!format!("{e}").is_empty());
             }
         }
@@ -161,50 +395,62 @@ mod coverage {
     mod csf_language_enum {
         use crate::csf::CsfLanguageEnum;
 
+        const KNOWN: [CsfLanguageEnum; 10] = [
+            CsfLanguageEnum::ENUS,
+            CsfLanguageEnum::ENUK,
+            CsfLanguageEnum::DE,
+            CsfLanguageEnum::FR,
+            CsfLanguageEnum::ES,
+            CsfLanguageEnum::IT,
+            CsfLanguageEnum::JA,
+            CsfLanguageEnum::XX,
+            CsfLanguageEnum::KO,
+            CsfLanguageEnum::ZHCN,
+        ];
+
         #[test]
-        fn try_from() {
-            for (i, e) in [
-                CsfLanguageEnum::ENUS,
-                CsfLanguageEnum::ENUK,
-                CsfLanguageEnum::DE,
-                CsfLanguageEnum::FR,
-                CsfLanguageEnum::ES,
-                CsfLanguageEnum::IT,
-                CsfLanguageEnum::JA,
-                CsfLanguageEnum::XX,
-                CsfLanguageEnum::KO,
-                CsfLanguageEnum::ZHCN,
-            ]
-            .into_iter()
-            .enumerate()
-            {
+        fn from() {
+            for (i, e) in KNOWN.into_iter().enumerate() {
                 let i = i as u32;
-                let result: Result<u32, _> = e.try_into();
-                assert!(result.is_ok());
-                assert_eq!(result.unwrap(), i);
-                let result: Result<CsfLanguageEnum, _> = i.try_into();
-                assert!(result.is_ok());
-                assert_eq!(result.unwrap(), e);
+                assert_eq!(u32::from(e), i);
+                assert_eq!(CsfLanguageEnum::from(i), e);
             }
 
-            let result: Result<CsfLanguageEnum, _> = 255.try_into();
-            assert!(result.is_err());
+            for i in [10, 255, u32::MAX] {
+                let e = CsfLanguageEnum::from(i);
+                assert_eq!(e, CsfLanguageEnum::Unknown(i));
+                assert_eq!(u32::from(e), i);
+            }
         }
 
         #[test]
-        fn display() {
-            for e in [
-                CsfLanguageEnum::ENUS,
-                CsfLanguageEnum::ENUK,
-                CsfLanguageEnum::DE,
-                CsfLanguageEnum::FR,
-                CsfLanguageEnum::ES,
-                CsfLanguageEnum::IT,
-                CsfLanguageEnum::JA,
-                CsfLanguageEnum::XX,
-                CsfLanguageEnum::KO,
-                CsfLanguageEnum::ZHCN,
+        fn tag() {
+            for e in KNOWN.into_iter().chain([CsfLanguageEnum::Unknown(100)]) {
+                assert_eq!(CsfLanguageEnum::from_tag(&e.tag()), Some(e));
+            }
+        }
+
+        #[test]
+        fn from_code() {
+            for (code, e) in [
+                ("DE_de", Some(CsfLanguageEnum::DE)),
+                ("fr-CA", Some(CsfLanguageEnum::FR)),
+                ("es_MX.UTF-8", Some(CsfLanguageEnum::ES)),
+                ("it@euro", Some(CsfLanguageEnum::IT)),
+                ("ja-Jpan-JP", Some(CsfLanguageEnum::JA)),
+                ("en-US", Some(CsfLanguageEnum::ENUS)),
+                ("en_gb.UTF-8", Some(CsfLanguageEnum::ENUK)),
+                ("en", None),
+                ("zh-TW", None),
+                ("", None),
             ] {
+                assert_eq!(CsfLanguageEnum::from_code(code), e, "{code}");
+            }
+        }
+
+        #[test]
+        fn display() {
+            for e in KNOWN.into_iter().chain([CsfLanguageEnum::Unknown(255)]) {
                 assert!(!format!("{e}").is_empty());
             }
         }
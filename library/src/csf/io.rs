@@ -29,21 +29,22 @@ pub enum Error {
     /// Data is not a valid UTF-16 string.
     #[error("{0}")]
     Utf16(#[from] FromUtf16Error),
-    /// A [`rust_alert::csf::Error`][crate::csf::Error].
-    #[error("{0}")]
-    CSF(#[from] crate::csf::Error),
 }
 
 #[doc(hidden)]
 type Result<T> = std::result::Result<T, Error>;
 
 /// Storage for static strings.
-struct CsfPrefixes {}
+pub struct CsfPrefixes {}
 impl CsfPrefixes {
-    const CSF_PREFIX: &'static [u8] = b" FSC";
-    const LBL_PREFIX: &'static [u8] = b" LBL";
-    const STR_PREFIX: &'static [u8] = b" RTS";
-    const STRW_PREFIX: &'static [u8] = b"WRTS";
+    /// Magic prefix of a CSF file.
+    pub const CSF_PREFIX: &'static [u8] = b" FSC";
+    /// Magic prefix of a CSF label.
+    pub const LBL_PREFIX: &'static [u8] = b" LBL";
+    /// Magic prefix of a CSF string.
+    pub const STR_PREFIX: &'static [u8] = b" RTS";
+    /// Magic prefix of a CSF string with extra value.
+    pub const STRW_PREFIX: &'static [u8] = b"WRTS";
 }
 
 /// Contains methods to read CSF strings, labels and stringtables from bytes.
@@ -269,7 +270,7 @@ impl CsfRead for CsfReader {
         let mut buf = [0u8; size_of::<u32>()];
 
         reader.read_exact(&mut buf)?;
-        csf.version = u32::from_le_bytes(buf).try_into()?;
+        csf.version = u32::from_le_bytes(buf).into();
 
         reader.read_exact(&mut buf)?;
         let num_labels = u32::from_le_bytes(buf);
@@ -281,7 +282,7 @@ impl CsfRead for CsfReader {
         csf.extra = u32::from_le_bytes(buf);
 
         reader.read_exact(&mut buf)?;
-        csf.language = u32::from_le_bytes(buf).try_into()?;
+        csf.language = u32::from_le_bytes(buf).into();
 
         Ok((csf, num_labels))
     }
@@ -342,11 +343,11 @@ impl CsfRead for CsfReader {
 impl CsfWrite for CsfReader {
     fn write_header(&mut self, csf: &CsfStringtable, writer: &mut dyn Write) -> Result<()> {
         writer.write_all(CsfPrefixes::CSF_PREFIX)?;
-        writer.write_all(&TryInto::<u32>::try_into(csf.version)?.to_le_bytes())?;
+        writer.write_all(&u32::from(csf.version).to_le_bytes())?;
         writer.write_all(&(csf.len() as u32).to_le_bytes())?;
         writer.write_all(&(csf.strings_len() as u32).to_le_bytes())?;
         writer.write_all(&csf.extra.to_le_bytes())?;
-        writer.write_all(&TryInto::<u32>::try_into(csf.language)?.to_le_bytes())?;
+        writer.write_all(&u32::from(csf.language).to_le_bytes())?;
 
         Ok(())
     }
@@ -390,12 +391,12 @@ impl CsfWrite for CsfReader {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, io::Read};
+    use std::io::Read;
 
     use crate::{
         csf::{
             io::{CsfPrefixes, CsfRead, CsfReader, CsfWrite, Error},
-            CsfLabel, CsfString, CsfStringtable,
+            CsfLabel, CsfLanguageEnum, CsfString, CsfStringtable, CsfVersionEnum,
         },
         unwrap_assert,
     };
@@ -601,17 +602,55 @@ mod tests {
         let string = "String";
         let buf = make_stringtable(label, string, "");
         let reader: &mut dyn Read = &mut buf.as_slice();
-        let mut labels: HashSet<CsfLabel> = Default::default();
-        labels.insert(CsfLabel::new(label, string));
-
         let mut expected = CsfStringtable::default();
-        expected.extend(labels);
+        expected.insert(CsfLabel::new(label, string));
         let actual = CsfReader::new().read(reader);
 
         assert!(actual.is_ok());
         unwrap_assert!(actual, expected);
     }
 
+    /// Read and write a CsfStringtable, keeping label order byte for byte.
+    #[test]
+    fn read_write_stringtable_order() {
+        let names = [
+            "Zeta", "Alpha", "Mid", "Beta", "Omega", "Gamma", "Delta", "Kappa",
+        ];
+        let mut buf = vec![b' ', b'F', b'S', b'C', 3, 0, 0, 0];
+        buf.extend_from_slice(&(names.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&(names.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&[0; 8]);
+        for (i, name) in names.iter().enumerate() {
+            buf.extend(make_label(*name, i.to_string(), ""));
+        }
+        let reader: &mut dyn Read = &mut buf.as_slice();
+
+        let csf = CsfReader::new().read(reader).unwrap();
+        let actual: Vec<_> = csf.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(actual, names);
+
+        let mut written = vec![];
+        CsfReader::new().write(&csf, &mut written).unwrap();
+        assert_eq!(written, buf);
+    }
+
+    /// Read and write a stringtable with unknown version and language.
+    #[test]
+    fn read_write_unknown_header() {
+        let mut buf = vec![b' ', b'F', b'S', b'C', 4, 0, 0, 0];
+        buf.extend_from_slice(&[0; 12]);
+        buf.extend_from_slice(&42u32.to_le_bytes());
+        let reader: &mut dyn Read = &mut buf.as_slice();
+
+        let csf = CsfReader::new().read(reader).unwrap();
+        assert_eq!(csf.version, CsfVersionEnum::Unknown(4));
+        assert_eq!(csf.language, CsfLanguageEnum::Unknown(42));
+
+        let mut written = vec![];
+        CsfReader::new().write(&csf, &mut written).unwrap();
+        assert_eq!(written, buf);
+    }
+
     /// Write a CsfString (Ok).
     #[test]
     fn write_string_ok() {
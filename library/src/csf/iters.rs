@@ -1,8 +1,6 @@
 //! Iterators for [`CsfStringtable`].
 
-use std::collections::hash_set::{
-    Drain as HashSetDrain, IntoIter as HashSetIntoIter, Iter as HashSetIter,
-};
+use indexmap::set::{Drain as IndexSetDrain, IntoIter as IndexSetIntoIter, Iter as IndexSetIter};
 
 use crate::csf::CsfLabel;
 
@@ -21,7 +19,7 @@ use crate::csf::CsfLabel;
 /// let mut iter = csf.iter();
 /// ```
 pub struct Iter<'a> {
-    iter: HashSetIter<'a, CsfLabel>,
+    iter: IndexSetIter<'a, CsfLabel>,
 }
 
 impl<'a> Iterator for Iter<'a> {
@@ -32,8 +30,8 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
-impl<'a> From<HashSetIter<'a, CsfLabel>> for Iter<'a> {
-    fn from(iter: HashSetIter<'a, CsfLabel>) -> Self {
+impl<'a> From<IndexSetIter<'a, CsfLabel>> for Iter<'a> {
+    fn from(iter: IndexSetIter<'a, CsfLabel>) -> Self {
         Self { iter }
     }
 }
@@ -54,7 +52,7 @@ impl<'a> From<HashSetIter<'a, CsfLabel>> for Iter<'a> {
 /// let mut iter = csf.into_iter();
 /// ```
 pub struct IntoIter {
-    iter: HashSetIntoIter<CsfLabel>,
+    iter: IndexSetIntoIter<CsfLabel>,
 }
 
 impl Iterator for IntoIter {
@@ -65,8 +63,8 @@ impl Iterator for IntoIter {
     }
 }
 
-impl From<HashSetIntoIter<CsfLabel>> for IntoIter {
-    fn from(iter: HashSetIntoIter<CsfLabel>) -> Self {
+impl From<IndexSetIntoIter<CsfLabel>> for IntoIter {
+    fn from(iter: IndexSetIntoIter<CsfLabel>) -> Self {
         Self { iter }
     }
 }
@@ -86,7 +84,7 @@ impl From<HashSetIntoIter<CsfLabel>> for IntoIter {
 /// let mut iter = csf.drain();
 /// ```
 pub struct Drain<'a> {
-    drain: HashSetDrain<'a, CsfLabel>,
+    drain: IndexSetDrain<'a, CsfLabel>,
 }
 
 impl<'a> Iterator for Drain<'a> {
@@ -97,8 +95,8 @@ impl<'a> Iterator for Drain<'a> {
     }
 }
 
-impl<'a> From<HashSetDrain<'a, CsfLabel>> for Drain<'a> {
-    fn from(drain: HashSetDrain<'a, CsfLabel>) -> Self {
+impl<'a> From<IndexSetDrain<'a, CsfLabel>> for Drain<'a> {
+    fn from(drain: IndexSetDrain<'a, CsfLabel>) -> Self {
         Self { drain }
     }
 }
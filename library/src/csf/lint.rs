@@ -0,0 +1,230 @@
+//! Checks of stringtables against engine limits.
+
+use std::collections::HashMap;
+
+use crate::csf::{string_name, CsfStringtable};
+
+/// Settings of [`CsfStringtable::lint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsfLintOptions {
+    /// Size of the engine buffer strings are copied to, in UTF-16 code units
+    /// including the terminating null. Default is 1024; set it to the buffer size
+    /// of the engine version you target.
+    pub string_buffer_size: usize,
+}
+
+impl Default for CsfLintOptions {
+    fn default() -> Self {
+        Self {
+            string_buffer_size: 1024,
+        }
+    }
+}
+
+/// A problem found in a stringtable by [`CsfStringtable::lint`]. Strings are referred
+/// to by their label name and index in the label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsfLintEnum {
+    /// Label name has characters other than printable ASCII.
+    NonAsciiLabel(String),
+    /// Label name is empty.
+    EmptyLabelName,
+    /// Label names differ only in case, so the game finds only one of them.
+    CaseDuplicate(String, String),
+    /// Label has no strings, or only empty ones.
+    EmptyLabel(String),
+    /// String with given length in UTF-16 code units doesn't fit the engine buffer.
+    StringTooLong(String, usize, usize),
+    /// String has a character outside the Basic Multilingual Plane. It's written
+    /// as a surrogate pair, which the game can't render.
+    NonBmpCharacter(String, usize, char),
+    /// String has a control character other than a newline or carriage return.
+    ControlCharacter(String, usize, char),
+}
+
+impl std::fmt::Display for CsfLintEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonAsciiLabel(label) => {
+                write!(f, "{label:?}: label name is not printable ASCII")
+            }
+            Self::EmptyLabelName => write!(f, "Label name is empty"),
+            Self::CaseDuplicate(a, b) => {
+                write!(f, "{b}: label differs from {a} only in case")
+            }
+            Self::EmptyLabel(label) => write!(f, "{label}: label has no text"),
+            Self::StringTooLong(label, i, len) => write!(
+                f,
+                "{}: string is {} UTF-16 code units long",
+                string_name(label, *i),
+                len
+            ),
+            Self::NonBmpCharacter(label, i, c) => write!(
+                f,
+                "{}: character U+{:04X} is outside the BMP",
+                string_name(label, *i),
+                *c as u32
+            ),
+            Self::ControlCharacter(label, i, c) => write!(
+                f,
+                "{}: control character U+{:04X}",
+                string_name(label, *i),
+                *c as u32
+            ),
+        }
+    }
+}
+
+impl CsfStringtable {
+    /// Check the stringtable for problems the game has with labels and strings.
+    /// Every non-BMP or control character is reported once per string.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::csf::{CsfLintEnum, CsfLintOptions, CsfStringtable};
+    ///
+    /// let mut csf = CsfStringtable::default();
+    /// csf.create("GUI:Quit", "Quit");
+    /// csf.create("GUI:QUIT", "Quit");
+    /// csf.create("GUI:Tank", "🚜");
+    /// let issues = csf.lint(&CsfLintOptions::default());
+    ///
+    /// assert_eq!(
+    ///     issues,
+    ///     vec![
+    ///         CsfLintEnum::CaseDuplicate("GUI:Quit".to_string(), "GUI:QUIT".to_string()),
+    ///         CsfLintEnum::NonBmpCharacter("GUI:Tank".to_string(), 0, '🚜'),
+    ///     ]
+    /// );
+    /// ```
+    pub fn lint(&self, options: &CsfLintOptions) -> Vec<CsfLintEnum> {
+        let mut issues = vec![];
+        let mut names: HashMap<String, &str> = HashMap::new();
+        for label in self.iter() {
+            let name = &label.name;
+            if name.is_empty() {
+                issues.push(CsfLintEnum::EmptyLabelName);
+            } else if !name.chars().all(|c| (' '..='~').contains(&c)) {
+                issues.push(CsfLintEnum::NonAsciiLabel(name.clone()));
+            }
+            match names.get(&name.to_ascii_lowercase()) {
+                Some(first) => {
+                    issues.push(CsfLintEnum::CaseDuplicate(first.to_string(), name.clone()))
+                }
+                None => {
+                    names.insert(name.to_ascii_lowercase(), name);
+                }
+            }
+            if label.strings.iter().all(|x| x.value.is_empty()) {
+                issues.push(CsfLintEnum::EmptyLabel(name.clone()));
+            }
+            for (i, string) in label.strings.iter().enumerate() {
+                let len = string.value.encode_utf16().count();
+                if len >= options.string_buffer_size {
+                    issues.push(CsfLintEnum::StringTooLong(name.clone(), i, len));
+                }
+                if let Some(c) = string.value.chars().find(|c| *c > '\u{FFFF}') {
+                    issues.push(CsfLintEnum::NonBmpCharacter(name.clone(), i, c));
+                }
+                let control = |c: &char| c.is_control() && !matches!(c, '\n' | '\r');
+                if let Some(c) = string.value.chars().find(control) {
+                    issues.push(CsfLintEnum::ControlCharacter(name.clone(), i, c));
+                }
+            }
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn lint() {
+        use rust_alert::csf::{CsfLintEnum, CsfLintOptions, CsfStringtable};
+
+        let mut csf = CsfStringtable::default();
+        csf.create("GUI:Quit", "Quit");
+        csf.create("GUI:QUIT", "Quit");
+        csf.create("GUI:Tank", "🚜");
+        let issues = csf.lint(&CsfLintOptions::default());
+
+        assert_eq!(
+            issues,
+            vec![
+                CsfLintEnum::CaseDuplicate("GUI:Quit".to_string(), "GUI:QUIT".to_string()),
+                CsfLintEnum::NonBmpCharacter("GUI:Tank".to_string(), 0, '🚜'),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::csf::{CsfLabel, CsfLintEnum, CsfLintOptions, CsfString, CsfStringtable};
+
+    #[test]
+    fn lint() {
+        let mut csf = CsfStringtable::default();
+        csf.create("", "No name");
+        csf.create("Grüße", "Hello");
+        csf.create("Tab\tName", "Hello");
+        csf.insert(CsfLabel {
+            name: "NoStrings".to_string(),
+            strings: vec![],
+        });
+        csf.insert(CsfLabel {
+            name: "Multi".to_string(),
+            strings: vec![
+                CsfString::new("Line\r\nLine"),
+                CsfString::new("Bell\u{7}\u{7}"),
+                CsfString::new("x".repeat(8)),
+            ],
+        });
+        csf.create("Empty", "");
+        csf.create("A:a", "1");
+        csf.create("a:A", "2");
+        csf.create("a:a", "3");
+        let options = CsfLintOptions {
+            string_buffer_size: 8,
+        };
+
+        assert_eq!(
+            csf.lint(&options),
+            vec![
+                CsfLintEnum::EmptyLabelName,
+                CsfLintEnum::NonAsciiLabel("Grüße".to_string()),
+                CsfLintEnum::NonAsciiLabel("Tab\tName".to_string()),
+                CsfLintEnum::EmptyLabel("NoStrings".to_string()),
+                CsfLintEnum::StringTooLong("Multi".to_string(), 0, 10),
+                CsfLintEnum::ControlCharacter("Multi".to_string(), 1, '\u{7}'),
+                CsfLintEnum::StringTooLong("Multi".to_string(), 2, 8),
+                CsfLintEnum::EmptyLabel("Empty".to_string()),
+                CsfLintEnum::CaseDuplicate("A:a".to_string(), "a:A".to_string()),
+                CsfLintEnum::CaseDuplicate("A:a".to_string(), "a:a".to_string()),
+            ]
+        );
+        assert_eq!(
+            CsfLintEnum::StringTooLong("Multi".to_string(), 2, 8).to_string(),
+            "Multi|2: string is 8 UTF-16 code units long"
+        );
+    }
+
+    #[test]
+    fn utf16_length() {
+        let mut csf = CsfStringtable::default();
+        csf.create("A", "🚜🚜");
+        let options = CsfLintOptions {
+            string_buffer_size: 4,
+        };
+        assert_eq!(
+            csf.lint(&options),
+            vec![
+                CsfLintEnum::StringTooLong("A".to_string(), 0, 4),
+                CsfLintEnum::NonBmpCharacter("A".to_string(), 0, '🚜'),
+            ]
+        );
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! //! Also see the [ModEnc page for CSF file format](https://modenc.renegadeprojects.com/CSF_File_Format).
 
+mod check;
 mod core;
+mod diff;
 mod enums;
 pub mod io;
 mod iters;
+mod lint;
 
+pub use check::*;
 pub use core::*;
+pub use diff::*;
 pub use enums::*;
 pub use iters::*;
+pub use lint::*;
@@ -5,4 +5,8 @@ pub mod core;
 pub mod csf;
 pub mod ini;
 pub mod mix;
+pub mod po;
+pub mod table;
 pub mod utils;
+#[cfg(feature = "xliff")]
+pub mod xliff;
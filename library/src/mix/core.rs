@@ -5,7 +5,7 @@ use std::{fs::read, path::Path};
 use indexmap::IndexMap;
 use sha1::{Digest, Sha1};
 
-use crate::core::{crc, GameEnum};
+use crate::core::{crc, GameEnum, IndexOrderEnum};
 use crate::utils::{path_to_filename, PathToStringError};
 
 /// Size of a Blowfish key used in MIX encryption.
@@ -114,6 +114,44 @@ pub struct Mix {
     pub blowfish_key: Option<BlowfishKey>,
     /// Optional, SHA1 checksum of the entire MIX body. Always 20 bytes long. Used in RA and up.
     pub checksum: Option<Checksum>,
+    /// Order of IDs in the index expected by the target game. The index is sorted this way when writing.
+    pub index_order: IndexOrderEnum,
+}
+
+/// A problem found in a MIX by [`Mix::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MixIssueEnum {
+    /// Two neighbouring index entries are not in the expected order, so the game won't find some files.
+    UnorderedIndex(IndexOrderEnum, i32, i32),
+    /// A file extends past the end of the body.
+    FileOutOfBounds(i32),
+    /// Two files share some bytes of the body.
+    OverlappingFiles(i32, i32),
+    /// Body size declared in the header doesn't match the actual one.
+    BodySizeMismatch(u32, usize),
+}
+
+impl std::fmt::Display for MixIssueEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MixIssueEnum::UnorderedIndex(order, a, b) => write!(
+                f,
+                "Index is not in {:?} order: {:0>8X} comes before {:0>8X}",
+                order, a, b
+            ),
+            MixIssueEnum::FileOutOfBounds(id) => {
+                write!(f, "File {:0>8X} extends past the end of the body", id)
+            }
+            MixIssueEnum::OverlappingFiles(a, b) => {
+                write!(f, "Files {:0>8X} and {:0>8X} overlap", a, b)
+            }
+            MixIssueEnum::BodySizeMismatch(declared, actual) => write!(
+                f,
+                "Declared body size is {} bytes, but actual is {} bytes",
+                declared, actual
+            ),
+        }
+    }
 }
 
 impl Mix {
@@ -305,6 +343,96 @@ impl Mix {
         self.index.sort_keys();
     }
 
+    /// Sort MIX index by ascending ID, compared like the target game does (see [`Mix::index_order`]).
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::IndexOrderEnum, mix::Mix};
+    ///
+    /// let mut mix = Mix::default();
+    /// let _ = mix.add_file_raw(vec![0], -1, false);
+    /// let _ = mix.add_file_raw(vec![0], 1, false);
+    /// mix.sort_by_index_order();
+    /// assert_eq!(mix.index.first().unwrap().0, &1);
+    /// mix.index_order = IndexOrderEnum::Signed;
+    /// mix.sort_by_index_order();
+    /// assert_eq!(mix.index.first().unwrap().0, &-1);
+    /// ```
+    pub fn sort_by_index_order(&mut self) {
+        let order = self.index_order;
+        self.index.sort_by(|id1, _, id2, _| order.cmp(*id1, *id2));
+    }
+
+    /// Check if the MIX index is sorted by ascending ID in given order.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::IndexOrderEnum, mix::Mix};
+    ///
+    /// let mut mix = Mix::default();
+    /// let _ = mix.add_file_raw(vec![0], -1, false);
+    /// let _ = mix.add_file_raw(vec![0], 1, false);
+    /// assert!(mix.is_index_ordered(IndexOrderEnum::Signed));
+    /// assert!(!mix.is_index_ordered(IndexOrderEnum::Unsigned));
+    /// ```
+    pub fn is_index_ordered(&self, order: IndexOrderEnum) -> bool {
+        self.find_unordered(order).is_none()
+    }
+
+    /// Check the MIX for problems which would make the game fail to load its files.
+    /// The index is expected to be in the order given in [`Mix::index_order`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::IndexOrderEnum, mix::{Mix, MixIssueEnum}};
+    ///
+    /// let mut mix = Mix::default();
+    /// let _ = mix.add_file_raw(vec![0], -1, false);
+    /// let _ = mix.add_file_raw(vec![0], 1, false);
+    /// mix.declared_body_size = 2;
+    /// assert_eq!(mix.validate(), vec![MixIssueEnum::UnorderedIndex(IndexOrderEnum::Unsigned, -1, 1)]);
+    /// mix.sort_by_index_order();
+    /// assert!(mix.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<MixIssueEnum> {
+        let mut issues = vec![];
+        if let Some((a, b)) = self.find_unordered(self.index_order) {
+            issues.push(MixIssueEnum::UnorderedIndex(self.index_order, a, b));
+        }
+        let mut files: Vec<_> = self.index.values().collect();
+        for file in &files {
+            if file.offset as usize + file.size as usize > self.body.len() {
+                issues.push(MixIssueEnum::FileOutOfBounds(file.id));
+            }
+        }
+        // Empty files can share offsets with anything.
+        files.retain(|f| f.size > 0);
+        files.sort_by_key(|f| (f.offset, f.size));
+        for pair in files.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if a.offset as u64 + a.size as u64 > b.offset as u64 {
+                issues.push(MixIssueEnum::OverlappingFiles(a.id, b.id));
+            }
+        }
+        if self.declared_body_size as usize != self.body.len() {
+            let issue = MixIssueEnum::BodySizeMismatch(self.declared_body_size, self.body.len());
+            issues.push(issue);
+        }
+        issues
+    }
+
+    /// Find the first pair of neighbouring IDs which are not in given order.
+    fn find_unordered(&self, order: IndexOrderEnum) -> Option<(i32, i32)> {
+        self.index
+            .keys()
+            .zip(self.index.keys().skip(1))
+            .find(|(a, b)| order.cmp(**a, **b).is_ge())
+            .map(|(a, b)| (*a, *b))
+    }
+
     /// Sort MIX index by ascending offset.
     /// 
     /// # Examples
@@ -675,6 +803,50 @@ mod examples {
         assert_eq!(mix.index.first().unwrap().0, &1);
     }
 
+    #[test]
+    fn sort_by_index_order() {
+        use rust_alert::{core::IndexOrderEnum, mix::Mix};
+
+        let mut mix = Mix::default();
+        let _ = mix.add_file_raw(vec![0], -1, false);
+        let _ = mix.add_file_raw(vec![0], 1, false);
+        mix.sort_by_index_order();
+        assert_eq!(mix.index.first().unwrap().0, &1);
+        mix.index_order = IndexOrderEnum::Signed;
+        mix.sort_by_index_order();
+        assert_eq!(mix.index.first().unwrap().0, &-1);
+    }
+
+    #[test]
+    fn is_index_ordered() {
+        use rust_alert::{core::IndexOrderEnum, mix::Mix};
+
+        let mut mix = Mix::default();
+        let _ = mix.add_file_raw(vec![0], -1, false);
+        let _ = mix.add_file_raw(vec![0], 1, false);
+        assert!(mix.is_index_ordered(IndexOrderEnum::Signed));
+        assert!(!mix.is_index_ordered(IndexOrderEnum::Unsigned));
+    }
+
+    #[test]
+    fn validate() {
+        use rust_alert::{
+            core::IndexOrderEnum,
+            mix::{Mix, MixIssueEnum},
+        };
+
+        let mut mix = Mix::default();
+        let _ = mix.add_file_raw(vec![0], -1, false);
+        let _ = mix.add_file_raw(vec![0], 1, false);
+        mix.declared_body_size = 2;
+        assert_eq!(
+            mix.validate(),
+            vec![MixIssueEnum::UnorderedIndex(IndexOrderEnum::Unsigned, -1, 1)]
+        );
+        mix.sort_by_index_order();
+        assert!(mix.validate().is_empty());
+    }
+
     #[test]
     fn sort_by_offset() {
         use rust_alert::mix::Mix;
@@ -822,3 +994,29 @@ mod coverage {
         assert_eq!(u16::from(MixHeaderExtraFlags::NONE), 0x0000);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::mix::{Mix, MixIndexEntry, MixIssueEnum};
+
+    #[test]
+    fn validate_body() {
+        let mut mix = Mix {
+            body: vec![0; 4],
+            declared_body_size: 5,
+            ..Default::default()
+        };
+        mix.index.insert(1, MixIndexEntry::new(1, 0, 2));
+        mix.index.insert(2, MixIndexEntry::new(2, 1, 2));
+        mix.index.insert(3, MixIndexEntry::new(3, 3, 2));
+        mix.index.insert(4, MixIndexEntry::new(4, 3, 0));
+        assert_eq!(
+            mix.validate(),
+            vec![
+                MixIssueEnum::FileOutOfBounds(3),
+                MixIssueEnum::OverlappingFiles(1, 2),
+                MixIssueEnum::BodySizeMismatch(5, 4),
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Name databases compiled in from `resources/gmd.*.ini`.
+//!
+//! Every table is a list of IDs and names sorted by ID, so lookups don't need
+//! to build a hash map first. All candidate names of an ID are kept next to each other,
+//! primary first.
+
+use crate::{
+    core::GameEnum,
+    mix::db::{GlobalMixDatabase, MixDatabase, MixDatabaseEntry, NameSourceEnum},
+};
+
+include!(concat!(env!("OUT_DIR"), "/builtin_gmd.rs"));
+
+/// Returns the built-in table for given game, along with the game it was made for.
+/// FS uses the TS table and RA2 uses the YR table.
+fn table(game: GameEnum) -> (GameEnum, &'static [(i32, &'static str)]) {
+    match game {
+        GameEnum::TD => (GameEnum::TD, BUILTIN_TD),
+        GameEnum::RA => (GameEnum::RA, BUILTIN_RA),
+        GameEnum::TS | GameEnum::FS => (GameEnum::TS, BUILTIN_TS),
+        GameEnum::RA2 | GameEnum::YR => (GameEnum::YR, BUILTIN_YR),
+    }
+}
+
+/// Look up the primary name in the built-in database of given game, without building
+/// a [`MixDatabase`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{core::{crc, GameEnum}, mix::db::builtin_name};
+///
+/// assert_eq!(builtin_name(crc("trex.shp", GameEnum::TD), GameEnum::TD), Some("trex.shp"));
+/// assert_eq!(builtin_name(0, GameEnum::TD), None);
+/// ```
+pub fn builtin_name(id: i32, game: GameEnum) -> Option<&'static str> {
+    builtin_names(id, game).next()
+}
+
+/// Look up all candidate names in the built-in database of given game, primary first.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{core::{crc, GameEnum}, mix::db::builtin_names};
+///
+/// let id = crc("trex.shp", GameEnum::TD);
+/// assert_eq!(builtin_names(id, GameEnum::TD).collect::<Vec<_>>(), vec!["trex.shp"]);
+/// assert_eq!(builtin_names(0, GameEnum::TD).count(), 0);
+/// ```
+pub fn builtin_names(id: i32, game: GameEnum) -> impl Iterator<Item = &'static str> {
+    let (_, table) = table(game);
+    let start = table.partition_point(|x| x.0 < id);
+    table[start..]
+        .iter()
+        .take_while(move |x| x.0 == id)
+        .map(|x| x.1)
+}
+
+impl GlobalMixDatabase {
+    /// Returns the built-in database for given game, made from the bundled `resources/gmd.*.ini`
+    /// files. FS shares the TS database and RA2 shares the YR one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::{crc, GameEnum}, mix::db::GlobalMixDatabase};
+    ///
+    /// let gmd = GlobalMixDatabase::builtin(GameEnum::RA2);
+    /// assert_eq!(gmd.dbs[0].game, Some(GameEnum::YR));
+    /// let id = crc("yacnst_b.shp", GameEnum::RA2);
+    /// assert_eq!(gmd.get_name(id, GameEnum::RA2).unwrap(), "yacnst_b.shp");
+    /// ```
+    pub fn builtin(game: GameEnum) -> Self {
+        let (game, table) = table(game);
+        let mut db = MixDatabase::with_game(game);
+        db.entries.reserve(table.len());
+        for (id, name) in table {
+            db.add_entry(
+                *id,
+                MixDatabaseEntry::new(*name).with_source(NameSourceEnum::Gmd),
+            );
+        }
+        Self { dbs: vec![db] }
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn builtin_name() {
+        use rust_alert::{
+            core::{crc, GameEnum},
+            mix::db::builtin_name,
+        };
+
+        assert_eq!(
+            builtin_name(crc("trex.shp", GameEnum::TD), GameEnum::TD),
+            Some("trex.shp")
+        );
+        assert_eq!(builtin_name(0, GameEnum::TD), None);
+    }
+
+    #[test]
+    fn builtin_names() {
+        use rust_alert::{
+            core::{crc, GameEnum},
+            mix::db::builtin_names,
+        };
+
+        let id = crc("trex.shp", GameEnum::TD);
+        assert_eq!(
+            builtin_names(id, GameEnum::TD).collect::<Vec<_>>(),
+            vec!["trex.shp"]
+        );
+        assert_eq!(builtin_names(0, GameEnum::TD).count(), 0);
+    }
+
+    #[test]
+    fn builtin() {
+        use rust_alert::{
+            core::{crc, GameEnum},
+            mix::db::GlobalMixDatabase,
+        };
+
+        let gmd = GlobalMixDatabase::builtin(GameEnum::RA2);
+        assert_eq!(gmd.dbs[0].game, Some(GameEnum::YR));
+        let id = crc("yacnst_b.shp", GameEnum::RA2);
+        assert_eq!(gmd.get_name(id, GameEnum::RA2).unwrap(), "yacnst_b.shp");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{crc, GameEnum},
+        mix::db::{
+            builtin::{builtin_name, builtin_names, table},
+            GlobalMixDatabase,
+        },
+    };
+
+    #[test]
+    fn builtin_tables() {
+        for game in [GameEnum::TD, GameEnum::RA, GameEnum::TS, GameEnum::YR] {
+            let (table_game, table) = table(game);
+            assert_eq!(table_game, game);
+            assert!(!table.is_empty());
+            assert!(table.windows(2).all(|x| x[0].0 <= x[1].0));
+            assert!(table.iter().all(|(id, name)| crc(name, game) == *id));
+        }
+    }
+
+    #[test]
+    fn builtin_candidates() {
+        // All candidates of an ID are in the table and the database, primary first.
+        for game in [GameEnum::TD, GameEnum::RA, GameEnum::TS, GameEnum::YR] {
+            let (_, table) = table(game);
+            let gmd = GlobalMixDatabase::builtin(game);
+            let db = &gmd.dbs[0];
+            assert_eq!(db.iter_all().count(), table.len());
+            for (id, _) in table {
+                let names: Vec<_> = db.get_all(*id).iter().map(|x| x.name.as_str()).collect();
+                assert_eq!(builtin_names(*id, game).collect::<Vec<_>>(), names);
+                assert_eq!(builtin_name(*id, game), names.first().copied());
+            }
+        }
+    }
+}
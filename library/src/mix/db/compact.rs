@@ -0,0 +1,399 @@
+//! Compact binary MIX database format.
+//!
+//! The file starts with a header, followed by a table of fixed-size records sorted by ID
+//! and a pool of UTF-8 strings the records point into. All numbers are little-endian u32s,
+//! so the file can be used straight from memory (or a memory map) and looked up with
+//! a binary search, without building a [`MixDatabase`] first.
+//!
+//! | Offset | Contents                                                   |
+//! |--------|------------------------------------------------------------|
+//! | 0      | [`COMPACT_MAGIC`]                                          |
+//! | 4      | Format version, [`COMPACT_VERSION`]                        |
+//! | 8      | Game, index into [`COMPACT_GAMES`] or `0xFFFFFFFF` if none |
+//! | 12     | Number of records                                          |
+//! | 16     | Size of the string pool                                    |
+//! | 20     | Records: ID, name offset, name size, description offset and size |
+//! | ...    | String pool                                                |
+//!
+//! IDs with several candidate names have one record per name, primary first.
+//! Empty descriptions are not stored. Name sources are not stored.
+
+use std::{borrow::Cow, io::Write};
+
+use crate::{
+    core::GameEnum,
+    mix::db::{MixDatabase, MixDatabaseEntry, MixDatabaseLookup},
+};
+
+/// Magic number every compact database starts with.
+pub const COMPACT_MAGIC: &[u8; 4] = b"RADB";
+/// Current version of the format.
+pub const COMPACT_VERSION: u32 = 1;
+/// Games, by their index stored in the header.
+pub const COMPACT_GAMES: &[GameEnum] = &[
+    GameEnum::TD,
+    GameEnum::RA,
+    GameEnum::TS,
+    GameEnum::FS,
+    GameEnum::RA2,
+    GameEnum::YR,
+];
+
+const HEADER_SIZE: usize = 20;
+const RECORD_SIZE: usize = 20;
+const NO_GAME: u32 = u32::MAX;
+
+/// The error type for compact MIX databases.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A [`std::io::Error`].
+    #[error("{0}")]
+    IO(#[from] std::io::Error),
+    /// The data doesn't start with [`COMPACT_MAGIC`].
+    #[error("Attempted to read a compact database, but the magic number didn't match")]
+    InvalidMagic,
+    /// The format version is not supported.
+    #[error("Unsupported compact database version: {0}")]
+    UnsupportedVersion(u32),
+    /// The game index is not valid.
+    #[error("Unknown game in compact database: {0}")]
+    UnknownGame(u32),
+    /// The data is shorter than the header says.
+    #[error("Expected at least {0} bytes, but got {1}")]
+    TooShort(usize, usize),
+    /// The string pool is not valid UTF-8.
+    #[error("{0}")]
+    Utf8Error(#[from] std::str::Utf8Error),
+    /// The database is too big for the format.
+    #[error("Database is too big for the compact format")]
+    TooBig,
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// A read-only view of a compact MIX database, borrowing its data.
+///
+/// Opening only checks the header and validates the string pool. Records pointing
+/// outside the pool are treated as missing.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactMixDatabase<'a> {
+    game: Option<GameEnum>,
+    table: &'a [u8],
+    pool: &'a str,
+}
+
+impl<'a> CompactMixDatabase<'a> {
+    /// Open a compact database stored in given data.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::{compact::{CompactMixDatabase, CompactMixDbWriter}, MixDatabase};
+    ///
+    /// let mut db = MixDatabase::default();
+    /// db.insert(1, "a.shp");
+    /// let mut data = vec![];
+    /// CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+    ///
+    /// let view = CompactMixDatabase::new(&data).unwrap();
+    /// assert_eq!(view.get_name(1), Some("a.shp"));
+    /// assert_eq!(view.get_name(2), None);
+    /// ```
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE {
+            return Err(Error::TooShort(HEADER_SIZE, data.len()));
+        }
+        if !data.starts_with(COMPACT_MAGIC) {
+            return Err(Error::InvalidMagic);
+        }
+        let version = read_u32(data, 4);
+        if version != COMPACT_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let game = match read_u32(data, 8) {
+            NO_GAME => None,
+            x => Some(*COMPACT_GAMES.get(x as usize).ok_or(Error::UnknownGame(x))?),
+        };
+        let count = read_u32(data, 12) as usize;
+        let pool_size = read_u32(data, 16) as usize;
+        let pool_start = HEADER_SIZE + count * RECORD_SIZE;
+        let size = pool_start + pool_size;
+        if data.len() < size {
+            return Err(Error::TooShort(size, data.len()));
+        }
+        Ok(Self {
+            game,
+            table: &data[HEADER_SIZE..pool_start],
+            pool: std::str::from_utf8(&data[pool_start..size])?,
+        })
+    }
+
+    /// Returns the game of the database.
+    pub fn game(&self) -> Option<GameEnum> {
+        self.game
+    }
+
+    /// Returns the number of records, counting every candidate name.
+    pub fn len(&self) -> usize {
+        self.table.len() / RECORD_SIZE
+    }
+
+    /// Returns true if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Get the primary name by ID.
+    pub fn get_name(&self, id: i32) -> Option<&'a str> {
+        self.get_all(id).next().map(|x| x.0)
+    }
+
+    /// Returns an iterator over all candidate names and descriptions by ID, primary first.
+    pub fn get_all(&self, id: i32) -> impl Iterator<Item = (&'a str, Option<&'a str>)> + '_ {
+        let start = self.partition_point(|x| x < id);
+        (start..self.len())
+            .take_while(move |i| self.id(*i) == id)
+            .filter_map(|i| self.record(i).map(|x| (x.1, x.2)))
+    }
+
+    /// Returns an iterator over all records, sorted by ID.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &'a str, Option<&'a str>)> + '_ {
+        (0..self.len()).filter_map(|i| self.record(i))
+    }
+
+    /// Copy the whole database into a [`MixDatabase`].
+    pub fn to_db(&self) -> MixDatabase {
+        let mut db = MixDatabase {
+            game: self.game,
+            ..Default::default()
+        };
+        for (id, name, description) in self.iter() {
+            let entry = match description {
+                Some(description) => MixDatabaseEntry::with_description(name, description),
+                None => MixDatabaseEntry::new(name),
+            };
+            db.add_entry(id, entry);
+        }
+        db
+    }
+
+    fn partition_point(&self, pred: impl Fn(i32) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(self.id(mid)) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    fn id(&self, i: usize) -> i32 {
+        read_u32(self.table, i * RECORD_SIZE) as i32
+    }
+
+    fn record(&self, i: usize) -> Option<(i32, &'a str, Option<&'a str>)> {
+        let offset = i * RECORD_SIZE;
+        let string = |at: usize| {
+            let start = read_u32(self.table, offset + at) as usize;
+            let size = read_u32(self.table, offset + at + 4) as usize;
+            self.pool.get(start..start.checked_add(size)?)
+        };
+        let name = string(4)?;
+        let description = string(12)?;
+        let description = (!description.is_empty()).then_some(description);
+        Some((self.id(i), name, description))
+    }
+}
+
+impl MixDatabaseLookup for CompactMixDatabase<'_> {
+    fn game(&self) -> Option<GameEnum> {
+        self.game
+    }
+
+    /// Looks up candidates with a binary search, copying only the entries found.
+    fn lookup(&self, id: i32) -> Vec<Cow<'_, MixDatabaseEntry>> {
+        self.get_all(id)
+            .map(|(name, description)| {
+                Cow::Owned(match description {
+                    Some(description) => MixDatabaseEntry::with_description(name, description),
+                    None => MixDatabaseEntry::new(name),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Provides static methods for writing compact MIX databases.
+pub struct CompactMixDbWriter {}
+
+impl CompactMixDbWriter {
+    /// Write a database in the compact format. Candidates of every ID keep their order.
+    pub fn write_file(writer: &mut dyn Write, db: &MixDatabase) -> Result<()> {
+        let mut ids: Vec<_> = db.entries.keys().copied().collect();
+        ids.sort();
+        let mut table = Vec::new();
+        let mut pool = String::new();
+        let push = |pool: &mut String, table: &mut Vec<u8>, value: &str| -> Result<()> {
+            let start = u32::try_from(pool.len()).map_err(|_| Error::TooBig)?;
+            let size = u32::try_from(value.len()).map_err(|_| Error::TooBig)?;
+            pool.push_str(value);
+            table.extend_from_slice(&start.to_le_bytes());
+            table.extend_from_slice(&size.to_le_bytes());
+            Ok(())
+        };
+        let mut count = 0u32;
+        for id in ids {
+            for entry in db.get_all(id) {
+                table.extend_from_slice(&id.to_le_bytes());
+                push(&mut pool, &mut table, &entry.name)?;
+                let description = entry.description.as_deref().unwrap_or_default();
+                push(&mut pool, &mut table, description)?;
+                count = count.checked_add(1).ok_or(Error::TooBig)?;
+            }
+        }
+        let game = db.game.map_or(NO_GAME, |game| {
+            COMPACT_GAMES
+                .iter()
+                .position(|x| *x == game)
+                .unwrap_or_else(|| unreachable!()) as u32
+        });
+        let pool_size = u32::try_from(pool.len()).map_err(|_| Error::TooBig)?;
+
+        writer.write_all(COMPACT_MAGIC)?;
+        writer.write_all(&COMPACT_VERSION.to_le_bytes())?;
+        writer.write_all(&game.to_le_bytes())?;
+        writer.write_all(&count.to_le_bytes())?;
+        writer.write_all(&pool_size.to_le_bytes())?;
+        writer.write_all(&table)?;
+        writer.write_all(pool.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(
+        data[offset..offset + 4]
+            .try_into()
+            .unwrap_or_else(|_| unreachable!()),
+    ) // Won't panic: callers check the size first.
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn new() {
+        use rust_alert::mix::db::{
+            compact::{CompactMixDatabase, CompactMixDbWriter},
+            MixDatabase,
+        };
+
+        let mut db = MixDatabase::default();
+        db.insert(1, "a.shp");
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+
+        let view = CompactMixDatabase::new(&data).unwrap();
+        assert_eq!(view.get_name(1), Some("a.shp"));
+        assert_eq!(view.get_name(2), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::GameEnum,
+        mix::db::{
+            compact::{CompactMixDatabase, CompactMixDbWriter, Error},
+            MixDatabase, MixDatabaseEntry, MixDatabaseLookup,
+        },
+    };
+
+    fn sample() -> MixDatabase {
+        let mut db = MixDatabase::with_game(GameEnum::RA2);
+        db.insert(-5, "neg.shp");
+        db.insert_entry(3, MixDatabaseEntry::with_description("c.shp", "Desc"));
+        db.add(3, "d.shp");
+        db.insert(7, "\u{e9}.shp");
+        db
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let db = sample();
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+
+        let view = CompactMixDatabase::new(&data).unwrap();
+        assert_eq!(view.game(), Some(GameEnum::RA2));
+        assert_eq!(view.len(), 4);
+        assert_eq!(view.get_name(-5), Some("neg.shp"));
+        assert_eq!(view.get_name(7), Some("\u{e9}.shp"));
+        let all: Vec<_> = view.get_all(3).collect();
+        assert_eq!(all, vec![("c.shp", Some("Desc")), ("d.shp", None)]);
+        for id in [i32::MIN, -6, 0, 4, 8, i32::MAX] {
+            assert_eq!(view.get_name(id), None);
+        }
+        assert_eq!(view.to_db(), db);
+    }
+
+    #[test]
+    fn compact_lookup() {
+        let db = sample();
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+        let view = CompactMixDatabase::new(&data).unwrap();
+
+        assert_eq!(MixDatabaseLookup::game(&view), db.game);
+        for id in [-5, 0, 3, 7] {
+            assert_eq!(view.lookup(id), db.lookup(id));
+        }
+    }
+
+    #[test]
+    fn compact_empty() {
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &MixDatabase::default()).unwrap();
+        let view = CompactMixDatabase::new(&data).unwrap();
+        assert!(view.is_empty());
+        assert_eq!(view.game(), None);
+        assert_eq!(view.get_name(0), None);
+    }
+
+    #[test]
+    fn compact_err() {
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &sample()).unwrap();
+        assert!(matches!(
+            CompactMixDatabase::new(&data[..data.len() - 1]),
+            Err(Error::TooShort(_, _))
+        ));
+        assert!(matches!(
+            CompactMixDatabase::new(b"RADC\x01\x00\x00\x00"),
+            Err(Error::TooShort(_, _))
+        ));
+        let mut bad = data.clone();
+        bad[0] = b'X';
+        assert!(matches!(
+            CompactMixDatabase::new(&bad),
+            Err(Error::InvalidMagic)
+        ));
+        let mut bad = data.clone();
+        bad[4] = 2;
+        assert!(matches!(
+            CompactMixDatabase::new(&bad),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        let mut bad = data;
+        bad[8] = 6;
+        assert!(matches!(
+            CompactMixDatabase::new(&bad),
+            Err(Error::UnknownGame(6))
+        ));
+    }
+}
@@ -1,8 +1,14 @@
 //! MIX database structures and manipulation.
 
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
 
-use crate::{core::GameEnum, utils::BuildNothingHasher};
+use crate::{
+    core::{crc, GameEnum},
+    utils::BuildNothingHasher,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -65,14 +71,7 @@ impl From<LMDVersionEnum> for GameEnum {
 
 impl From<GameEnum> for LMDVersionEnum {
     fn from(value: GameEnum) -> Self {
-        match value {
-            GameEnum::TD => LMDVersionEnum::TD,
-            GameEnum::RA => LMDVersionEnum::RA,
-            GameEnum::TS => LMDVersionEnum::TS,
-            GameEnum::FS => LMDVersionEnum::TS,
-            GameEnum::RA2 => LMDVersionEnum::RA2,
-            GameEnum::YR => LMDVersionEnum::YR,
-        }
+        value.profile().lmd_version
     }
 }
 
@@ -82,11 +81,311 @@ impl std::fmt::Display for LMDVersionEnum {
     }
 }
 
+/// Where a name in a MIX database comes from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NameSourceEnum {
+    /// Unknown, for example added by hand.
+    #[default]
+    Unknown,
+    /// Read from an LMD.
+    Lmd,
+    /// Read from a GMD or the built-in database.
+    Gmd,
+    /// Read from an INI DB file.
+    Ini,
+    /// Recovered by cracking or harvesting. Might not be the original name.
+    Guessed,
+}
+
+impl std::fmt::Display for NameSourceEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// A single MIX database entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MixDatabaseEntry {
+    /// Original filename.
+    pub name: String,
+    /// Optional description of the file. Only GMDs store descriptions.
+    pub description: Option<String>,
+    /// Where the name comes from.
+    pub source: NameSourceEnum,
+}
+
+impl MixDatabaseEntry {
+    /// Create a new entry with no description.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a new entry with a description.
+    pub fn with_description(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: Some(description.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the source of the entry.
+    pub fn with_source(mut self, source: NameSourceEnum) -> Self {
+        self.source = source;
+        self
+    }
+}
+
 /// A MIX database is a file mapping unique file IDs into their original names.
-#[derive(Debug, Default, Clone)]
+///
+/// Several names can have the same ID, so every ID maps to a list of candidate names.
+/// The first candidate is the primary one, which is used by single-name lookups.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MixDatabase {
-    pub names: HashMap<i32, String, BuildNothingHasher>,
+    /// Game the names belong to, which also decides how their IDs were calculated.
+    /// `None` if unknown.
+    pub game: Option<GameEnum>,
+    /// Candidate entries by file ID.
+    pub entries: HashMap<i32, Vec<MixDatabaseEntry>, BuildNothingHasher>,
+}
+
+impl MixDatabase {
+    /// Create an empty database of given game.
+    pub fn with_game(game: GameEnum) -> Self {
+        Self {
+            game: Some(game),
+            ..Default::default()
+        }
+    }
+
+    /// Insert a name with no description as the only candidate, returning the previous
+    /// primary entry with the same ID.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::MixDatabase;
+    ///
+    /// let mut db = MixDatabase::default();
+    /// assert!(db.insert(1, "a").is_none());
+    /// assert_eq!(db.insert(1, "b").unwrap().name, "a");
+    /// assert_eq!(db.get_name(1).unwrap(), "b");
+    /// ```
+    pub fn insert(&mut self, id: i32, name: impl Into<String>) -> Option<MixDatabaseEntry> {
+        self.insert_entry(id, MixDatabaseEntry::new(name))
+    }
+
+    /// Insert an entry as the only candidate, returning the previous primary entry with the same ID.
+    pub fn insert_entry(&mut self, id: i32, entry: MixDatabaseEntry) -> Option<MixDatabaseEntry> {
+        self.entries
+            .insert(id, vec![entry])
+            .and_then(|x| x.into_iter().next())
+    }
+
+    /// Add a name with no description as another candidate. See [`MixDatabase::add_entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::MixDatabase;
+    ///
+    /// let mut db = MixDatabase::default();
+    /// assert!(db.add(1, "a"));
+    /// assert!(db.add(1, "b"));
+    /// assert!(!db.add(1, "A"));
+    /// assert_eq!(db.get_name(1).unwrap(), "a");
+    /// assert_eq!(db.get_all(1).len(), 2);
+    /// ```
+    pub fn add(&mut self, id: i32, name: impl Into<String>) -> bool {
+        self.add_entry(id, MixDatabaseEntry::new(name))
+    }
+
+    /// Add an entry as another candidate, unless there already is a candidate
+    /// with the same name (ignoring case). Returns true if the entry was added.
+    pub fn add_entry(&mut self, id: i32, entry: MixDatabaseEntry) -> bool {
+        let candidates = self.entries.entry(id).or_default();
+        if candidates
+            .iter()
+            .any(|x| x.name.eq_ignore_ascii_case(&entry.name))
+        {
+            return false;
+        }
+        candidates.push(entry);
+        true
+    }
+
+    /// Remove all candidates with given ID.
+    pub fn remove(&mut self, id: i32) -> Option<Vec<MixDatabaseEntry>> {
+        self.entries.remove(&id)
+    }
+
+    /// Get the primary entry by ID.
+    pub fn get(&self, id: i32) -> Option<&MixDatabaseEntry> {
+        self.entries.get(&id).and_then(|x| x.first())
+    }
+
+    /// Get all candidate entries by ID, primary first.
+    pub fn get_all(&self, id: i32) -> &[MixDatabaseEntry] {
+        self.entries.get(&id).map_or(&[], |x| x.as_slice())
+    }
+
+    /// Get the primary name by ID.
+    pub fn get_name(&self, id: i32) -> Option<&String> {
+        self.get(id).map(|x| &x.name)
+    }
+
+    /// Get the primary description by ID.
+    pub fn get_description(&self, id: i32) -> Option<&String> {
+        self.get(id).and_then(|x| x.description.as_ref())
+    }
+
+    /// Checks if the database contains given ID.
+    pub fn contains(&self, id: i32) -> bool {
+        !self.get_all(id).is_empty()
+    }
+
+    /// Checks if given ID has more than one candidate name.
+    pub fn has_collision(&self, id: i32) -> bool {
+        self.get_all(id).len() > 1
+    }
+
+    /// Returns an iterator over IDs with more than one candidate name and their candidates.
+    pub fn collisions(&self) -> impl Iterator<Item = (&i32, &Vec<MixDatabaseEntry>)> {
+        self.entries.iter().filter(|(_, x)| x.len() > 1)
+    }
+
+    /// Returns an iterator over IDs and primary entries, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&i32, &MixDatabaseEntry)> {
+        self.entries
+            .iter()
+            .filter_map(|(id, x)| x.first().map(|x| (id, x)))
+    }
+
+    /// Returns an iterator over IDs and all candidate entries, in arbitrary order.
+    pub fn iter_all(&self) -> impl Iterator<Item = (&i32, &MixDatabaseEntry)> {
+        self.entries
+            .iter()
+            .flat_map(|(id, x)| x.iter().map(move |x| (id, x)))
+    }
+
+    /// Checks if given name is one of the candidates for given ID, ignoring case.
+    pub fn contains_name(&self, id: i32, name: &str) -> bool {
+        self.get_all(id)
+            .iter()
+            .any(|x| x.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add all candidates from another database. Names already known for an ID are skipped.
+    /// Returns sorted IDs which had different names in both databases, which are now collisions.
+    /// If both databases have games with different CRC algorithms, names of the other
+    /// database are hashed again for the game of this one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::MixDatabase;
+    ///
+    /// let mut db = MixDatabase::default();
+    /// db.insert(1, "a");
+    /// db.insert(2, "b");
+    /// let mut other = MixDatabase::default();
+    /// other.insert(2, "c");
+    /// other.insert(3, "d");
+    ///
+    /// assert_eq!(db.merge(other), vec![2]);
+    /// assert_eq!(db.len(), 3);
+    /// assert_eq!(db.get_name(2).unwrap(), "b");
+    /// assert!(db.contains_name(2, "c"));
+    /// ```
+    pub fn merge(&mut self, other: MixDatabase) -> Vec<i32> {
+        let rehash = self.rehash_game(&other);
+        let mut created = HashSet::new();
+        let mut conflicts = vec![];
+        for (id, entries) in other.entries {
+            for entry in entries {
+                let id = rehash.map_or(id, |game| crc(&entry.name, game));
+                let known = self.contains(id) && !created.contains(&id);
+                if !self.add_entry(id, entry) {
+                    continue;
+                }
+                if known {
+                    conflicts.push(id);
+                } else {
+                    created.insert(id);
+                }
+            }
+        }
+        if self.game.is_none() {
+            self.game = other.game;
+        }
+        conflicts.sort();
+        conflicts.dedup();
+        conflicts
+    }
+
+    /// Remove all names known by another database. IDs left with no names are removed.
+    /// If both databases have games with different CRC algorithms, names are looked up
+    /// in the other database by their ID in its game.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::MixDatabase;
+    ///
+    /// let mut db = MixDatabase::default();
+    /// db.insert(1, "a");
+    /// db.add(1, "b");
+    /// db.insert(2, "c");
+    /// let mut other = MixDatabase::default();
+    /// other.insert(1, "A");
+    /// other.insert(2, "c");
+    /// db.subtract(&other);
+    ///
+    /// assert_eq!(db.len(), 1);
+    /// assert_eq!(db.get_name(1).unwrap(), "b");
+    /// ```
+    pub fn subtract(&mut self, other: &MixDatabase) {
+        let rehash = other.rehash_game(self);
+        self.entries.retain(|id, entries| {
+            entries.retain(|x| {
+                let id = rehash.map_or(*id, |game| crc(&x.name, game));
+                !other.contains_name(id, &x.name)
+            });
+            !entries.is_empty()
+        });
+    }
+
+    /// Returns the game of this database if names of the other one have to be hashed again
+    /// to match it, which is when both have games with different CRC algorithms.
+    fn rehash_game(&self, other: &MixDatabase) -> Option<GameEnum> {
+        match (self.game, other.game) {
+            (Some(game), Some(other)) if game.profile().crc != other.profile().crc => Some(game),
+            _ => None,
+        }
+    }
+
+    /// Returns a database of names this database knows and the other doesn't.
+    pub fn difference(&self, other: &MixDatabase) -> MixDatabase {
+        let mut db = self.clone();
+        db.subtract(other);
+        db
+    }
+
+    /// Returns the number of IDs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 }
 
 /// A local MIX database is a file within a MIX. XCC addition.
@@ -105,18 +404,417 @@ pub struct GlobalMixDatabase {
 }
 
 impl GlobalMixDatabase {
-    pub fn get_name(&self, id: i32) -> Option<&String> {
-        self.dbs.iter().find_map(|x| x.names.get(&id))
+    /// Get a name by ID, preferring databases of given game. Databases of the game are
+    /// searched first, then untagged ones, then ones of other games with the same CRC algorithm.
+    /// Databases of games using a different CRC algorithm are never searched.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::GameEnum, mix::db::{GlobalMixDatabase, MixDatabase}};
+    ///
+    /// let mut ts = MixDatabase::with_game(GameEnum::TS);
+    /// ts.insert(1, "ts.shp");
+    /// let mut yr = MixDatabase::with_game(GameEnum::YR);
+    /// yr.insert(1, "yr.shp");
+    /// let mut td = MixDatabase::with_game(GameEnum::TD);
+    /// td.insert(2, "td.shp");
+    /// let gmd = GlobalMixDatabase { dbs: vec![ts, yr, td] };
+    ///
+    /// assert_eq!(gmd.get_name(1, GameEnum::YR).unwrap(), "yr.shp");
+    /// assert_eq!(gmd.get_name(1, GameEnum::RA2).unwrap(), "ts.shp");
+    /// assert!(gmd.get_name(2, GameEnum::YR).is_none());
+    /// ```
+    pub fn get_name(&self, id: i32, game: GameEnum) -> Option<&String> {
+        self.dbs_by_preference(game).find_map(|x| x.get_name(id))
     }
 
-    pub fn get_name_mut(&mut self, id: i32) -> Option<&mut String> {
-        self.dbs.iter_mut().find_map(|x| x.names.get_mut(&id))
+    /// Get all candidate entries by ID from all databases searched by
+    /// [`GlobalMixDatabase::get_name`], in the same order. Repeated names are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{core::GameEnum, mix::db::{GlobalMixDatabase, MixDatabase}};
+    ///
+    /// let mut lmd = MixDatabase::with_game(GameEnum::YR);
+    /// lmd.insert(1, "a.shp");
+    /// let mut gmd = MixDatabase::default();
+    /// gmd.add(1, "A.SHP");
+    /// gmd.add(1, "b.shp");
+    /// let gmd = GlobalMixDatabase { dbs: vec![lmd, gmd] };
+    ///
+    /// let names: Vec<_> = gmd.get_all(1, GameEnum::YR).iter().map(|x| &x.name).collect();
+    /// assert_eq!(names, vec!["a.shp", "b.shp"]);
+    /// ```
+    pub fn get_all(&self, id: i32, game: GameEnum) -> Vec<&MixDatabaseEntry> {
+        let mut all: Vec<&MixDatabaseEntry> = vec![];
+        for entry in self.dbs_by_preference(game).flat_map(|x| x.get_all(id)) {
+            if !all.iter().any(|x| x.name.eq_ignore_ascii_case(&entry.name)) {
+                all.push(entry);
+            }
+        }
+        all
+    }
+
+    /// Get a name by ID, only from databases of given game.
+    pub fn get_name_for(&self, id: i32, game: GameEnum) -> Option<&String> {
+        self.dbs_for(game).find_map(|x| x.get_name(id))
     }
 
-    pub fn get_name_or_id(&self, id: i32) -> String {
+    /// Get a mutable name by ID from the first database containing it.
+    pub fn get_name_mut(&mut self, id: i32) -> Option<&mut String> {
         self.dbs
-            .iter()
-            .find_map(|x| x.names.get(&id))
+            .iter_mut()
+            .find_map(|x| x.entries.get_mut(&id).and_then(|x| x.first_mut()))
+            .map(|x| &mut x.name)
+    }
+
+    /// Get a name by ID like [`GlobalMixDatabase::get_name`], or the ID in hex if it's unknown.
+    pub fn get_name_or_id(&self, id: i32, game: GameEnum) -> String {
+        self.get_name(id, game)
             .map_or_else(|| format!("{:0>8X}", id), |x| x.to_string())
     }
+
+    /// Returns an iterator over databases of given game.
+    pub fn dbs_for(&self, game: GameEnum) -> impl Iterator<Item = &MixDatabase> {
+        self.dbs.iter().filter(move |x| x.game == Some(game))
+    }
+
+    /// Returns an iterator over databases searched for given game, most preferred first.
+    fn dbs_by_preference(&self, game: GameEnum) -> impl Iterator<Item = &MixDatabase> {
+        by_preference(self.dbs.iter(), game)
+    }
+}
+
+/// Read-only lookups of names by ID, shared by [`MixDatabase`] and
+/// [`CompactMixDatabase`](crate::mix::db::compact::CompactMixDatabase).
+pub trait MixDatabaseLookup {
+    /// Returns the game of the database, or `None` if unknown.
+    fn game(&self) -> Option<GameEnum>;
+
+    /// Returns all candidate entries by ID, primary first.
+    fn lookup(&self, id: i32) -> Vec<Cow<'_, MixDatabaseEntry>>;
+}
+
+impl MixDatabaseLookup for MixDatabase {
+    fn game(&self) -> Option<GameEnum> {
+        self.game
+    }
+
+    fn lookup(&self, id: i32) -> Vec<Cow<'_, MixDatabaseEntry>> {
+        self.get_all(id).iter().map(Cow::Borrowed).collect()
+    }
+}
+
+/// A list of borrowed databases of any kind, searched in the same order as
+/// a [`GlobalMixDatabase`]. Useful for databases which are not worth copying
+/// into a [`MixDatabase`], like compact ones.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{
+///     core::GameEnum,
+///     mix::db::{compact::{CompactMixDatabase, CompactMixDbWriter}, MixDatabase, MixDatabaseChain},
+/// };
+///
+/// let mut lmd = MixDatabase::with_game(GameEnum::YR);
+/// lmd.insert(1, "a.shp");
+/// let mut db = MixDatabase::default();
+/// db.insert(1, "b.shp");
+/// db.insert(2, "c.shp");
+/// let mut data = vec![];
+/// CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+/// let compact = CompactMixDatabase::new(&data).unwrap();
+/// let chain = MixDatabaseChain { dbs: vec![&compact, &lmd] };
+///
+/// assert_eq!(chain.get_name(1, GameEnum::YR).unwrap(), "a.shp");
+/// assert_eq!(chain.get_all(1, GameEnum::YR).len(), 2);
+/// assert_eq!(chain.get_name(2, GameEnum::YR).unwrap(), "c.shp");
+/// ```
+#[derive(Default)]
+pub struct MixDatabaseChain<'a> {
+    /// Databases to search.
+    pub dbs: Vec<&'a dyn MixDatabaseLookup>,
+}
+
+impl MixDatabaseChain<'_> {
+    /// Get the primary name by ID, preferring databases like [`GlobalMixDatabase::get_name`].
+    pub fn get_name(&self, id: i32, game: GameEnum) -> Option<String> {
+        by_preference(self.dbs.iter().copied(), game)
+            .find_map(|x| x.lookup(id).first().map(|x| x.name.clone()))
+    }
+
+    /// Get all candidate entries by ID like [`GlobalMixDatabase::get_all`].
+    pub fn get_all(&self, id: i32, game: GameEnum) -> Vec<Cow<'_, MixDatabaseEntry>> {
+        let mut all: Vec<Cow<MixDatabaseEntry>> = vec![];
+        for entry in by_preference(self.dbs.iter().copied(), game).flat_map(|x| x.lookup(id)) {
+            if !all.iter().any(|x| x.name.eq_ignore_ascii_case(&entry.name)) {
+                all.push(entry);
+            }
+        }
+        all
+    }
+}
+
+/// Returns an iterator over databases searched for given game, most preferred first.
+/// Databases of the game go first, then untagged ones, then ones of other games with
+/// the same CRC algorithm.
+fn by_preference<'a, T: MixDatabaseLookup + ?Sized + 'a>(
+    dbs: impl Iterator<Item = &'a T> + Clone + 'a,
+    game: GameEnum,
+) -> impl Iterator<Item = &'a T> + 'a {
+    let crc = game.profile().crc;
+    let matching = dbs.clone().filter(move |x| x.game() == Some(game));
+    let untagged = dbs.clone().filter(|x| x.game().is_none());
+    let similar = dbs.filter(move |x| {
+        x.game()
+            .is_some_and(|x| x != game && x.profile().crc == crc)
+    });
+    matching.chain(untagged).chain(similar)
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn insert() {
+        use rust_alert::mix::db::MixDatabase;
+
+        let mut db = MixDatabase::default();
+        assert!(db.insert(1, "a").is_none());
+        assert_eq!(db.insert(1, "b").unwrap().name, "a");
+        assert_eq!(db.get_name(1).unwrap(), "b");
+    }
+
+    #[test]
+    fn add() {
+        use rust_alert::mix::db::MixDatabase;
+
+        let mut db = MixDatabase::default();
+        assert!(db.add(1, "a"));
+        assert!(db.add(1, "b"));
+        assert!(!db.add(1, "A"));
+        assert_eq!(db.get_name(1).unwrap(), "a");
+        assert_eq!(db.get_all(1).len(), 2);
+    }
+
+    #[test]
+    fn merge() {
+        use rust_alert::mix::db::MixDatabase;
+
+        let mut db = MixDatabase::default();
+        db.insert(1, "a");
+        db.insert(2, "b");
+        let mut other = MixDatabase::default();
+        other.insert(2, "c");
+        other.insert(3, "d");
+
+        assert_eq!(db.merge(other), vec![2]);
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.get_name(2).unwrap(), "b");
+        assert!(db.contains_name(2, "c"));
+    }
+
+    #[test]
+    fn subtract() {
+        use rust_alert::mix::db::MixDatabase;
+
+        let mut db = MixDatabase::default();
+        db.insert(1, "a");
+        db.add(1, "b");
+        db.insert(2, "c");
+        let mut other = MixDatabase::default();
+        other.insert(1, "A");
+        other.insert(2, "c");
+        db.subtract(&other);
+
+        assert_eq!(db.len(), 1);
+        assert_eq!(db.get_name(1).unwrap(), "b");
+    }
+
+    #[test]
+    fn get_all() {
+        use rust_alert::{
+            core::GameEnum,
+            mix::db::{GlobalMixDatabase, MixDatabase},
+        };
+
+        let mut lmd = MixDatabase::with_game(GameEnum::YR);
+        lmd.insert(1, "a.shp");
+        let mut gmd = MixDatabase::default();
+        gmd.add(1, "A.SHP");
+        gmd.add(1, "b.shp");
+        let gmd = GlobalMixDatabase {
+            dbs: vec![lmd, gmd],
+        };
+
+        let names: Vec<_> = gmd
+            .get_all(1, GameEnum::YR)
+            .iter()
+            .map(|x| &x.name)
+            .collect();
+        assert_eq!(names, vec!["a.shp", "b.shp"]);
+    }
+
+    #[test]
+    fn get_name() {
+        use rust_alert::{
+            core::GameEnum,
+            mix::db::{GlobalMixDatabase, MixDatabase},
+        };
+
+        let mut ts = MixDatabase::with_game(GameEnum::TS);
+        ts.insert(1, "ts.shp");
+        let mut yr = MixDatabase::with_game(GameEnum::YR);
+        yr.insert(1, "yr.shp");
+        let mut td = MixDatabase::with_game(GameEnum::TD);
+        td.insert(2, "td.shp");
+        let gmd = GlobalMixDatabase {
+            dbs: vec![ts, yr, td],
+        };
+
+        assert_eq!(gmd.get_name(1, GameEnum::YR).unwrap(), "yr.shp");
+        assert_eq!(gmd.get_name(1, GameEnum::RA2).unwrap(), "ts.shp");
+        assert!(gmd.get_name(2, GameEnum::YR).is_none());
+    }
+
+    #[test]
+    fn chain() {
+        use rust_alert::{
+            core::GameEnum,
+            mix::db::{
+                compact::{CompactMixDatabase, CompactMixDbWriter},
+                MixDatabase, MixDatabaseChain,
+            },
+        };
+
+        let mut lmd = MixDatabase::with_game(GameEnum::YR);
+        lmd.insert(1, "a.shp");
+        let mut db = MixDatabase::default();
+        db.insert(1, "b.shp");
+        db.insert(2, "c.shp");
+        let mut data = vec![];
+        CompactMixDbWriter::write_file(&mut data, &db).unwrap();
+        let compact = CompactMixDatabase::new(&data).unwrap();
+        let chain = MixDatabaseChain {
+            dbs: vec![&compact, &lmd],
+        };
+
+        assert_eq!(chain.get_name(1, GameEnum::YR).unwrap(), "a.shp");
+        assert_eq!(chain.get_all(1, GameEnum::YR).len(), 2);
+        assert_eq!(chain.get_name(2, GameEnum::YR).unwrap(), "c.shp");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{crc, GameEnum},
+        mix::db::{GlobalMixDatabase, MixDatabase},
+    };
+
+    #[test]
+    fn get_name_untagged() {
+        let mut untagged = MixDatabase::default();
+        untagged.insert(1, "lmd.shp");
+        let mut ts = MixDatabase::with_game(GameEnum::TS);
+        ts.insert(1, "ts.shp");
+        ts.insert(2, "other.shp");
+        let gmd = GlobalMixDatabase {
+            dbs: vec![ts, untagged],
+        };
+
+        // Untagged databases win over other games.
+        assert_eq!(gmd.get_name(1, GameEnum::YR).unwrap(), "lmd.shp");
+        assert_eq!(gmd.get_name(1, GameEnum::TS).unwrap(), "ts.shp");
+        assert_eq!(gmd.get_name_or_id(2, GameEnum::TD), "00000002");
+        assert!(gmd.get_name_for(1, GameEnum::YR).is_none());
+        assert_eq!(gmd.dbs_for(GameEnum::TS).count(), 1);
+    }
+
+    #[test]
+    fn collisions() {
+        let mut db = MixDatabase::default();
+        db.insert(1, "a");
+        db.add(1, "b");
+        db.add(2, "c");
+        assert!(db.has_collision(1));
+        assert!(!db.has_collision(2));
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.iter().count(), 2);
+        assert_eq!(db.iter_all().count(), 3);
+        assert_eq!(
+            db.collisions().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        // Inserting replaces all candidates.
+        assert_eq!(db.insert(1, "d").unwrap().name, "a");
+        assert!(!db.has_collision(1));
+        assert_eq!(db.remove(2).unwrap().len(), 1);
+        assert!(!db.contains(2));
+        assert!(db.get_all(2).is_empty());
+    }
+
+    #[test]
+    fn merge_and_difference() {
+        let mut a = MixDatabase::default();
+        a.insert(1, "a");
+        a.insert(2, "b");
+        let mut b = MixDatabase::with_game(GameEnum::TS);
+        b.insert(1, "A");
+        b.insert(2, "c");
+        b.insert(3, "d");
+
+        let only_a = a.difference(&b);
+        assert_eq!(only_a.len(), 1);
+        assert_eq!(only_a.get_name(2).unwrap(), "b");
+        let only_b = b.difference(&a);
+        assert_eq!(only_b.len(), 2);
+        assert!(only_b.contains_name(2, "c") && only_b.contains_name(3, "d"));
+
+        // Same name with different case is not a conflict.
+        assert_eq!(a.merge(b.clone()), vec![2]);
+        assert_eq!(a.game, Some(GameEnum::TS));
+        assert_eq!(a.get_all(1).len(), 1);
+        assert_eq!(a.get_all(2).len(), 2);
+        assert!(a.difference(&b).contains_name(2, "b"));
+        a.subtract(&b);
+        assert_eq!(a.iter_all().count(), 1);
+    }
+
+    #[test]
+    fn merge_other_crc() {
+        let mut ts = MixDatabase::with_game(GameEnum::TS);
+        ts.insert(crc("a.shp", GameEnum::TS), "a.shp");
+        let mut td = MixDatabase::with_game(GameEnum::TD);
+        td.insert(crc("a.shp", GameEnum::TD), "a.shp");
+        td.insert(crc("b.shp", GameEnum::TD), "b.shp");
+
+        assert!(ts.difference(&td).is_empty());
+        assert_eq!(
+            td.difference(&ts)
+                .get_name(crc("b.shp", GameEnum::TD))
+                .unwrap(),
+            "b.shp"
+        );
+        assert!(ts.merge(td.clone()).is_empty());
+        assert_eq!(ts.game, Some(GameEnum::TS));
+        assert_eq!(ts.len(), 2);
+        assert_eq!(ts.get_name(crc("b.shp", GameEnum::TS)).unwrap(), "b.shp");
+        assert!(!ts.contains(crc("b.shp", GameEnum::TD)));
+        td.subtract(&ts);
+        assert!(td.is_empty());
+
+        // Games with the same CRC algorithm keep IDs as they are.
+        let mut yr = MixDatabase::with_game(GameEnum::YR);
+        yr.merge(MixDatabase {
+            game: Some(GameEnum::TS),
+            ..ts.clone()
+        });
+        assert_eq!(yr.len(), 2);
+    }
 }
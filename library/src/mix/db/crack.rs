@@ -0,0 +1,533 @@
+//! Filename recovery from name templates.
+
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{
+    core::{crc, GameEnum},
+    mix::db::{MixDatabase, MixDatabaseEntry, NameSourceEnum},
+};
+
+/// The error type for name templates.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A group was opened with `{`, but never closed.
+    #[error("Unclosed group in template \"{0}\"")]
+    UnclosedGroup(String),
+    /// A group was closed with `}`, but never opened.
+    #[error("Unopened group in template \"{0}\"")]
+    UnopenedGroup(String),
+    /// A range has its bounds in the wrong order.
+    #[error("Invalid range \"{0}\"")]
+    InvalidRange(String),
+    /// The template is empty.
+    #[error("Template is empty")]
+    EmptyTemplate,
+    /// The template describes more candidates than can be counted.
+    #[error("Template \"{0}\" has too many candidates")]
+    TooManyCandidates(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// A filename template, describing a set of candidate names.
+///
+/// Templates consist of literal text and groups in curly braces. A group is a comma-separated
+/// list of alternatives, where each alternative is one of:
+/// * a character range, like `{a-z}`,
+/// * a numeric range, like `{0000-9999}` (zero-padded to the width of the lower bound),
+/// * a literal, like `{shp,vxl}` (which may be empty, making the group optional).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameTemplate {
+    source: String,
+    segments: Vec<Vec<AlternativeEnum>>,
+    len: u64,
+}
+
+/// A single alternative of a group. Numeric ranges are expanded lazily, so that
+/// templates with huge ranges don't allocate all of their candidates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AlternativeEnum {
+    Literal(String),
+    Numbers { lo: u64, hi: u64, width: usize },
+}
+
+impl AlternativeEnum {
+    /// Returns the number of choices of the alternative, or `None` if it doesn't fit in `u64`.
+    fn len(&self) -> Option<u64> {
+        match self {
+            Self::Literal(_) => Some(1),
+            Self::Numbers { lo, hi, .. } => (hi - lo).checked_add(1),
+        }
+    }
+
+    /// Appends the choice with given index to a buffer.
+    fn write(&self, index: u64, buf: &mut String) {
+        use std::fmt::Write;
+        match self {
+            Self::Literal(x) => buf.push_str(x),
+            Self::Numbers { lo, width, .. } => {
+                let _ = write!(buf, "{:0>width$}", lo + index, width = width);
+            }
+        }
+    }
+}
+
+impl NameTemplate {
+    /// Returns the number of candidates described by the template.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::crack::NameTemplate;
+    ///
+    /// let template: NameTemplate = "{a-z}{0-9}.shp".parse().unwrap();
+    /// assert_eq!(template.len(), 260);
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if the template describes no candidates.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the candidate with given index, or `None` if the index is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::crack::NameTemplate;
+    ///
+    /// let template: NameTemplate = "ice{0000-9999}.des".parse().unwrap();
+    /// assert_eq!(template.get(0).unwrap(), "ice0000.des");
+    /// assert_eq!(template.get(42).unwrap(), "ice0042.des");
+    /// assert!(template.get(10000).is_none());
+    /// ```
+    pub fn get(&self, index: u64) -> Option<String> {
+        let mut buf = String::new();
+        self.write_candidate(index, &mut buf).then_some(buf)
+    }
+
+    /// Writes the candidate with given index into a buffer, replacing its contents.
+    /// Returns false if the index is out of range.
+    pub fn write_candidate(&self, mut index: u64, buf: &mut String) -> bool {
+        buf.clear();
+        if index >= self.len {
+            return false;
+        }
+        // Mixed radix, with the last segment changing the fastest.
+        let mut digits = vec![0u64; self.segments.len()];
+        // Lengths were checked when counting candidates, so they can't overflow here.
+        for (digit, choices) in digits.iter_mut().zip(&self.segments).rev() {
+            let Some(radix) = Self::segment_len(choices) else {
+                return false;
+            };
+            *digit = index % radix;
+            index /= radix;
+        }
+        for (mut digit, choices) in digits.into_iter().zip(&self.segments) {
+            for choice in choices {
+                let Some(len) = choice.len() else {
+                    return false;
+                };
+                if digit < len {
+                    choice.write(digit, buf);
+                    break;
+                }
+                digit -= len;
+            }
+        }
+        true
+    }
+
+    /// Returns an iterator over all candidates.
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.len).filter_map(|i| self.get(i))
+    }
+
+    /// Surrounds the template with a group of prefixes and a group of suffixes.
+    /// Empty lists are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::crack::NameTemplate;
+    ///
+    /// let template: NameTemplate = "{0-9}".parse().unwrap();
+    /// let template = template.with_affixes(&["a".into(), "b".into()], &[".shp".into()]).unwrap();
+    /// assert_eq!(template.len(), 20);
+    /// assert_eq!(template.get(10).unwrap(), "b0.shp");
+    /// ```
+    pub fn with_affixes(mut self, prefixes: &[String], suffixes: &[String]) -> Result<Self> {
+        let literals = |x: &[String]| x.iter().cloned().map(AlternativeEnum::Literal).collect();
+        if !prefixes.is_empty() {
+            self.segments.insert(0, literals(prefixes));
+        }
+        if !suffixes.is_empty() {
+            self.segments.push(literals(suffixes));
+        }
+        self.len = Self::count(&self.source, &self.segments)?;
+        Ok(self)
+    }
+
+    fn segment_len(choices: &[AlternativeEnum]) -> Option<u64> {
+        choices
+            .iter()
+            .try_fold(0u64, |sum, x| sum.checked_add(x.len()?))
+    }
+
+    fn count(source: &str, segments: &[Vec<AlternativeEnum>]) -> Result<u64> {
+        segments
+            .iter()
+            .try_fold(1u64, |acc, x| acc.checked_mul(Self::segment_len(x)?))
+            .ok_or_else(|| Error::TooManyCandidates(source.to_string()))
+    }
+
+    /// Parse a single group alternative. Character ranges are expanded, and numeric
+    /// ranges are kept as bounds.
+    fn parse_alternative(alternative: &str) -> Result<Vec<AlternativeEnum>> {
+        let literal = || Ok(vec![AlternativeEnum::Literal(alternative.to_string())]);
+        let Some((lo, hi)) = alternative.split_once('-') else {
+            return literal();
+        };
+        let mut lo_chars = lo.chars();
+        let mut hi_chars = hi.chars();
+        match (
+            lo_chars.next(),
+            lo_chars.next(),
+            hi_chars.next(),
+            hi_chars.next(),
+        ) {
+            // Character range.
+            (Some(lo_char), None, Some(hi_char), None) => {
+                if lo_char > hi_char {
+                    return Err(Error::InvalidRange(alternative.to_string()));
+                }
+                Ok((lo_char..=hi_char)
+                    .map(|x| AlternativeEnum::Literal(x.into()))
+                    .collect())
+            }
+            // Numeric range.
+            _ if !lo.is_empty()
+                && !hi.is_empty()
+                && lo.bytes().all(|x| x.is_ascii_digit())
+                && hi.bytes().all(|x| x.is_ascii_digit()) =>
+            {
+                let (Ok(lo_num), Ok(hi_num)) = (lo.parse::<u64>(), hi.parse::<u64>()) else {
+                    return Err(Error::InvalidRange(alternative.to_string()));
+                };
+                if lo_num > hi_num {
+                    return Err(Error::InvalidRange(alternative.to_string()));
+                }
+                Ok(vec![AlternativeEnum::Numbers {
+                    lo: lo_num,
+                    hi: hi_num,
+                    width: lo.len(),
+                }])
+            }
+            // Not a range at all.
+            _ => literal(),
+        }
+    }
+}
+
+impl FromStr for NameTemplate {
+    type Err = Error;
+
+    /// Parse a template string.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::crack::{Error, NameTemplate};
+    ///
+    /// assert!("{a-z}{a-z}{0-9}{0-9}.shp".parse::<NameTemplate>().is_ok());
+    /// assert!(matches!("{a-z".parse::<NameTemplate>(), Err(Error::UnclosedGroup(_))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::EmptyTemplate);
+        }
+        let mut segments: Vec<Vec<AlternativeEnum>> = vec![];
+        let mut literal = String::new();
+        let mut rest = s;
+        while let Some(pos) = rest.find(['{', '}']) {
+            literal.push_str(&rest[..pos]);
+            if rest[pos..].starts_with('}') {
+                return Err(Error::UnopenedGroup(s.to_string()));
+            }
+            let end = rest[pos..]
+                .find('}')
+                .ok_or_else(|| Error::UnclosedGroup(s.to_string()))?
+                + pos;
+            let group = &rest[pos + 1..end];
+            if group.contains('{') {
+                return Err(Error::UnclosedGroup(s.to_string()));
+            }
+            if !literal.is_empty() {
+                segments.push(vec![AlternativeEnum::Literal(std::mem::take(&mut literal))]);
+            }
+            let mut choices = vec![];
+            for alternative in group.split(',') {
+                choices.extend(Self::parse_alternative(alternative)?);
+            }
+            segments.push(choices);
+            rest = &rest[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            segments.push(vec![AlternativeEnum::Literal(literal)]);
+        }
+        let len = Self::count(s, &segments)?;
+        Ok(Self {
+            source: s.to_string(),
+            segments,
+            len,
+        })
+    }
+}
+
+impl std::fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Enumerates all candidates from given templates in parallel, and returns a database of
+/// candidates whose IDs (calculated for given game) are in `ids`.
+///
+/// If more than one candidate matches an ID, the first one in template order is kept.
+/// A panic in a worker thread is propagated to the caller.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::collections::HashSet;
+/// use rust_alert::{core::{crc, GameEnum}, mix::db::crack::{crack, NameTemplate}};
+///
+/// let ids = HashSet::from([crc("ab12.shp", GameEnum::YR)]);
+/// let templates = vec!["{a-z}{a-z}{0-9}{0-9}.shp".parse::<NameTemplate>().unwrap()];
+/// let db = crack(&ids, &templates, GameEnum::YR);
+/// assert_eq!(db.get_name(crc("ab12.shp", GameEnum::YR)).unwrap(), "ab12.shp");
+/// ```
+pub fn crack(ids: &HashSet<i32>, templates: &[NameTemplate], game: GameEnum) -> MixDatabase {
+    let threads = std::thread::available_parallelism().map_or(1, |x| x.get()) as u64;
+    let mut db = MixDatabase::default();
+    for template in templates {
+        let chunk = template.len().div_ceil(threads).max(1);
+        let found: Vec<Vec<(i32, String)>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|i| {
+                    let start = (i * chunk).min(template.len());
+                    let end = (start + chunk).min(template.len());
+                    scope.spawn(move || crack_range(ids, template, game, start..end))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|x| x.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+        for (id, name) in found.into_iter().flatten() {
+            db.add_entry(
+                id,
+                MixDatabaseEntry::new(name).with_source(NameSourceEnum::Guessed),
+            );
+        }
+    }
+    db
+}
+
+fn crack_range(
+    ids: &HashSet<i32>,
+    template: &NameTemplate,
+    game: GameEnum,
+    range: std::ops::Range<u64>,
+) -> Vec<(i32, String)> {
+    let mut found = vec![];
+    let mut buf = String::new();
+    for i in range {
+        template.write_candidate(i, &mut buf);
+        let id = crc(&buf, game);
+        if ids.contains(&id) {
+            found.push((id, buf.clone()));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn len() {
+        use rust_alert::mix::db::crack::NameTemplate;
+
+        let template: NameTemplate = "{a-z}{0-9}.shp".parse().unwrap();
+        assert_eq!(template.len(), 260);
+    }
+
+    #[test]
+    fn get() {
+        use rust_alert::mix::db::crack::NameTemplate;
+
+        let template: NameTemplate = "ice{0000-9999}.des".parse().unwrap();
+        assert_eq!(template.get(0).unwrap(), "ice0000.des");
+        assert_eq!(template.get(42).unwrap(), "ice0042.des");
+        assert!(template.get(10000).is_none());
+    }
+
+    #[test]
+    fn with_affixes() {
+        use rust_alert::mix::db::crack::NameTemplate;
+
+        let template: NameTemplate = "{0-9}".parse().unwrap();
+        let template = template
+            .with_affixes(&["a".into(), "b".into()], &[".shp".into()])
+            .unwrap();
+        assert_eq!(template.len(), 20);
+        assert_eq!(template.get(10).unwrap(), "b0.shp");
+    }
+
+    #[test]
+    fn from_str() {
+        use rust_alert::mix::db::crack::{Error, NameTemplate};
+
+        assert!("{a-z}{a-z}{0-9}{0-9}.shp".parse::<NameTemplate>().is_ok());
+        assert!(matches!(
+            "{a-z".parse::<NameTemplate>(),
+            Err(Error::UnclosedGroup(_))
+        ));
+    }
+
+    #[test]
+    fn crack() {
+        use rust_alert::{
+            core::{crc, GameEnum},
+            mix::db::crack::{crack, NameTemplate},
+        };
+        use std::collections::HashSet;
+
+        let ids = HashSet::from([crc("ab12.shp", GameEnum::YR)]);
+        let templates = vec!["{a-z}{a-z}{0-9}{0-9}.shp".parse::<NameTemplate>().unwrap()];
+        let db = crack(&ids, &templates, GameEnum::YR);
+        assert_eq!(
+            db.get_name(crc("ab12.shp", GameEnum::YR)).unwrap(),
+            "ab12.shp"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        core::{crc, GameEnum},
+        mix::db::crack::{crack, Error, NameTemplate},
+    };
+
+    #[test]
+    fn parse_literal() {
+        let template: NameTemplate = "rules.ini".parse().unwrap();
+        assert_eq!(template.len(), 1);
+        assert_eq!(template.iter().collect::<Vec<_>>(), vec!["rules.ini"]);
+    }
+
+    #[test]
+    fn parse_alternatives() {
+        let template: NameTemplate = "a{,b,c-d}.{shp,vxl}".parse().unwrap();
+        let expected = vec![
+            "a.shp", "a.vxl", "ab.shp", "ab.vxl", "ac.shp", "ac.vxl", "ad.shp", "ad.vxl",
+        ];
+        assert_eq!(template.iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn parse_numeric_unpadded() {
+        let template: NameTemplate = "{8-11}".parse().unwrap();
+        assert_eq!(
+            template.iter().collect::<Vec<_>>(),
+            vec!["8", "9", "10", "11"]
+        );
+    }
+
+    #[test]
+    fn parse_huge_range() {
+        let template: NameTemplate = "{0000000000-9999999999}{,.shp}".parse().unwrap();
+        assert_eq!(template.len(), 20_000_000_000);
+        assert_eq!(template.get(0).unwrap(), "0000000000");
+        assert_eq!(template.get(85).unwrap(), "0000000042.shp");
+        assert_eq!(template.get(19_999_999_999).unwrap(), "9999999999.shp");
+        assert!(template.get(20_000_000_000).is_none());
+    }
+
+    #[test]
+    fn parse_mixed_group() {
+        let template: NameTemplate = "{x,8-10,a-b}".parse().unwrap();
+        assert_eq!(
+            template.iter().collect::<Vec<_>>(),
+            vec!["x", "8", "9", "10", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn parse_not_a_range() {
+        let template: NameTemplate = "{ab-cd}".parse().unwrap();
+        assert_eq!(template.iter().collect::<Vec<_>>(), vec!["ab-cd"]);
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!(matches!(
+            "".parse::<NameTemplate>(),
+            Err(Error::EmptyTemplate)
+        ));
+        assert!(matches!(
+            "a}".parse::<NameTemplate>(),
+            Err(Error::UnopenedGroup(_))
+        ));
+        assert!(matches!(
+            "{a{b}".parse::<NameTemplate>(),
+            Err(Error::UnclosedGroup(_))
+        ));
+        assert!(matches!(
+            "{z-a}".parse::<NameTemplate>(),
+            Err(Error::InvalidRange(_))
+        ));
+        assert!(matches!(
+            "{9-0}".parse::<NameTemplate>(),
+            Err(Error::InvalidRange(_))
+        ));
+        assert!(matches!(
+            "{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}{0-9}"
+                .parse::<NameTemplate>(),
+            Err(Error::TooManyCandidates(_))
+        ));
+        assert!(matches!(
+            "{0-18446744073709551615}".parse::<NameTemplate>(),
+            Err(Error::TooManyCandidates(_))
+        ));
+        assert_eq!(
+            "{1-18446744073709551615}"
+                .parse::<NameTemplate>()
+                .unwrap()
+                .len(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn crack_first_template_wins() {
+        let ids = HashSet::from([crc("a1.shp", GameEnum::TD), 0]);
+        let templates = vec![
+            "a{0-9}.shp".parse().unwrap(),
+            "{a-z}{0-9}.shp".parse().unwrap(),
+        ];
+        let db = crack(&ids, &templates, GameEnum::TD);
+        assert_eq!(db.len(), 1);
+        assert_eq!(db.get_name(crc("a1.shp", GameEnum::TD)).unwrap(), "a1.shp");
+    }
+}
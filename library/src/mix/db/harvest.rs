@@ -0,0 +1,280 @@
+//! Filename recovery from names referenced inside files.
+//!
+//! Game INIs (rules, art, sound, missions.pkt etc.) mention most of the files the game loads,
+//! either by their full name or by a base name that the engine completes with an extension.
+
+use std::collections::HashSet;
+
+use crate::{
+    core::{crc_batch, GameEnum},
+    ini::{io::IniReader, IniFile},
+    mix::{
+        db::{MixDatabase, MixDatabaseEntry, NameSourceEnum},
+        guess_file_type,
+        io::MixReader,
+        FileTypeEnum, Mix,
+    },
+};
+
+/// Extensions the engine appends to base names found in INIs, in addition to theater
+/// extensions from the game profile.
+pub const HARVEST_EXTENSIONS: &[&str] = &[
+    "shp", "vxl", "hva", "pal", "aud", "wav", "ini", "map", "mpr", "mmx", "yrm", "pcx", "tmp",
+    "vqa", "bik", "mix", "csf", "fnt",
+];
+
+/// Theater letters substituted for the second letter of theater-specific building images
+/// (generic, temperate, snow, urban, desert, lunar and new urban).
+pub const HARVEST_THEATER_LETTERS: &[char] = &['g', 't', 'a', 'u', 'd', 'l', 'n'];
+
+/// Collects candidate filenames from INI files and keeps the ones matching known IDs.
+#[derive(Debug, Default)]
+pub struct Harvester {
+    game: GameEnum,
+    ids: HashSet<i32>,
+    candidates: HashSet<String>,
+}
+
+impl Harvester {
+    /// Create a new harvester, which calculates IDs for given game.
+    pub fn new(game: GameEnum) -> Self {
+        Self {
+            game,
+            ..Default::default()
+        }
+    }
+
+    /// Add IDs of all files in a MIX and nested MIXes, and harvest candidates from
+    /// all INI files found inside. Nested MIXes which fail to load are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::{
+    ///     core::{crc, GameEnum},
+    ///     mix::{db::harvest::Harvester, Mix},
+    /// };
+    ///
+    /// let mut mix = Mix::default();
+    /// let ini = b"[GAPILL]\nImage=GTPILL\n".to_vec();
+    /// mix.add_file_raw(ini, crc("art.ini", GameEnum::YR), false).unwrap();
+    /// mix.add_file_raw(vec![], crc("gapill.shp", GameEnum::YR), false).unwrap();
+    ///
+    /// let mut harvester = Harvester::new(GameEnum::YR);
+    /// harvester.add_mix(&mix, false);
+    /// let db = harvester.finish();
+    /// assert_eq!(db.get_name(crc("gapill.shp", GameEnum::YR)).unwrap(), "gapill.shp");
+    /// ```
+    pub fn add_mix(&mut self, mix: &Mix, force_new_format: bool) {
+        for id in mix.index.keys() {
+            self.ids.insert(*id);
+            let Some(data) = mix.get_file(*id) else {
+                continue;
+            };
+            match guess_file_type(data) {
+                Some(FileTypeEnum::Ini) => self.add_ini_bytes(data),
+                Some(FileTypeEnum::Mix) => {
+                    if let Ok(inner) = MixReader::read_file(&mut &data[..], force_new_format) {
+                        self.add_mix(&inner, force_new_format);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Add IDs to look for.
+    pub fn add_ids(&mut self, ids: impl IntoIterator<Item = i32>) {
+        self.ids.extend(ids);
+    }
+
+    /// Harvest candidates from raw INI-like file contents. Invalid text and lines
+    /// which don't parse are skipped.
+    pub fn add_ini_bytes(&mut self, data: &[u8]) {
+        let text = sanitize_ini(&String::from_utf8_lossy(data));
+        if let Ok(ini) = IniReader::read_file(text.as_bytes()) {
+            self.add_ini(&ini);
+        }
+    }
+
+    /// Harvest candidates from section names, keys and values of an INI file.
+    pub fn add_ini(&mut self, ini: &IniFile) {
+        for (name, section) in ini.iter() {
+            self.add_tokens(name);
+            for (key, entry) in section.iter() {
+                self.add_tokens(key);
+                self.add_tokens(&entry.value);
+            }
+        }
+    }
+
+    /// Add candidates derived from a comma or whitespace-separated list of names.
+    pub fn add_tokens(&mut self, text: &str) {
+        let tokens = text
+            .split(|c: char| c == ',' || c == '|' || c.is_whitespace())
+            .map(|x| x.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+            .filter(|x| !x.is_empty() && x.len() <= 32 && x.chars().all(is_name_char));
+        for token in tokens {
+            let token = token.to_ascii_lowercase();
+            if token.contains('.') {
+                self.candidates.insert(token);
+                continue;
+            }
+            for variant in theater_variants(&token) {
+                let theaters = self.game.profile().theaters.iter().map(|x| &x.extension);
+                for extension in HARVEST_EXTENSIONS.iter().chain(theaters) {
+                    self.candidates.insert(format!("{variant}.{extension}"));
+                }
+            }
+        }
+    }
+
+    /// Returns the number of unique candidates harvested so far.
+    pub fn candidates_len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// Hash all candidates and return a database of ones matching the known IDs.
+    /// Colliding candidates are all kept, sorted by name.
+    pub fn finish(&self) -> MixDatabase {
+        let mut candidates: Vec<&String> = self.candidates.iter().collect();
+        candidates.sort();
+        let ids = crc_batch(&candidates, self.game);
+        let mut db = MixDatabase::default();
+        for (candidate, id) in candidates.into_iter().zip(ids) {
+            if self.ids.contains(&id) {
+                let entry = MixDatabaseEntry::new(candidate).with_source(NameSourceEnum::Guessed);
+                db.add_entry(id, entry);
+            }
+        }
+        db
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '~')
+}
+
+/// Returns the name itself and, if its second letter can be a theater letter,
+/// its variants for all theaters.
+fn theater_variants(name: &str) -> Vec<String> {
+    let mut chars: Vec<char> = name.chars().collect();
+    match chars.get(1) {
+        Some(c) if HARVEST_THEATER_LETTERS.contains(c) => HARVEST_THEATER_LETTERS
+            .iter()
+            .map(|letter| {
+                chars[1] = *letter;
+                chars.iter().collect()
+            })
+            .collect(),
+        _ => vec![name.to_string()],
+    }
+}
+
+/// Keep only the lines [`IniReader`] can parse without errors.
+fn sanitize_ini(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut has_section = false;
+    for line in text.lines() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.starts_with('[') && line.contains(']') {
+            has_section = true;
+        } else if !has_section
+            || !line
+                .split_once('=')
+                .is_some_and(|(k, v)| !k.trim().is_empty() && !v.trim().is_empty())
+        {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn add_mix() {
+        use rust_alert::{
+            core::{crc, GameEnum},
+            mix::{db::harvest::Harvester, Mix},
+        };
+
+        let mut mix = Mix::default();
+        let ini = b"[GAPILL]\nImage=GTPILL\n".to_vec();
+        mix.add_file_raw(ini, crc("art.ini", GameEnum::YR), false)
+            .unwrap();
+        mix.add_file_raw(vec![], crc("gapill.shp", GameEnum::YR), false)
+            .unwrap();
+
+        let mut harvester = Harvester::new(GameEnum::YR);
+        harvester.add_mix(&mix, false);
+        let db = harvester.finish();
+        assert_eq!(
+            db.get_name(crc("gapill.shp", GameEnum::YR)).unwrap(),
+            "gapill.shp"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{crc, GameEnum},
+        mix::{db::harvest::Harvester, io::MixWriter, Mix},
+    };
+
+    #[test]
+    fn harvest_tokens() {
+        let mut harvester = Harvester::new(GameEnum::YR);
+        harvester.add_ids([
+            crc("igi1.wav", GameEnum::YR),
+            crc("tree01.sno", GameEnum::YR),
+            crc("mapsel.pal", GameEnum::YR),
+        ]);
+        harvester.add_tokens("$igi1 igi2, tree01|MAPSEL.PAL");
+        let db = harvester.finish();
+        assert_eq!(db.len(), 3);
+        assert_eq!(
+            db.get_name(crc("mapsel.pal", GameEnum::YR)).unwrap(),
+            "mapsel.pal"
+        );
+    }
+
+    #[test]
+    fn harvest_broken_ini() {
+        let mut harvester = Harvester::new(GameEnum::TS);
+        harvester.add_ids([crc("e1.shp", GameEnum::TS), crc("bad.shp", GameEnum::TS)]);
+        harvester
+            .add_ini_bytes(b"orphan=bad\n[E1]\nEmpty=\n\xFF\xFE\n[Broken\nImage=E1 ; comment\n");
+        let db = harvester.finish();
+        assert_eq!(db.len(), 1);
+        assert!(db.contains(crc("e1.shp", GameEnum::TS)));
+    }
+
+    #[test]
+    fn harvest_nested_mix() {
+        let mut inner = Mix::default();
+        inner
+            .add_file_raw(b"[Missions]\nall01smd.map=Mission\n".to_vec(), 1, false)
+            .unwrap();
+        inner
+            .add_file_raw(vec![], crc("all01smd.map", GameEnum::RA2), false)
+            .unwrap();
+        let mut data = vec![];
+        MixWriter::write_file(&mut data, &mut inner, true).unwrap();
+        let mut outer = Mix::default();
+        outer.add_file_raw(data, 2, false).unwrap();
+
+        let mut harvester = Harvester::new(GameEnum::RA2);
+        harvester.add_mix(&outer, false);
+        let db = harvester.finish();
+        assert_eq!(
+            db.get_name(crc("all01smd.map", GameEnum::RA2)).unwrap(),
+            "all01smd.map"
+        );
+    }
+}
@@ -7,49 +7,104 @@ use std::{
 
 use crate::{
     core::{crc, GameEnum},
-    mix::db::{GlobalMixDatabase, LMDVersionEnum, LocalMixDatabase, MixDatabase},
+    mix::{
+        db::{
+            GlobalMixDatabase, LMDVersionEnum, LocalMixDatabase, MixDatabase, MixDatabaseEntry,
+            NameSourceEnum,
+        },
+        Mix, LMD_KEY_TD, LMD_KEY_TS,
+    },
 };
 
 /// Prefix of every LMD header.
 pub const LMD_PREFIX: &[u8; 32] = b"XCC by Olaf van der Spek\x1a\x04\x17\x27\x10\x19\x80\x00";
 /// Size of the entire LMD header.
 pub const LMD_HEADER_SIZE: usize = LMD_PREFIX.len() + 20;
+/// Games of GMD databases, in the order they're stored in. Databases past these
+/// are not tagged with a game and use the TS CRC.
+pub const GMD_GAMES: &[GameEnum] = &[GameEnum::TD, GameEnum::RA, GameEnum::TS, GameEnum::RA2];
 
+/// Returns the index of the GMD database that holds names of given game.
+/// FS names go with TS ones and YR names go with RA2 ones.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::{core::GameEnum, mix::db::io::{gmd_index, GMD_GAMES}};
+///
+/// assert_eq!(GMD_GAMES[gmd_index(GameEnum::YR)], GameEnum::RA2);
+/// ```
+pub fn gmd_index(game: GameEnum) -> usize {
+    match game {
+        GameEnum::TD => 0,
+        GameEnum::RA => 1,
+        GameEnum::TS | GameEnum::FS => 2,
+        GameEnum::RA2 | GameEnum::YR => 3,
+    }
+}
+
+/// The error type for reading and writing MIX databases.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// A [`std::io::Error`].
     #[error("{0}")]
     IO(#[from] std::io::Error),
+    /// A string is not valid UTF-8.
     #[error("{0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
+    /// The LMD doesn't start with [`LMD_PREFIX`].
     #[error("Attempted to read the LMD, but the prefix didn't match")]
     InvalidLMDPrefix,
+    /// A Blowfish key has the wrong size.
     #[error("Expected Blowfish key to be 56 bytes long, but was {0}")]
     WrongBlowfishSize(usize),
+    /// A [`rust_alert::mix::db::Error`][crate::mix::db::Error].
     #[error("{0}")]
     MixDbError(#[from] crate::mix::db::Error),
+    /// A name at given position is not null terminated.
     #[error("Expected a null terminated string, but couldn't find null")]
     NoNullTermination(usize),
+    /// A description at given position is not null terminated.
     #[error("Expected a null terminated string description, but couldn't find null")]
     NoNullDescTermination(usize),
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// Provides static methods for reading LMDs.
 pub struct LocalMixDbReader {}
 
 impl LocalMixDbReader {
+    /// Read an LMD, calculating IDs with the CRC function for its version.
     pub fn read_file(reader: &mut dyn Read) -> Result<LocalMixDatabase> {
         // Read the LMD header.
         let (_, version, size) = Self::read_header(reader)?;
         // Read and process the LMD body.
         let strings = Self::read_strings(reader, size as usize - LMD_HEADER_SIZE, version)?;
         let mut lmd = LocalMixDatabase::default();
-        lmd.db.names.extend(strings);
-        lmd.db.names.remove(&0);
+        for (id, name) in strings {
+            // Colliding names are all kept, in the order they're stored in.
+            lmd.db.add_entry(
+                id,
+                MixDatabaseEntry::new(name).with_source(NameSourceEnum::Lmd),
+            );
+        }
+        lmd.db.remove(0);
         lmd.version = version;
         Ok(lmd)
     }
 
+    /// Read the LMD stored in a MIX, or return `None` if it has none. The LMD key
+    /// of given game is tried first, but MIXes in the wild may use either key.
+    pub fn read_from_mix(mix: &Mix, game: GameEnum) -> Result<Option<LocalMixDatabase>> {
+        [game.profile().lmd_key, LMD_KEY_TS, LMD_KEY_TD]
+            .into_iter()
+            .find_map(|key| mix.get_file(key))
+            .map(|mut x| Self::read_file(&mut x))
+            .transpose()
+    }
+
+    /// Read the LMD header. Returns the number of names, version and total size.
     pub fn read_header(reader: &mut dyn Read) -> Result<(u32, LMDVersionEnum, u32)> {
         // Read the mandatory prefix.
         let mut buf = [0u8; LMD_PREFIX.len()];
@@ -91,9 +146,11 @@ impl LocalMixDbReader {
     }
 }
 
+/// Provides static methods for writing LMDs.
 pub struct LocalMixDbWriter {}
 
 impl LocalMixDbWriter {
+    /// Write an LMD. Descriptions are not stored.
     pub fn write_file(writer: &mut dyn Write, lmd: &LocalMixDatabase) -> Result<()> {
         Self::write_header(writer, lmd)?;
         Self::write_strings(writer, lmd)?;
@@ -101,26 +158,27 @@ impl LocalMixDbWriter {
         Ok(())
     }
 
+    /// Write the LMD header.
     pub fn write_header(writer: &mut dyn Write, lmd: &LocalMixDatabase) -> Result<()> {
         let size = lmd
             .db
-            .names
-            .values()
-            .fold(0u32, |acc, x| acc + x.len() as u32 + 1);
+            .iter_all()
+            .fold(0u32, |acc, (_, x)| acc + x.name.len() as u32 + 1);
         writer.write_all(LMD_PREFIX)?;
         writer.write_all(&(LMD_HEADER_SIZE as u32 + size).to_le_bytes())?;
         writer.write_all(&[0u8, 0, 0, 0])?;
         writer.write_all(&[0u8, 0, 0, 0])?;
         writer.write_all(&TryInto::<u32>::try_into(lmd.version)?.to_le_bytes())?;
-        writer.write_all(&(lmd.db.names.len() as u32).to_le_bytes())?;
+        writer.write_all(&(lmd.db.iter_all().count() as u32).to_le_bytes())?;
 
         Ok(())
     }
 
+    /// Write null terminated names, including all candidates of colliding IDs.
     pub fn write_strings(writer: &mut dyn Write, lmd: &LocalMixDatabase) -> Result<()> {
-        let joint = lmd.db.names.values().fold(String::new(), |mut acc, x| {
-            acc.reserve(x.len() + 1);
-            acc.push_str(x);
+        let joint = lmd.db.iter_all().fold(String::new(), |mut acc, (_, x)| {
+            acc.reserve(x.name.len() + 1);
+            acc.push_str(&x.name);
             acc.push(0 as char);
             acc
         });
@@ -130,9 +188,13 @@ impl LocalMixDbWriter {
     }
 }
 
+/// Provides static methods for reading GMDs (XCC's `global mix database.dat`).
 pub struct GlobalMixDbReader {}
 
 impl GlobalMixDbReader {
+    /// Read a GMD. Every database is a 32-bit name count followed by pairs of
+    /// null terminated names and descriptions. Databases are tagged with games
+    /// from [`GMD_GAMES`] and their IDs are calculated with the CRC of that game.
     pub fn read_file(reader: &mut dyn Read) -> Result<GlobalMixDatabase> {
         // TODO: Might want to use BufRead and read_until(), or keep being a moron.
         // NOTE: The XCC format kinda sucks, because we don't know the size in advance
@@ -148,16 +210,20 @@ impl GlobalMixDbReader {
         reader.read_to_end(&mut buf)?;
         let mut ptr = 0;
         let len = buf.len();
-        let versions = [GameEnum::TD, GameEnum::RA, GameEnum::TS];
         let mut i = 0;
-        while ptr + 4 < len {
-            let (strings, new_ptr) = Self::read_database(&buf, ptr)?;
+        while ptr + 4 <= len {
+            let (entries, new_ptr) = Self::read_database(&buf, ptr)?;
             ptr = new_ptr;
-            // All DBs past the second will use newer CRC.
-            let version = versions[i.max(2)];
-            let mut db = MixDatabase::default();
-            db.names
-                .extend(strings.into_iter().map(|s| (crc(&s, version), s)));
+            let game = GMD_GAMES.get(i).copied();
+            // Unknown DBs past the known ones use newer CRC.
+            let crc_game = game.unwrap_or(GameEnum::TS);
+            let mut db = MixDatabase {
+                game,
+                ..Default::default()
+            };
+            for entry in entries {
+                db.add_entry(crc(&entry.name, crc_game), entry);
+            }
             i += 1;
             gmd.dbs.push(db);
         }
@@ -165,8 +231,10 @@ impl GlobalMixDbReader {
         Ok(gmd)
     }
 
-    pub fn read_database(buf: &[u8], mut ptr: usize) -> Result<(Vec<String>, usize)> {
-        let mut strings = Vec::<String>::new();
+    /// Read one database from the buffer, starting at `ptr`. Returns its entries
+    /// and the position right after it.
+    pub fn read_database(buf: &[u8], mut ptr: usize) -> Result<(Vec<MixDatabaseEntry>, usize)> {
+        let mut entries = Vec::<MixDatabaseEntry>::new();
         let num_names = u32::from_le_bytes(
             buf[ptr..ptr + 4]
                 .try_into()
@@ -178,38 +246,229 @@ impl GlobalMixDbReader {
                 .iter()
                 .position(|x| *x == 0)
                 .ok_or(Error::NoNullTermination(ptr))?;
-            strings.push(String::from_utf8(
-                buf[ptr..ptr + cut]
-                    .try_into()
-                    .unwrap_or_else(|_| unreachable!()),
-            )?); // Won't panic: just slicing a buffer.
+            let name = String::from_utf8(buf[ptr..ptr + cut].to_vec())?;
             ptr += cut + 1;
-            // Just advance the pointer, we don't need the description.
-            match buf[ptr..].iter().position(|x| *x == 0) {
-                Some(x) => ptr += x,
-                _ => return Err(Error::NoNullDescTermination(ptr)),
-            }
+            let cut = buf
+                .get(ptr..)
+                .and_then(|x| x.iter().position(|x| *x == 0))
+                .ok_or(Error::NoNullDescTermination(ptr))?;
+            let description = String::from_utf8(buf[ptr..ptr + cut].to_vec())?;
+            ptr += cut + 1;
+            let entry = match description.is_empty() {
+                true => MixDatabaseEntry::new(name),
+                false => MixDatabaseEntry::with_description(name, description),
+            };
+            entries.push(entry.with_source(NameSourceEnum::Gmd));
         }
 
-        Ok((strings, ptr))
+        Ok((entries, ptr))
     }
 }
 
+/// Provides static methods for writing GMDs (XCC's `global mix database.dat`).
 pub struct GlobalMixDbWriter {}
 
 impl GlobalMixDbWriter {
+    /// Write a GMD. Entries are sorted by name (then ID) so the output is deterministic.
+    /// Databases are written in order, so their games are only kept if they follow [`GMD_GAMES`].
     pub fn write_file(writer: &mut dyn Write, gmd: &GlobalMixDatabase) -> Result<()> {
         for db in &gmd.dbs {
-            writer.write_all(&db.names.len().to_le_bytes())?;
-            let strings = db.names.values().fold(String::default(), |mut acc, s| {
-                acc.reserve(s.len() + 2);
-                acc.push_str(s);
-                acc.push_str("\0\0");
+            let mut entries: Vec<_> = db.iter_all().collect();
+            writer.write_all(&(entries.len() as u32).to_le_bytes())?;
+            entries.sort_by(|(id1, e1), (id2, e2)| e1.name.cmp(&e2.name).then(id1.cmp(id2)));
+            let strings = entries.into_iter().fold(Vec::new(), |mut acc, (_, e)| {
+                let description = e.description.as_deref().unwrap_or_default();
+                acc.reserve(e.name.len() + description.len() + 2);
+                acc.extend_from_slice(e.name.as_bytes());
+                acc.push(0);
+                acc.extend_from_slice(description.as_bytes());
+                acc.push(0);
                 acc
             });
-            writer.write_all(strings.as_bytes())?;
+            writer.write_all(&strings)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn gmd_index() {
+        use rust_alert::{
+            core::GameEnum,
+            mix::db::io::{gmd_index, GMD_GAMES},
+        };
+
+        assert_eq!(GMD_GAMES[gmd_index(GameEnum::YR)], GameEnum::RA2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::{crc, GameEnum},
+        mix::{
+            db::{
+                io::{
+                    Error, GlobalMixDbReader, GlobalMixDbWriter, LocalMixDbReader, LocalMixDbWriter,
+                },
+                GlobalMixDatabase, LMDVersionEnum, LocalMixDatabase, MixDatabase, MixDatabaseEntry,
+                NameSourceEnum,
+            },
+            Mix, LMD_KEY_TD, LMD_KEY_TS,
+        },
+    };
+
+    fn sorted_entries(db: &MixDatabase) -> Vec<(String, Option<String>)> {
+        let mut entries: Vec<_> = db
+            .iter_all()
+            .map(|(_, x)| (x.name.clone(), x.description.clone()))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn lmd_round_trip() {
+        let mut lmd = LocalMixDatabase {
+            version: LMDVersionEnum::TD,
+            ..Default::default()
+        };
+        lmd.db.insert(0x5CB0AAD5u32 as i32, "a10.shp");
+        let mut buf = vec![];
+        LocalMixDbWriter::write_file(&mut buf, &lmd).unwrap();
+
+        let out = LocalMixDbReader::read_file(&mut &buf[..]).unwrap();
+        assert_eq!(out.version, LMDVersionEnum::TD);
+        assert_eq!(sorted_entries(&out.db), sorted_entries(&lmd.db));
+        assert_eq!(
+            out.db.get(0x5CB0AAD5u32 as i32).unwrap().source,
+            NameSourceEnum::Lmd
+        );
+    }
+
+    #[test]
+    fn lmd_from_mix() {
+        let mut lmd = LocalMixDatabase {
+            version: LMDVersionEnum::TS,
+            ..Default::default()
+        };
+        lmd.db.insert(crc("a.shp", GameEnum::TS), "a.shp");
+        let mut buf = vec![];
+        LocalMixDbWriter::write_file(&mut buf, &lmd).unwrap();
+
+        // A TS key is found when looking for a TD LMD too.
+        let mut mix = Mix::default();
+        assert!(LocalMixDbReader::read_from_mix(&mix, GameEnum::TD)
+            .unwrap()
+            .is_none());
+        mix.add_file_raw(buf, LMD_KEY_TS, false).unwrap();
+        let out = LocalMixDbReader::read_from_mix(&mix, GameEnum::TD)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sorted_entries(&out.db), sorted_entries(&lmd.db));
+
+        let mut mix = Mix::default();
+        mix.add_file_raw(vec![0; 4], LMD_KEY_TD, false).unwrap();
+        assert!(LocalMixDbReader::read_from_mix(&mix, GameEnum::TD).is_err());
+    }
+
+    #[test]
+    fn lmd_collisions() {
+        // Both names have the same TD CRC.
+        let id = crc("bbcaa.shp", GameEnum::TD);
+        assert_eq!(id, crc("abcac.shp", GameEnum::TD));
+        let mut lmd = LocalMixDatabase {
+            version: LMDVersionEnum::TD,
+            ..Default::default()
+        };
+        lmd.db.add(id, "bbcaa.shp");
+        lmd.db.add(id, "abcac.shp");
+        let mut buf = vec![];
+        LocalMixDbWriter::write_file(&mut buf, &lmd).unwrap();
+
+        let out = LocalMixDbReader::read_file(&mut &buf[..]).unwrap();
+        assert!(out.db.has_collision(id));
+        assert_eq!(sorted_entries(&out.db), sorted_entries(&lmd.db));
+    }
+
+    #[test]
+    fn gmd_layout() {
+        let mut db = MixDatabase::default();
+        db.insert_entry(2, MixDatabaseEntry::with_description("b", "Desc"));
+        db.insert(1, "a");
+        let gmd = GlobalMixDatabase { dbs: vec![db] };
+        let mut buf = vec![];
+        GlobalMixDbWriter::write_file(&mut buf, &gmd).unwrap();
+
+        assert_eq!(buf, b"\x02\x00\x00\x00a\x00\x00b\x00Desc\x00");
+    }
+
+    #[test]
+    fn gmd_round_trip() {
+        let mut first = MixDatabase::default();
+        first.insert_entry(1, MixDatabaseEntry::with_description("a.shp", "Desc A"));
+        first.insert(2, "b.shp");
+        let mut second = MixDatabase::default();
+        second.insert_entry(3, MixDatabaseEntry::with_description("c.shp", "Desc C"));
+        let gmd = GlobalMixDatabase {
+            dbs: vec![first, MixDatabase::default(), second],
+        };
+        let mut buf = vec![];
+        GlobalMixDbWriter::write_file(&mut buf, &gmd).unwrap();
+
+        let out = GlobalMixDbReader::read_file(&mut &buf[..]).unwrap();
+        assert_eq!(out.dbs.len(), 3);
+        for (a, b) in out.dbs.iter().zip(&gmd.dbs) {
+            assert_eq!(sorted_entries(a), sorted_entries(b));
+        }
+        let mut again = vec![];
+        GlobalMixDbWriter::write_file(&mut again, &out).unwrap();
+        assert_eq!(buf, again);
+    }
+
+    #[test]
+    fn gmd_games() {
+        let names = ["a10.shp", "bomb.shp"];
+        let gmd = GlobalMixDatabase {
+            dbs: (0..5)
+                .map(|i| {
+                    let mut db = MixDatabase::default();
+                    db.insert(i, names[i as usize % 2]);
+                    db
+                })
+                .collect(),
+        };
+        let mut buf = vec![];
+        GlobalMixDbWriter::write_file(&mut buf, &gmd).unwrap();
+
+        let out = GlobalMixDbReader::read_file(&mut &buf[..]).unwrap();
+        let games: Vec<_> = out.dbs.iter().map(|x| x.game).collect();
+        assert_eq!(
+            games,
+            vec![
+                Some(GameEnum::TD),
+                Some(GameEnum::RA),
+                Some(GameEnum::TS),
+                Some(GameEnum::RA2),
+                None
+            ]
+        );
+        for (i, db) in out.dbs.iter().enumerate() {
+            let name = names[i % 2];
+            let game = db.game.unwrap_or(GameEnum::TS);
+            assert_eq!(db.get_name(crc(name, game)).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn gmd_err_no_description() {
+        let buf = b"\x01\x00\x00\x00a\x00Desc";
+        let out = GlobalMixDbReader::read_file(&mut &buf[..]);
+        assert!(matches!(out, Err(Error::NoNullDescTermination(_))));
+    }
+}
@@ -1,6 +1,15 @@
 //! MIX database module.
 
+#[cfg(feature = "embedded-gmd")]
+mod builtin;
+pub mod compact;
 mod core;
+pub mod crack;
+pub mod harvest;
 pub mod io;
+#[cfg(feature = "search")]
+pub mod search;
 
+#[cfg(feature = "embedded-gmd")]
+pub use builtin::{builtin_name, builtin_names};
 pub use core::*;
@@ -0,0 +1,273 @@
+//! Searching MIX databases by name or ID patterns.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    core::GameEnum,
+    mix::db::{GlobalMixDatabase, MixDatabase, MixDatabaseEntry},
+};
+
+/// The error type for search patterns.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The regular expression is not valid.
+    #[error("{0}")]
+    InvalidRegex(#[from] regex::Error),
+    /// The ID prefix is not a hex number of at most 8 digits.
+    #[error("Invalid ID prefix: {0}")]
+    InvalidIdPrefix(String),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// A pattern matching names or IDs. Name patterns ignore case.
+#[derive(Debug, Clone)]
+pub enum SearchPatternEnum {
+    /// Wildcard pattern, where `*` matches any string and `?` any single character.
+    Glob(String),
+    /// Regular expression, matching anywhere in the name unless anchored.
+    Regex(Regex),
+    /// File extension, without the dot.
+    Extension(String),
+    /// Prefix of the ID written as 8 hex digits.
+    IdPrefix(String),
+}
+
+impl SearchPatternEnum {
+    /// Make a wildcard pattern.
+    pub fn glob(pattern: impl Into<String>) -> Self {
+        Self::Glob(pattern.into().to_lowercase())
+    }
+
+    /// Make a regular expression pattern.
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern).case_insensitive(true).build()?;
+        Ok(Self::Regex(regex))
+    }
+
+    /// Make a file extension pattern. A leading dot is ignored.
+    pub fn extension(extension: impl Into<String>) -> Self {
+        let extension = extension.into();
+        Self::Extension(extension.trim_start_matches('.').to_lowercase())
+    }
+
+    /// Make an ID prefix pattern from hex digits.
+    pub fn id_prefix(prefix: &str) -> Result<Self> {
+        if prefix.len() > 8 || !prefix.chars().all(|x| x.is_ascii_hexdigit()) {
+            return Err(Error::InvalidIdPrefix(prefix.to_string()));
+        }
+        Ok(Self::IdPrefix(prefix.to_uppercase()))
+    }
+
+    /// Check if given ID and name match the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::search::SearchPatternEnum;
+    ///
+    /// assert!(SearchPatternEnum::glob("*.SHP").matches(1, "trex.shp"));
+    /// assert!(SearchPatternEnum::glob("tre?.*").matches(1, "trex.shp"));
+    /// assert!(SearchPatternEnum::regex("^t.*x").unwrap().matches(1, "trex.shp"));
+    /// assert!(SearchPatternEnum::extension(".shp").matches(1, "trex.shp"));
+    /// assert!(SearchPatternEnum::id_prefix("00d2").unwrap().matches(0x00D2F7D6, "trex.shp"));
+    /// assert!(!SearchPatternEnum::extension("shp").matches(1, "trex.pal"));
+    /// ```
+    pub fn matches(&self, id: i32, name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => glob_match(pattern, &name.to_lowercase()),
+            Self::Regex(regex) => regex.is_match(name),
+            Self::Extension(extension) => name
+                .rsplit_once('.')
+                .is_some_and(|(_, x)| x.eq_ignore_ascii_case(extension)),
+            Self::IdPrefix(prefix) => format!("{:0>8X}", id).starts_with(prefix.as_str()),
+        }
+    }
+}
+
+/// A single search result from a global MIX database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchHit<'a> {
+    /// Index of the database the entry came from.
+    pub db: usize,
+    /// Game of the database the entry came from.
+    pub game: Option<GameEnum>,
+    /// ID of the entry.
+    pub id: i32,
+    /// The matching entry.
+    pub entry: &'a MixDatabaseEntry,
+}
+
+impl MixDatabase {
+    /// Returns all entries matching given pattern, including all candidate names,
+    /// sorted by ID (as unsigned) and then by name.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::db::{search::SearchPatternEnum, MixDatabase};
+    ///
+    /// let mut db = MixDatabase::default();
+    /// db.insert(2, "b.shp");
+    /// db.insert(1, "a.shp");
+    /// db.insert(3, "c.pal");
+    ///
+    /// let hits = db.search(&SearchPatternEnum::extension("shp"));
+    /// let names: Vec<_> = hits.iter().map(|(_, x)| x.name.as_str()).collect();
+    /// assert_eq!(names, vec!["a.shp", "b.shp"]);
+    /// ```
+    pub fn search(&self, pattern: &SearchPatternEnum) -> Vec<(i32, &MixDatabaseEntry)> {
+        let mut hits: Vec<_> = self
+            .iter_all()
+            .filter(|(id, x)| pattern.matches(**id, &x.name))
+            .map(|(id, x)| (*id, x))
+            .collect();
+        hits.sort_by(|a, b| (a.0 as u32, &a.1.name).cmp(&(b.0 as u32, &b.1.name)));
+        hits
+    }
+}
+
+impl GlobalMixDatabase {
+    /// Returns all entries matching given pattern from all databases, in database order.
+    /// Entries of each database are sorted like in [`MixDatabase::search`].
+    pub fn search(&self, pattern: &SearchPatternEnum) -> Vec<SearchHit<'_>> {
+        self.dbs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, db)| {
+                db.search(pattern)
+                    .into_iter()
+                    .map(move |(id, entry)| SearchHit {
+                        db: i,
+                        game: db.game,
+                        id,
+                        entry,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Match a wildcard pattern against a whole string.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|x| *x == '*')
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn matches() {
+        use rust_alert::mix::db::search::SearchPatternEnum;
+
+        assert!(SearchPatternEnum::glob("*.SHP").matches(1, "trex.shp"));
+        assert!(SearchPatternEnum::glob("tre?.*").matches(1, "trex.shp"));
+        assert!(SearchPatternEnum::regex("^t.*x")
+            .unwrap()
+            .matches(1, "trex.shp"));
+        assert!(SearchPatternEnum::extension(".shp").matches(1, "trex.shp"));
+        assert!(SearchPatternEnum::id_prefix("00d2")
+            .unwrap()
+            .matches(0x00D2F7D6, "trex.shp"));
+        assert!(!SearchPatternEnum::extension("shp").matches(1, "trex.pal"));
+    }
+
+    #[test]
+    fn search() {
+        use rust_alert::mix::db::{search::SearchPatternEnum, MixDatabase};
+
+        let mut db = MixDatabase::default();
+        db.insert(2, "b.shp");
+        db.insert(1, "a.shp");
+        db.insert(3, "c.pal");
+
+        let hits = db.search(&SearchPatternEnum::extension("shp"));
+        let names: Vec<_> = hits.iter().map(|(_, x)| x.name.as_str()).collect();
+        assert_eq!(names, vec!["a.shp", "b.shp"]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        core::GameEnum,
+        mix::db::{
+            search::{glob_match, Error, SearchPatternEnum},
+            GlobalMixDatabase, MixDatabase,
+        },
+    };
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("*.shp", ".shp"));
+        assert!(!glob_match("*.shp", "a.shpx"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(glob_match("**a", "bba"));
+    }
+
+    #[test]
+    fn id_prefix() {
+        let pattern = SearchPatternEnum::id_prefix("FF").unwrap();
+        assert!(pattern.matches(-1, ""));
+        assert!(!pattern.matches(1, ""));
+        assert!(matches!(
+            SearchPatternEnum::id_prefix("G"),
+            Err(Error::InvalidIdPrefix(_))
+        ));
+        assert!(SearchPatternEnum::id_prefix("123456789").is_err());
+        assert!(matches!(
+            SearchPatternEnum::regex("("),
+            Err(Error::InvalidRegex(_))
+        ));
+    }
+
+    #[test]
+    fn search_global() {
+        let mut td = MixDatabase::with_game(GameEnum::TD);
+        td.insert(1, "a.shp");
+        let mut untagged = MixDatabase::default();
+        untagged.add(1, "b.shp");
+        untagged.add(1, "c.shp");
+        untagged.add(-1, "d.pal");
+        let gmd = GlobalMixDatabase {
+            dbs: vec![td, untagged],
+        };
+
+        let hits = gmd.search(&SearchPatternEnum::glob("*.shp"));
+        let hits: Vec<_> = hits
+            .iter()
+            .map(|x| (x.db, x.game, x.id, x.entry.name.as_str()))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                (0, Some(GameEnum::TD), 1, "a.shp"),
+                (1, None, 1, "b.shp"),
+                (1, None, 1, "c.shp"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,470 @@
+//! Content-based file type detection for MIX members.
+
+use crate::{csf::io::CsfPrefixes, mix::db::io::LMD_PREFIX};
+
+/// File formats that can be recognized by looking at file contents.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum FileTypeEnum {
+    /// CSF stringtable.
+    Csf,
+    /// XCC local mix database.
+    Lmd,
+    /// A nested MIX archive.
+    Mix,
+    /// INI text file.
+    Ini,
+    /// 256 color VGA palette.
+    Pal,
+    /// TD/RA SHP image.
+    ShpTd,
+    /// TS/RA2 SHP image.
+    ShpTs,
+    /// Voxel model.
+    Vxl,
+    /// Voxel animation (Hierarchical Voxel Animation).
+    Hva,
+    /// Terrain template (tile set).
+    Tmp,
+    /// Westwood audio.
+    Aud,
+    /// Vector Quantized Animation (video).
+    Vqa,
+    /// RIFF wave audio.
+    Wav,
+    /// PC Paintbrush image.
+    Pcx,
+    /// Bink video.
+    Bik,
+}
+
+impl FileTypeEnum {
+    /// Returns the usual file extension (without a dot) for this file type.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::mix::FileTypeEnum;
+    ///
+    /// assert_eq!(FileTypeEnum::ShpTs.extension(), "shp");
+    /// assert_eq!(FileTypeEnum::Lmd.extension(), "dat");
+    /// ```
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csf => "csf",
+            Self::Lmd => "dat",
+            Self::Mix => "mix",
+            Self::Ini => "ini",
+            Self::Pal => "pal",
+            Self::ShpTd => "shp",
+            Self::ShpTs => "shp",
+            Self::Vxl => "vxl",
+            Self::Hva => "hva",
+            Self::Tmp => "tmp",
+            Self::Aud => "aud",
+            Self::Vqa => "vqa",
+            Self::Wav => "wav",
+            Self::Pcx => "pcx",
+            Self::Bik => "bik",
+        }
+    }
+}
+
+impl std::fmt::Display for FileTypeEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::Csf => "CSF",
+            Self::Lmd => "LMD",
+            Self::Mix => "MIX",
+            Self::Ini => "INI",
+            Self::Pal => "PAL",
+            Self::ShpTd => "SHP (TD)",
+            Self::ShpTs => "SHP (TS)",
+            Self::Vxl => "VXL",
+            Self::Hva => "HVA",
+            Self::Tmp => "TMP",
+            Self::Aud => "AUD",
+            Self::Vqa => "VQA",
+            Self::Wav => "WAV",
+            Self::Pcx => "PCX",
+            Self::Bik => "BIK",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+/// Guesses the type of a file by inspecting its contents.
+///
+/// Formats with a magic prefix are checked first, then formats whose headers
+/// can be validated against the file size, and INI text last. Returns `None`
+/// if the contents don't look like any known format.
+///
+/// # Examples
+///
+/// ```ignore
+/// use rust_alert::mix::{guess_file_type, FileTypeEnum};
+///
+/// assert_eq!(guess_file_type(b"[General]\nName=Test\n"), Some(FileTypeEnum::Ini));
+/// assert_eq!(guess_file_type(&[0xFF, 0xFE]), None);
+/// ```
+pub fn guess_file_type(data: &[u8]) -> Option<FileTypeEnum> {
+    if data.starts_with(CsfPrefixes::CSF_PREFIX) {
+        Some(FileTypeEnum::Csf)
+    } else if data.starts_with(LMD_PREFIX) {
+        Some(FileTypeEnum::Lmd)
+    } else if data.starts_with(b"Voxel Animation\0") {
+        Some(FileTypeEnum::Vxl)
+    } else if data.starts_with(b"FORM") && data.get(8..12) == Some(b"WVQA") {
+        Some(FileTypeEnum::Vqa)
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+        Some(FileTypeEnum::Wav)
+    } else if data.starts_with(b"BIK") {
+        Some(FileTypeEnum::Bik)
+    } else if is_mix(data) {
+        Some(FileTypeEnum::Mix)
+    } else if is_hva(data) {
+        Some(FileTypeEnum::Hva)
+    } else if is_tmp(data) {
+        Some(FileTypeEnum::Tmp)
+    } else if is_aud(data) {
+        Some(FileTypeEnum::Aud)
+    } else if is_shp_ts(data) {
+        Some(FileTypeEnum::ShpTs)
+    } else if is_shp_td(data) {
+        Some(FileTypeEnum::ShpTd)
+    } else if is_pcx(data) {
+        Some(FileTypeEnum::Pcx)
+    } else if is_pal(data) {
+        Some(FileTypeEnum::Pal)
+    } else if is_ini(data) {
+        Some(FileTypeEnum::Ini)
+    } else {
+        None
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes(b.try_into().unwrap_or_else(|_| unreachable!())))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap_or_else(|_| unreachable!())))
+}
+
+/// Checks if all index entries fit in the available body space.
+fn mix_entries_fit(data: &[u8], index_start: usize, num_files: usize, body_size: usize) -> bool {
+    (0..num_files).all(|i| {
+        let entry = index_start + i * 12;
+        match (read_u32(data, entry + 4), read_u32(data, entry + 8)) {
+            (Some(offset), Some(size)) => offset as usize + size as usize <= body_size,
+            _ => false,
+        }
+    })
+}
+
+fn is_mix(data: &[u8]) -> bool {
+    let Some(first) = read_u16(data, 0) else {
+        return false;
+    };
+    if first == 0 {
+        // New MIX format (>=RA).
+        let Some(flags) = read_u16(data, 2) else {
+            return false;
+        };
+        if flags & !0x0003 != 0 {
+            return false;
+        }
+        if flags & 0x0002 != 0 {
+            // Encrypted, we can only check if the key and the first block fit.
+            return data.len() >= 4 + 80 + 8;
+        }
+        let (Some(num_files), Some(_)) = (read_u16(data, 4), read_u32(data, 6)) else {
+            return false;
+        };
+        let num_files = num_files as usize;
+        let checksum = if flags & 0x0001 != 0 { 20 } else { 0 };
+        let index_end = 10 + num_files * 12;
+        num_files > 0
+            && index_end + checksum <= data.len()
+            && mix_entries_fit(data, 10, num_files, data.len() - index_end - checksum)
+    } else {
+        // Old MIX format (TD).
+        let Some(body_size) = read_u32(data, 2) else {
+            return false;
+        };
+        let num_files = first as usize;
+        let index_end = 6 + num_files * 12;
+        index_end + body_size as usize == data.len()
+            && mix_entries_fit(data, 6, num_files, body_size as usize)
+    }
+}
+
+fn is_hva(data: &[u8]) -> bool {
+    match (read_u32(data, 16), read_u32(data, 20)) {
+        (Some(frames), Some(sections)) if frames > 0 && sections > 0 => {
+            let (frames, sections) = (frames as u128, sections as u128);
+            24 + sections * 16 + frames * sections * 48 == data.len() as u128
+        }
+        _ => false,
+    }
+}
+
+fn is_tmp(data: &[u8]) -> bool {
+    // TS/RA2 isometric template.
+    if let (Some(x), Some(y), Some(cx), Some(cy)) = (
+        read_u32(data, 0),
+        read_u32(data, 4),
+        read_u32(data, 8),
+        read_u32(data, 12),
+    ) {
+        if matches!((cx, cy), (48, 24) | (60, 30))
+            && x > 0
+            && y > 0
+            && 16 + (x as u128 * y as u128) * 4 <= data.len() as u128
+        {
+            return true;
+        }
+    }
+    // TD/RA square template, where the declared size is in a different spot.
+    match (read_u16(data, 0), read_u16(data, 2), read_u16(data, 4)) {
+        (Some(24), Some(24), Some(count)) if count > 0 => {
+            let len = data.len() as u32;
+            read_u32(data, 8) == Some(len) || read_u32(data, 12) == Some(len)
+        }
+        _ => false,
+    }
+}
+
+fn is_aud(data: &[u8]) -> bool {
+    match (
+        read_u16(data, 0),
+        read_u32(data, 2),
+        data.get(10),
+        data.get(11),
+    ) {
+        (Some(rate), Some(size), Some(flags), Some(compression)) => {
+            rate > 0
+                && size as usize + 12 == data.len()
+                && flags & !0x03 == 0
+                && matches!(compression, 1 | 99)
+        }
+        _ => false,
+    }
+}
+
+fn is_shp_ts(data: &[u8]) -> bool {
+    let (Some(0), Some(width), Some(height), Some(num_frames)) = (
+        read_u16(data, 0),
+        read_u16(data, 2),
+        read_u16(data, 4),
+        read_u16(data, 6),
+    ) else {
+        return false;
+    };
+    let header_end = 8 + num_frames as usize * 24;
+    if width == 0 || height == 0 || num_frames == 0 || header_end > data.len() {
+        return false;
+    }
+    (0..num_frames as usize).all(|i| {
+        let frame = 8 + i * 24;
+        match (
+            read_u16(data, frame),
+            read_u16(data, frame + 2),
+            read_u16(data, frame + 4),
+            read_u16(data, frame + 6),
+            read_u32(data, frame + 8),
+            read_u32(data, frame + 20),
+        ) {
+            (Some(x), Some(y), Some(w), Some(h), Some(flags), Some(offset)) => {
+                x as u32 + w as u32 <= width as u32
+                    && y as u32 + h as u32 <= height as u32
+                    && flags & !0x03 == 0
+                    && (offset == 0
+                        || (offset as usize >= header_end && (offset as usize) < data.len()))
+            }
+            _ => false,
+        }
+    })
+}
+
+fn is_shp_td(data: &[u8]) -> bool {
+    let (Some(num_frames), Some(width), Some(height)) =
+        (read_u16(data, 0), read_u16(data, 6), read_u16(data, 8))
+    else {
+        return false;
+    };
+    let num_frames = num_frames as usize;
+    let header_end = 14 + (num_frames + 2) * 8;
+    if num_frames == 0 || width == 0 || height == 0 || header_end > data.len() {
+        return false;
+    }
+    // The offset list ends with an entry pointing at the end of the file.
+    let first = read_u32(data, 14).map(|x| x & 0x00FF_FFFF);
+    let last = read_u32(data, 14 + num_frames * 8).map(|x| x & 0x00FF_FFFF);
+    first == Some(header_end as u32) && last == Some(data.len() as u32)
+}
+
+fn is_pcx(data: &[u8]) -> bool {
+    data.len() >= 128
+        && data[0] == 0x0A
+        && matches!(data[1], 0 | 2 | 3 | 4 | 5)
+        && data[2] == 1
+        && matches!(data[3], 1 | 2 | 4 | 8)
+}
+
+fn is_pal(data: &[u8]) -> bool {
+    // 256 RGB entries with 6 bits per channel.
+    data.len() == 768 && data.iter().all(|x| *x < 64)
+}
+
+fn is_ini(data: &[u8]) -> bool {
+    let is_text = data
+        .iter()
+        .all(|x| *x >= 0x20 || matches!(x, b'\t' | b'\r' | b'\n' | 0x1A));
+    is_text
+        && data.split(|x| *x == b'\n').any(|line| {
+            let line = line.trim_ascii();
+            line.starts_with(b"[") && line.contains(&b']')
+        })
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn extension() {
+        use rust_alert::mix::FileTypeEnum;
+
+        assert_eq!(FileTypeEnum::ShpTs.extension(), "shp");
+        assert_eq!(FileTypeEnum::Lmd.extension(), "dat");
+    }
+
+    #[test]
+    fn guess_file_type() {
+        use rust_alert::mix::{guess_file_type, FileTypeEnum};
+
+        assert_eq!(
+            guess_file_type(b"[General]\nName=Test\n"),
+            Some(FileTypeEnum::Ini)
+        );
+        assert_eq!(guess_file_type(&[0xFF, 0xFE]), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mix::{
+        db::{io::LocalMixDbWriter, LocalMixDatabase},
+        guess_file_type,
+        io::MixWriter,
+        FileTypeEnum, Mix,
+    };
+
+    #[test]
+    fn guess_csf() {
+        let data = std::fs::read("../test_data/example.csf").unwrap();
+        assert_eq!(guess_file_type(&data), Some(FileTypeEnum::Csf));
+    }
+
+    #[test]
+    fn guess_lmd() {
+        let mut data = vec![];
+        LocalMixDbWriter::write_file(&mut data, &LocalMixDatabase::default()).unwrap();
+        assert_eq!(guess_file_type(&data), Some(FileTypeEnum::Lmd));
+    }
+
+    #[test]
+    fn guess_mix() {
+        for new_format in [false, true] {
+            let mut mix = Mix::default();
+            mix.add_file_raw(vec![1, 2, 3], 1, false).unwrap();
+            mix.add_file_raw(vec![4, 5], 2, false).unwrap();
+            let mut data = vec![];
+            MixWriter::write_file(&mut data, &mut mix, new_format).unwrap();
+            assert_eq!(guess_file_type(&data), Some(FileTypeEnum::Mix));
+        }
+    }
+
+    #[test]
+    fn guess_magic() {
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        wav.resize(44, 0);
+        assert_eq!(guess_file_type(&wav), Some(FileTypeEnum::Wav));
+        let vqa = b"FORM\0\0\0\0WVQAVQHD".to_vec();
+        assert_eq!(guess_file_type(&vqa), Some(FileTypeEnum::Vqa));
+        let vxl = b"Voxel Animation\0\x01\0\0\0".to_vec();
+        assert_eq!(guess_file_type(&vxl), Some(FileTypeEnum::Vxl));
+        assert_eq!(guess_file_type(b"BIKi\0\0\0\0"), Some(FileTypeEnum::Bik));
+    }
+
+    #[test]
+    fn guess_pal() {
+        assert_eq!(guess_file_type(&[63u8; 768]), Some(FileTypeEnum::Pal));
+        assert_eq!(guess_file_type(&[64u8; 768]), None);
+    }
+
+    #[test]
+    fn guess_aud() {
+        let mut aud = vec![0x22, 0x56, 4, 0, 0, 0, 16, 0, 0, 0, 0, 99];
+        aud.extend_from_slice(&[0, 1, 2, 3]);
+        assert_eq!(guess_file_type(&aud), Some(FileTypeEnum::Aud));
+    }
+
+    #[test]
+    fn guess_shp_ts() {
+        // 4x4 image with one 2x2 frame.
+        let mut shp = vec![0, 0, 4, 0, 4, 0, 1, 0];
+        shp.extend_from_slice(&[1, 0, 1, 0, 2, 0, 2, 0]);
+        shp.extend_from_slice(&[0u8; 12]);
+        shp.extend_from_slice(&32u32.to_le_bytes());
+        shp.extend_from_slice(&[0u8; 4]);
+        assert_eq!(guess_file_type(&shp), Some(FileTypeEnum::ShpTs));
+    }
+
+    #[test]
+    fn guess_shp_td() {
+        // One frame, offset list has 3 entries.
+        let mut shp = vec![1, 0, 0, 0, 0, 0, 2, 0, 2, 0, 4, 0, 0, 0];
+        shp.extend_from_slice(&(38u32 | 0x8000_0000).to_le_bytes());
+        shp.extend_from_slice(&[0u8; 4]);
+        shp.extend_from_slice(&42u32.to_le_bytes());
+        shp.extend_from_slice(&[0u8; 4]);
+        shp.extend_from_slice(&[0u8; 8]);
+        shp.extend_from_slice(&[0u8; 4]);
+        assert_eq!(guess_file_type(&shp), Some(FileTypeEnum::ShpTd));
+    }
+
+    #[test]
+    fn guess_hva() {
+        let mut hva = vec![0u8; 16];
+        hva.extend_from_slice(&1u32.to_le_bytes());
+        hva.extend_from_slice(&1u32.to_le_bytes());
+        hva.extend_from_slice(&[0u8; 16 + 48]);
+        assert_eq!(guess_file_type(&hva), Some(FileTypeEnum::Hva));
+    }
+
+    #[test]
+    fn guess_tmp() {
+        let mut tmp = vec![];
+        for x in [1u32, 1, 48, 24, 20] {
+            tmp.extend_from_slice(&x.to_le_bytes());
+        }
+        assert_eq!(guess_file_type(&tmp), Some(FileTypeEnum::Tmp));
+    }
+
+    #[test]
+    fn guess_pcx() {
+        let mut pcx = vec![0x0A, 5, 1, 8];
+        pcx.resize(128, 0);
+        assert_eq!(guess_file_type(&pcx), Some(FileTypeEnum::Pcx));
+    }
+
+    #[test]
+    fn guess_unknown() {
+        assert_eq!(guess_file_type(&[]), None);
+        assert_eq!(guess_file_type(b"Name=Test\n"), None);
+        assert_eq!(guess_file_type(&[0u8, 1, 2, 3, 4, 5, 6, 7, 8]), None);
+    }
+}
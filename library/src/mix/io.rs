@@ -1,8 +1,10 @@
 //! MIX I/O.
 
 use std::{
+    fs::File,
     io::{Read, Write},
     mem::size_of,
+    path::Path,
 };
 
 use blowfish::{
@@ -13,6 +15,7 @@ use num_bigint::BigUint;
 use rand;
 
 use crate::{
+    core::IndexOrderEnum,
     defaultarray,
     mix::{BlowfishKey, Checksum, Mix, MixHeaderFlags, MixIndexEntry},
 };
@@ -72,6 +75,8 @@ type HeaderReturnType = (Mix, u16, Option<(BlowfishKey, Blowfish, [u8; 2])>);
 impl MixReader {
     /// Create a MIX from given byte input. Note: in order to guard against incorrect
     /// body size declaration, input **will be read until EOF**.
+    /// If the index is sorted signed but not unsigned (as in TD and RA), the MIX
+    /// keeps the signed [`IndexOrderEnum`] so it's written back the same way.
     pub fn read_file(reader: &mut dyn Read, force_new_format: bool) -> Result<Mix> {
         // Read header.
         let (mut mix, num_files, blowfish_data) = Self::read_header(reader, force_new_format)?;
@@ -83,6 +88,11 @@ impl MixReader {
             Self::read_index(reader, num_files)
         }?;
         mix.index.extend(index.drain(..).map(|f| (f.id, f)));
+        if !mix.is_index_ordered(IndexOrderEnum::Unsigned)
+            && mix.is_index_ordered(IndexOrderEnum::Signed)
+        {
+            mix.index_order = IndexOrderEnum::Signed;
+        }
         // Read body.
         reader.read_to_end(&mut mix.body)?;
         // Read the checksum if available.
@@ -94,6 +104,12 @@ impl MixReader {
         Ok(mix)
     }
 
+    /// Open and read a MIX file. See [`read_file`][Self::read_file].
+    pub fn read_path(path: impl AsRef<Path>, force_new_format: bool) -> Result<Mix> {
+        let mut reader = File::open(path)?;
+        Self::read_file(&mut reader, force_new_format)
+    }
+
     /// Read the MIX header.
     pub fn read_header(reader: &mut dyn Read, force_new_format: bool) -> Result<HeaderReturnType> {
         let mut buf = [0u8; size_of::<u16>()];
@@ -264,7 +280,7 @@ impl MixWriter {
         mix: &mut Mix,
         key: &BlowfishKey,
     ) -> Result<()> {
-        mix.sort_by_id();
+        mix.sort_by_index_order();
         let size = size_of::<u16>() + size_of::<u32>() + mix.get_index_size();
         let fullsize = size.next_multiple_of(BLOWFISH_BLOCK_SIZE);
         let pad = fullsize - size;
@@ -288,7 +304,7 @@ impl MixWriter {
     }
 
     pub fn write_index(writer: &mut dyn Write, mix: &mut Mix) -> Result<()> {
-        mix.sort_by_id();
+        mix.sort_by_index_order();
         for entry in mix.index.values() {
             Self::write_index_entry(writer, entry)?;
         }
@@ -361,9 +377,10 @@ pub fn generate_blowfish() -> BlowfishKey {
 #[cfg(test)]
 mod tests {
     use crate::{
+        core::{GameEnum, IndexOrderEnum},
         mix::{
-            io::{decrypt_blowfish, encrypt_blowfish, BlowfishKeyEncrypted},
-            BlowfishKey,
+            io::{decrypt_blowfish, encrypt_blowfish, BlowfishKeyEncrypted, MixReader, MixWriter},
+            BlowfishKey, Mix,
         },
         unwrap_ref_assert,
     };
@@ -399,4 +416,28 @@ mod tests {
         assert!(decrypted.is_ok());
         unwrap_ref_assert!(decrypted, DECRYPTED_KEY);
     }
+
+    #[test]
+    /// Test that the index is written in the order of the target game and the order survives reading.
+    fn index_order() {
+        for (game, first) in [(GameEnum::TD, -1), (GameEnum::YR, 1)] {
+            let mut mix = Mix {
+                index_order: game.profile().index_order,
+                ..Default::default()
+            };
+            mix.add_file_raw(vec![0], 1, false).unwrap();
+            mix.add_file_raw(vec![1], -1, false).unwrap();
+            mix.add_file_raw(vec![2], 2, false).unwrap();
+            let mut data = vec![];
+            MixWriter::write_file(&mut data, &mut mix, false).unwrap();
+            assert_eq!(i32::from_le_bytes(data[6..10].try_into().unwrap()), first);
+
+            let mut read = MixReader::read_file(&mut data.as_slice(), false).unwrap();
+            assert_eq!(read.index_order, game.profile().index_order);
+            assert!(read.validate().is_empty());
+            read.sort_by_index_order();
+            assert_eq!(read.index.keys().next(), Some(&first));
+        }
+        assert_eq!(GameEnum::RA.profile().index_order, IndexOrderEnum::Signed);
+    }
 }
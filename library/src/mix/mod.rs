@@ -2,6 +2,8 @@
 
 mod core;
 pub mod db;
+mod filetype;
 pub mod io;
 
 pub use core::*;
+pub use filetype::*;
@@ -0,0 +1,132 @@
+//! PO structures and manipulation.
+
+use indexmap::IndexMap;
+
+/// A gettext PO file is a catalog of messages with their translations.
+/// POT files (templates) are PO files with no translations.
+/// Header fields and entries keep their order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoFile {
+    /// Header fields, such as `Language` or `Content-Type`.
+    pub header: IndexMap<String, String>,
+    /// List of entries.
+    pub entries: Vec<PoEntry>,
+}
+
+impl PoFile {
+    /// Look up the first entry with given context.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::po::{PoEntry, PoFile};
+    ///
+    /// let mut po = PoFile::default();
+    /// po.entries.push(PoEntry::new("GUI:Quit", "Quit", "Beenden"));
+    ///
+    /// assert_eq!(po.get("GUI:Quit").unwrap().string, "Beenden");
+    /// assert!(po.get("GUI:Load").is_none());
+    /// ```
+    pub fn get(&self, context: impl AsRef<str>) -> Option<&PoEntry> {
+        let context = context.as_ref();
+        self.entries
+            .iter()
+            .find(|x| x.context.as_deref() == Some(context))
+    }
+
+    /// Returns the number of entries, not counting the header.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A single message of a PO file with its translation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoEntry {
+    /// Translator comments (`# ...`).
+    pub comments: Vec<String>,
+    /// Comments extracted from the source (`#. ...`).
+    pub extracted_comments: Vec<String>,
+    /// Flags, such as `fuzzy` (`#, ...`).
+    pub flags: Vec<String>,
+    /// Message context (`msgctxt`), which tells apart identical messages.
+    pub context: Option<String>,
+    /// Untranslated message (`msgid`).
+    pub id: String,
+    /// Translated message (`msgstr`). Empty if not translated.
+    pub string: String,
+}
+
+impl PoEntry {
+    /// Creates a new entry with a context, message and its translation.
+    pub fn new(
+        context: impl Into<String>,
+        id: impl Into<String>,
+        string: impl Into<String>,
+    ) -> Self {
+        Self {
+            context: Some(context.into()),
+            id: id.into(),
+            string: string.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the entry is marked as fuzzy, so its translation needs review.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::po::PoEntry;
+    ///
+    /// let mut entry = PoEntry::new("GUI:Quit", "Quit", "Beenden");
+    /// assert!(entry.is_translated());
+    ///
+    /// entry.flags.push("fuzzy".to_string());
+    /// assert!(entry.is_fuzzy());
+    /// assert!(!entry.is_translated());
+    /// ```
+    pub fn is_fuzzy(&self) -> bool {
+        self.flags.iter().any(|x| x == "fuzzy")
+    }
+
+    /// Returns `true` if the entry has a translation which is not fuzzy.
+    pub fn is_translated(&self) -> bool {
+        !self.string.is_empty() && !self.is_fuzzy()
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn get() {
+        use rust_alert::po::{PoEntry, PoFile};
+
+        let mut po = PoFile::default();
+        po.entries.push(PoEntry::new("GUI:Quit", "Quit", "Beenden"));
+
+        assert_eq!(po.get("GUI:Quit").unwrap().string, "Beenden");
+        assert!(po.get("GUI:Load").is_none());
+    }
+
+    #[test]
+    fn is_fuzzy() {
+        use rust_alert::po::PoEntry;
+
+        let mut entry = PoEntry::new("GUI:Quit", "Quit", "Beenden");
+        assert!(entry.is_translated());
+
+        entry.flags.push("fuzzy".to_string());
+        assert!(entry.is_fuzzy());
+        assert!(!entry.is_translated());
+    }
+}
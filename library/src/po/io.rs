@@ -0,0 +1,387 @@
+//! PO I/O.
+
+use std::io::{BufRead, Write};
+
+use crate::po::{PoEntry, PoFile};
+
+/// The error type for serialization and deserialization of PO files.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A [`std::io::Error`].
+    #[error("{0}")]
+    IO(#[from] std::io::Error),
+    /// A line is neither a comment, a keyword nor a string continuation.
+    #[error("Unexpected line {0}")]
+    UnexpectedLine(usize),
+    /// A string is not enclosed in double quotes.
+    #[error("Invalid string at line {0}")]
+    InvalidString(usize),
+    /// A `msgstr` was not preceded by a `msgid`.
+    #[error("Missing msgid at line {0}")]
+    MissingMsgid(usize),
+    /// An entry ended without a `msgstr`.
+    #[error("Missing msgstr at line {0}")]
+    MissingMsgstr(usize),
+    /// An entry has plural forms, which are not supported.
+    #[error("Plural forms are not supported, at line {0}")]
+    UnsupportedPlural(usize),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Entry field which string continuation lines are appended to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldEnum {
+    Context,
+    Id,
+    String,
+}
+
+/// Provides static methods for reading PO files.
+#[derive(Debug, Default)]
+pub struct PoReader {}
+
+impl PoReader {
+    /// Read and parse a PO file from input. The header entry (with an empty `msgid` and
+    /// no context) is parsed into header fields. Obsolete entries and references are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::po::io::PoReader;
+    ///
+    /// let buf = "#, fuzzy\nmsgctxt \"GUI:Quit\"\nmsgid \"Quit\"\nmsgstr \"Beenden\"\n";
+    ///
+    /// let po = PoReader::read_file(buf.as_bytes()).unwrap();
+    /// let entry = po.get("GUI:Quit").unwrap();
+    /// assert_eq!(entry.string, "Beenden");
+    /// assert!(entry.is_fuzzy());
+    /// ```
+    pub fn read_file(reader: impl BufRead) -> Result<PoFile> {
+        let mut po = PoFile::default();
+        let mut entry = PoEntry::default();
+        let mut field: Option<FieldEnum> = None;
+        let mut rows = 0;
+
+        for (row, line) in reader.lines().enumerate() {
+            rows = row + 1;
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            // A comment or keyword after a msgstr starts a new entry.
+            let starts_entry =
+                line.starts_with('#') || line.starts_with("msgctxt") || line.starts_with("msgid ");
+            if starts_entry && field == Some(FieldEnum::String) {
+                Self::finish_entry(&mut po, std::mem::take(&mut entry));
+                field = None;
+            }
+
+            if let Some(comment) = line.strip_prefix('#') {
+                if field.is_some() {
+                    return Err(Error::UnexpectedLine(row));
+                }
+                Self::parse_comment(&mut entry, comment);
+                continue;
+            }
+
+            let (keyword, value) = match line.split_once(char::is_whitespace) {
+                _ if line.starts_with('"') => (None, line),
+                Some((keyword, value)) => (Some(keyword), value.trim_start()),
+                None => return Err(Error::UnexpectedLine(row)),
+            };
+            let value = Self::parse_string(value, row)?;
+            match keyword {
+                Some("msgctxt") if field.is_none() => {
+                    entry.context = Some(value);
+                    field = Some(FieldEnum::Context);
+                }
+                Some("msgid") if field != Some(FieldEnum::Id) => {
+                    entry.id = value;
+                    field = Some(FieldEnum::Id);
+                }
+                Some("msgstr") if field == Some(FieldEnum::Id) => {
+                    entry.string = value;
+                    field = Some(FieldEnum::String);
+                }
+                Some("msgstr") => return Err(Error::MissingMsgid(row)),
+                Some(x) if x == "msgid_plural" || x.starts_with("msgstr[") => {
+                    return Err(Error::UnsupportedPlural(row))
+                }
+                None => match field {
+                    Some(FieldEnum::Context) => {
+                        entry.context.get_or_insert_default().push_str(&value)
+                    }
+                    Some(FieldEnum::Id) => entry.id.push_str(&value),
+                    Some(FieldEnum::String) => entry.string.push_str(&value),
+                    None => return Err(Error::UnexpectedLine(row)),
+                },
+                Some(_) => return Err(Error::UnexpectedLine(row)),
+            }
+        }
+
+        match field {
+            Some(FieldEnum::String) => Self::finish_entry(&mut po, entry),
+            Some(_) => return Err(Error::MissingMsgstr(rows)),
+            None => (),
+        }
+        Ok(po)
+    }
+
+    /// Parse a comment line without the leading `#` into the entry.
+    fn parse_comment(entry: &mut PoEntry, comment: &str) {
+        match comment.chars().next() {
+            Some(',') => entry.flags.extend(
+                comment[1..]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(String::from),
+            ),
+            Some('.') => entry
+                .extracted_comments
+                .push(comment[1..].trim_start().to_string()),
+            // References, previous messages and obsolete entries.
+            Some(':') | Some('|') | Some('~') => (),
+            _ => entry
+                .comments
+                .push(comment.strip_prefix(' ').unwrap_or(comment).to_string()),
+        }
+    }
+
+    /// Add a complete entry to the file, or parse it as the header.
+    fn finish_entry(po: &mut PoFile, entry: PoEntry) {
+        if entry.context.is_none() && entry.id.is_empty() {
+            for line in entry.string.lines() {
+                if let Some((key, value)) = line.split_once(':') {
+                    po.header
+                        .insert(key.trim().to_string(), value.trim().to_string());
+                }
+            }
+        } else {
+            po.entries.push(entry);
+        }
+    }
+
+    /// Parse a quoted and escaped string.
+    fn parse_string(string: &str, row: usize) -> Result<String> {
+        string
+            .strip_prefix('"')
+            .and_then(|x| x.strip_suffix('"'))
+            .map(unescape)
+            .ok_or(Error::InvalidString(row))
+    }
+}
+
+/// Provides static methods for writing PO files.
+pub struct PoWriter {}
+
+impl PoWriter {
+    /// Write a PO file to output. Multi-line strings are split into one line per line break.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::po::{io::PoWriter, PoEntry, PoFile};
+    ///
+    /// let mut po = PoFile::default();
+    /// po.entries.push(PoEntry::new("GUI:Quit", "Quit", ""));
+    /// let mut writer = vec![];
+    ///
+    /// PoWriter::write_file(&po, &mut writer).unwrap();
+    /// assert_eq!(writer, "msgctxt \"GUI:Quit\"\nmsgid \"Quit\"\nmsgstr \"\"\n\n".as_bytes());
+    /// ```
+    pub fn write_file(po: &PoFile, writer: &mut impl Write) -> Result<()> {
+        if !po.header.is_empty() {
+            let header: String = po
+                .header
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}\n"))
+                .collect();
+            Self::write_string(writer, "msgid", "")?;
+            Self::write_string(writer, "msgstr", &header)?;
+            writeln!(writer)?;
+        }
+        for entry in &po.entries {
+            for comment in &entry.comments {
+                writeln!(writer, "# {comment}")?;
+            }
+            for comment in &entry.extracted_comments {
+                writeln!(writer, "#. {comment}")?;
+            }
+            if !entry.flags.is_empty() {
+                writeln!(writer, "#, {}", entry.flags.join(", "))?;
+            }
+            if let Some(context) = &entry.context {
+                Self::write_string(writer, "msgctxt", context)?;
+            }
+            Self::write_string(writer, "msgid", &entry.id)?;
+            Self::write_string(writer, "msgstr", &entry.string)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Write a keyword with a string, split into lines if it has more than one.
+    fn write_string(writer: &mut impl Write, keyword: &str, string: &str) -> Result<()> {
+        let lines: Vec<_> = string.split_inclusive('\n').collect();
+        if lines.len() <= 1 {
+            writeln!(writer, "{keyword} \"{}\"", escape(string))?;
+        } else {
+            writeln!(writer, "{keyword} \"\"")?;
+            for line in lines {
+                writeln!(writer, "\"{}\"", escape(line))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escape a string with C escape sequences.
+fn escape(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Reverse [`escape`]. Unknown escapes are kept as they are.
+fn unescape(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some(x @ ('\\' | '"')) => result.push(x),
+            Some(x) => {
+                result.push('\\');
+                result.push(x);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn read_file() {
+        use rust_alert::po::io::PoReader;
+
+        let buf = "#, fuzzy\nmsgctxt \"GUI:Quit\"\nmsgid \"Quit\"\nmsgstr \"Beenden\"\n";
+
+        let po = PoReader::read_file(buf.as_bytes()).unwrap();
+        let entry = po.get("GUI:Quit").unwrap();
+        assert_eq!(entry.string, "Beenden");
+        assert!(entry.is_fuzzy());
+    }
+
+    #[test]
+    fn write_file() {
+        use rust_alert::po::{io::PoWriter, PoEntry, PoFile};
+
+        let mut po = PoFile::default();
+        po.entries.push(PoEntry::new("GUI:Quit", "Quit", ""));
+        let mut writer = vec![];
+
+        PoWriter::write_file(&po, &mut writer).unwrap();
+        assert_eq!(
+            writer,
+            "msgctxt \"GUI:Quit\"\nmsgid \"Quit\"\nmsgstr \"\"\n\n".as_bytes()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::po::{
+        io::{escape, unescape, Error, PoReader, PoWriter},
+        PoEntry, PoFile,
+    };
+
+    #[test]
+    fn read_write() {
+        let mut po = PoFile::default();
+        po.header.insert("Language".to_string(), "de".to_string());
+        po.header.insert(
+            "Content-Type".to_string(),
+            "text/plain; charset=UTF-8".to_string(),
+        );
+        let mut entry = PoEntry::new("GUI:Text", "Line 1\nLine \"2\"\n", "Zeile 1\n\tZeile 2");
+        entry.comments.push("Checked".to_string());
+        entry.extracted_comments.push("Extra".to_string());
+        entry.flags.push("fuzzy".to_string());
+        po.entries.push(entry);
+        po.entries.push(PoEntry {
+            id: "No context".to_string(),
+            ..Default::default()
+        });
+
+        let mut buf = vec![];
+        PoWriter::write_file(&po, &mut buf).unwrap();
+        let actual = PoReader::read_file(buf.as_slice()).unwrap();
+        assert_eq!(actual, po);
+    }
+
+    #[test]
+    fn read_without_blank_lines() {
+        let buf = "msgid \"\"\nmsgstr \"Language: fr\\n\"\n# Comment\nmsgid \"A\"\nmsgstr \"\"\n\"B\"\nmsgctxt \"C\"\nmsgid \"D\"\nmsgstr \"E\"\n#~ msgid \"Old\"\n";
+        let po = PoReader::read_file(buf.as_bytes()).unwrap();
+        assert_eq!(po.header.get("Language").map(String::as_str), Some("fr"));
+        assert_eq!(po.len(), 2);
+        assert_eq!(po.entries[0].comments, vec!["Comment"]);
+        assert_eq!(po.entries[0].string, "B");
+        assert_eq!(po.get("C").unwrap().string, "E");
+    }
+
+    #[test]
+    fn read_errors() {
+        let cases = [
+            ("msgstr \"A\"", "MissingMsgid"),
+            ("msgid \"A\"", "MissingMsgstr"),
+            ("msgid A\nmsgstr \"\"", "InvalidString"),
+            ("msgid \"A\"\nmsgid_plural \"B\"", "UnsupportedPlural"),
+            ("\"A\"", "UnexpectedLine"),
+            ("msgid \"A\"\n# Comment\nmsgstr \"\"", "UnexpectedLine"),
+            ("unknown \"A\"", "UnexpectedLine"),
+        ];
+        for (text, expected) in cases {
+            let actual = match PoReader::read_file(text.as_bytes()) {
+                Err(Error::MissingMsgid(_)) => "MissingMsgid",
+                Err(Error::MissingMsgstr(_)) => "MissingMsgstr",
+                Err(Error::InvalidString(_)) => "InvalidString",
+                Err(Error::UnsupportedPlural(_)) => "UnsupportedPlural",
+                Err(Error::UnexpectedLine(_)) => "UnexpectedLine",
+                Err(Error::IO(_)) => "IO",
+                Ok(_) => "Ok",
+            };
+            assert_eq!(actual, expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn escapes() {
+        let string = "a\\b\"c\nd\re\tf";
+        assert_eq!(escape(string), "a\\\\b\\\"c\\nd\\re\\tf");
+        assert_eq!(unescape(&escape(string)), string);
+        assert_eq!(unescape("\\q\\"), "\\q\\");
+    }
+}
@@ -0,0 +1,8 @@
+//! Gettext PO (translation catalog) module.
+//!
+//! Also see the [gettext manual](https://www.gnu.org/software/gettext/manual/html_node/PO-Files.html).
+
+mod core;
+pub mod io;
+
+pub use core::*;
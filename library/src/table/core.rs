@@ -0,0 +1,83 @@
+//! Table structures and manipulation.
+
+use std::fmt::Display;
+
+/// Format of a table file.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableFormatEnum {
+    /// Comma-separated values.
+    #[default]
+    Csv,
+    /// Tab-separated values.
+    Tsv,
+}
+
+impl TableFormatEnum {
+    /// Returns the character separating cells of a row.
+    pub fn delimiter(&self) -> char {
+        match self {
+            Self::Csv => ',',
+            Self::Tsv => '\t',
+        }
+    }
+}
+
+impl Display for TableFormatEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+/// A table of text cells. Rows can have different lengths.
+///
+/// Empty cells are `None`, which tells them apart from cells with quoted
+/// empty strings (`""`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableFile {
+    /// List of rows.
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+impl TableFile {
+    /// Look up a cell by its row and column. Returns `None` for empty and missing cells.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::table::TableFile;
+    ///
+    /// let mut table = TableFile::default();
+    /// table.rows.push(vec![Some("A".to_string()), None]);
+    ///
+    /// assert_eq!(table.get(0, 0), Some("A"));
+    /// assert_eq!(table.get(0, 1), None);
+    /// assert_eq!(table.get(1, 0), None);
+    /// ```
+    pub fn get(&self, row: usize, column: usize) -> Option<&str> {
+        self.rows.get(row)?.get(column)?.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn get() {
+        use rust_alert::table::TableFile;
+
+        let mut table = TableFile::default();
+        table.rows.push(vec![Some("A".to_string()), None]);
+
+        assert_eq!(table.get(0, 0), Some("A"));
+        assert_eq!(table.get(0, 1), None);
+        assert_eq!(table.get(1, 0), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Table I/O.
+
+use std::io::{Read, Write};
+
+use crate::table::{TableFile, TableFormatEnum};
+
+/// The error type for serialization and deserialization of table files.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A [`std::io::Error`].
+    #[error("{0}")]
+    IO(#[from] std::io::Error),
+    /// A quoted cell is not closed until the end of file.
+    #[error("Unterminated quoted cell starting at line {0}")]
+    UnterminatedQuote(usize),
+    /// A quoted cell is followed by something other than a delimiter or line break.
+    #[error("Unexpected character after quoted cell at line {0}")]
+    UnexpectedCharacter(usize),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Provides static methods for reading table files.
+#[derive(Debug, Default)]
+pub struct TableReader {}
+
+impl TableReader {
+    /// Read and parse a table file from input. Lines can end with `\n` or `\r\n`,
+    /// a UTF-8 byte order mark is skipped, and a line break at the end of file
+    /// doesn't start a new row. Quotes inside unquoted cells are kept as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::table::{io::TableReader, TableFormatEnum};
+    ///
+    /// let input = "A,\"1,\"\"2\"\"\",\n\"B\nC\",\"\",3\n";
+    /// let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+    ///
+    /// assert_eq!(table.rows.len(), 2);
+    /// assert_eq!(table.rows[0][1].as_deref(), Some("1,\"2\""));
+    /// assert_eq!(table.rows[0][2], None);
+    /// assert_eq!(table.rows[1][0].as_deref(), Some("B\nC"));
+    /// assert_eq!(table.rows[1][1].as_deref(), Some(""));
+    /// ```
+    pub fn read_file(mut reader: impl Read, format: TableFormatEnum) -> Result<TableFile> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let delimiter = format.delimiter();
+        let mut table = TableFile::default();
+        let mut row = vec![];
+        let mut cell = String::new();
+        let mut quoted = false;
+        let mut line = 1;
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(&text);
+        let mut chars = text.chars().peekable();
+        let take = |cell: &mut String, quoted: &mut bool| match (cell.is_empty(), *quoted) {
+            (true, false) => None,
+            _ => {
+                *quoted = false;
+                Some(std::mem::take(cell))
+            }
+        };
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if cell.is_empty() && !quoted => {
+                    quoted = true;
+                    let start = line;
+                    loop {
+                        match chars.next() {
+                            None => return Err(Error::UnterminatedQuote(start)),
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                cell.push('"');
+                            }
+                            Some('"') => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                cell.push(c);
+                            }
+                        }
+                    }
+                    match chars.peek() {
+                        None | Some('\r' | '\n') => {}
+                        Some(c) if *c == delimiter => {}
+                        Some(_) => return Err(Error::UnexpectedCharacter(line)),
+                    }
+                }
+                c if c == delimiter => row.push(take(&mut cell, &mut quoted)),
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    row.push(take(&mut cell, &mut quoted));
+                    table.rows.push(std::mem::take(&mut row));
+                    line += 1;
+                }
+                c => cell.push(c),
+            }
+        }
+        if !row.is_empty() || !cell.is_empty() || quoted {
+            row.push(take(&mut cell, &mut quoted));
+            table.rows.push(row);
+        }
+        Ok(table)
+    }
+}
+
+/// Provides static methods for writing table files.
+pub struct TableWriter {}
+
+impl TableWriter {
+    /// Write a table file to output. Lines end with `\r\n`. Cells with delimiters,
+    /// quotes or line breaks, and empty strings, are quoted.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::table::{io::TableWriter, TableFile, TableFormatEnum};
+    ///
+    /// let mut table = TableFile::default();
+    /// table.rows.push(vec![Some("A B".to_string()), Some("Say \"Hi\"".to_string())]);
+    /// table.rows.push(vec![None, Some("".to_string())]);
+    /// let mut writer = vec![];
+    ///
+    /// TableWriter::write_file(&table, &mut writer, TableFormatEnum::Csv).unwrap();
+    /// assert_eq!(writer, "A B,\"Say \"\"Hi\"\"\"\r\n,\"\"\r\n".as_bytes());
+    /// ```
+    pub fn write_file(
+        table: &TableFile,
+        writer: &mut impl Write,
+        format: TableFormatEnum,
+    ) -> Result<()> {
+        let delimiter = format.delimiter();
+        for row in &table.rows {
+            let cells: Vec<_> = row
+                .iter()
+                .map(|x| match x {
+                    None => String::new(),
+                    Some(x) => Self::quote(x, delimiter),
+                })
+                .collect();
+            write!(writer, "{}\r\n", cells.join(&delimiter.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Quote a cell if needed.
+    fn quote(value: &str, delimiter: char) -> String {
+        if value.is_empty() || value.contains([delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn read_file() {
+        use rust_alert::table::{io::TableReader, TableFormatEnum};
+
+        let input = "A,\"1,\"\"2\"\"\",\n\"B\nC\",\"\",3\n";
+        let table = TableReader::read_file(input.as_bytes(), TableFormatEnum::Csv).unwrap();
+
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][1].as_deref(), Some("1,\"2\""));
+        assert_eq!(table.rows[0][2], None);
+        assert_eq!(table.rows[1][0].as_deref(), Some("B\nC"));
+        assert_eq!(table.rows[1][1].as_deref(), Some(""));
+    }
+
+    #[test]
+    fn write_file() {
+        use rust_alert::table::{io::TableWriter, TableFile, TableFormatEnum};
+
+        let mut table = TableFile::default();
+        table.rows.push(vec![
+            Some("A B".to_string()),
+            Some("Say \"Hi\"".to_string()),
+        ]);
+        table.rows.push(vec![None, Some("".to_string())]);
+        let mut writer = vec![];
+
+        TableWriter::write_file(&table, &mut writer, TableFormatEnum::Csv).unwrap();
+        assert_eq!(writer, "A B,\"Say \"\"Hi\"\"\"\r\n,\"\"\r\n".as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::table::{
+        io::{Error, TableReader, TableWriter},
+        TableFile, TableFormatEnum,
+    };
+
+    fn cells(row: &[Option<&str>]) -> Vec<Option<String>> {
+        row.iter().map(|x| x.map(str::to_string)).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut table = TableFile::default();
+        table.rows.push(cells(&[Some("A"), None, Some("")]));
+        table
+            .rows
+            .push(cells(&[Some("Tab\there"), Some("Comma, here")]));
+        table
+            .rows
+            .push(cells(&[Some("\"Line\"\r\nbreak\n"), Some(" ")]));
+        for format in [TableFormatEnum::Csv, TableFormatEnum::Tsv] {
+            let mut buf = vec![];
+            TableWriter::write_file(&table, &mut buf, format).unwrap();
+            let result = TableReader::read_file(buf.as_slice(), format).unwrap();
+            assert_eq!(result, table);
+        }
+    }
+
+    #[test]
+    fn read_variants() {
+        let read = |x: &str| TableReader::read_file(x.as_bytes(), TableFormatEnum::Tsv);
+        let table = read("\u{FEFF}A\tB\"C\r\n\r\n\"D\"").unwrap();
+        assert_eq!(
+            table.rows,
+            vec![
+                cells(&[Some("A"), Some("B\"C")]),
+                cells(&[None]),
+                cells(&[Some("D")]),
+            ]
+        );
+        assert!(read("").unwrap().rows.is_empty());
+        assert_eq!(read("\"\"").unwrap().rows, vec![cells(&[Some("")])]);
+        assert_eq!(read("A\t\n").unwrap().rows, vec![cells(&[Some("A"), None])]);
+
+        assert!(matches!(read("A\n\"B\n"), Err(Error::UnterminatedQuote(2))));
+        assert!(matches!(
+            read("A\n\"B\nC\"D"),
+            Err(Error::UnexpectedCharacter(3))
+        ));
+    }
+}
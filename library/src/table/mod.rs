@@ -0,0 +1,9 @@
+//! CSV and TSV (spreadsheet table) module.
+//!
+//! Tables follow [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180), with tabs
+//! instead of commas in TSV files.
+
+mod core;
+pub mod io;
+
+pub use core::*;
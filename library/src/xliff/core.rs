@@ -0,0 +1,151 @@
+//! XLIFF structures and manipulation.
+
+use std::fmt::Display;
+
+/// Version of the XLIFF format.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum XliffVersionEnum {
+    /// XLIFF 1.2, with `trans-unit` elements.
+    #[cfg_attr(feature = "clap", value(name = "1.2"))]
+    V12,
+    /// XLIFF 2.0, with `unit` and `segment` elements.
+    #[default]
+    #[cfg_attr(feature = "clap", value(name = "2.0"))]
+    V20,
+}
+
+impl Display for XliffVersionEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::V12 => "1.2",
+            Self::V20 => "2.0",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+/// An XLIFF document with units from a single source file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XliffFile {
+    /// Version of the format.
+    pub version: XliffVersionEnum,
+    /// Name of the source file the units come from.
+    pub original: String,
+    /// BCP 47 tag of the source language, such as `en-US`.
+    pub source_language: String,
+    /// BCP 47 tag of the target language, if set.
+    pub target_language: Option<String>,
+    /// List of units.
+    pub units: Vec<XliffUnit>,
+}
+
+impl XliffFile {
+    /// Look up the first unit with given name.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::xliff::{XliffFile, XliffUnit};
+    ///
+    /// let mut xliff = XliffFile::default();
+    /// xliff.units.push(XliffUnit::new("1", "GUI:Quit", "Quit"));
+    ///
+    /// assert_eq!(xliff.get("GUI:Quit").unwrap().source, "Quit");
+    /// assert!(xliff.get("GUI:Load").is_none());
+    /// ```
+    pub fn get(&self, name: impl AsRef<str>) -> Option<&XliffUnit> {
+        let name = name.as_ref();
+        self.units.iter().find(|x| x.name == name)
+    }
+}
+
+/// A single translatable text with its translation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XliffUnit {
+    /// Identifier of the unit, unique in the file.
+    pub id: String,
+    /// Name of the resource the text comes from (`resname` in XLIFF 1.2).
+    pub name: String,
+    /// Text in the source language.
+    pub source: String,
+    /// Text in the target language, if translated.
+    pub target: Option<String>,
+    /// Translation state, as written in the file. Values differ between versions.
+    pub state: Option<String>,
+    /// Notes for translators or from them.
+    pub notes: Vec<String>,
+}
+
+impl XliffUnit {
+    /// Translation states meaning that the target is not translated yet.
+    const UNTRANSLATED_STATES: [&'static str; 3] = ["new", "needs-translation", "initial"];
+
+    /// Creates a new untranslated unit.
+    pub fn new(id: impl Into<String>, name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            source: source.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the unit has a non-empty target, and its state (if any)
+    /// doesn't mark it as untranslated.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::xliff::XliffUnit;
+    ///
+    /// let mut unit = XliffUnit::new("1", "GUI:Quit", "Quit");
+    /// assert!(!unit.is_translated());
+    ///
+    /// unit.target = Some("Beenden".to_string());
+    /// assert!(unit.is_translated());
+    ///
+    /// unit.state = Some("needs-translation".to_string());
+    /// assert!(!unit.is_translated());
+    /// ```
+    pub fn is_translated(&self) -> bool {
+        self.target.as_ref().is_some_and(|x| !x.is_empty())
+            && !self
+                .state
+                .as_ref()
+                .is_some_and(|x| Self::UNTRANSLATED_STATES.contains(&x.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn get() {
+        use rust_alert::xliff::{XliffFile, XliffUnit};
+
+        let mut xliff = XliffFile::default();
+        xliff.units.push(XliffUnit::new("1", "GUI:Quit", "Quit"));
+
+        assert_eq!(xliff.get("GUI:Quit").unwrap().source, "Quit");
+        assert!(xliff.get("GUI:Load").is_none());
+    }
+
+    #[test]
+    fn is_translated() {
+        use rust_alert::xliff::XliffUnit;
+
+        let mut unit = XliffUnit::new("1", "GUI:Quit", "Quit");
+        assert!(!unit.is_translated());
+
+        unit.target = Some("Beenden".to_string());
+        assert!(unit.is_translated());
+
+        unit.state = Some("needs-translation".to_string());
+        assert!(!unit.is_translated());
+    }
+}
@@ -0,0 +1,594 @@
+//! XLIFF I/O.
+
+use std::io::{BufRead, Write};
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::xliff::{XliffFile, XliffUnit, XliffVersionEnum};
+
+/// The error type for serialization and deserialization of XLIFF files.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A [`std::io::Error`].
+    #[error("{0}")]
+    IO(#[from] std::io::Error),
+    /// A [`quick_xml::Error`].
+    #[error("{0}")]
+    Xml(#[from] quick_xml::Error),
+    /// The XLIFF version is missing or not supported.
+    #[error("Unsupported XLIFF version {0:?}")]
+    UnsupportedVersion(String),
+    /// A string has a character that XML 1.0 doesn't allow, not even as a character reference.
+    /// Contains the name of the unit, or the original file name for file attributes,
+    /// and the character.
+    #[error("{0}: character U+{:04X} can't be written to XLIFF", u32::from(*.1))]
+    InvalidCharacter(String, char),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Provides static methods for reading XLIFF files.
+#[derive(Debug, Default)]
+pub struct XliffReader {}
+
+impl XliffReader {
+    /// Read and parse an XLIFF 1.2 or 2.0 file from input. Units of all `file` elements
+    /// are read, but file attributes are taken from the first one. Inline markup
+    /// in sources and targets is skipped, keeping its text. Alternative translations
+    /// of 1.2 and data of 2.0 modules in units are skipped too.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::xliff::{io::XliffReader, XliffVersionEnum};
+    ///
+    /// let buf = r#"<xliff version="2.0" srcLang="en-US" trgLang="de"><file id="f1">
+    /// <unit id="1" name="GUI:Quit"><segment state="translated">
+    /// <source>Quit</source><target>Beenden</target>
+    /// </segment></unit></file></xliff>"#;
+    ///
+    /// let xliff = XliffReader::read_file(buf.as_bytes()).unwrap();
+    /// assert_eq!(xliff.version, XliffVersionEnum::V20);
+    /// assert_eq!(xliff.target_language.as_deref(), Some("de"));
+    /// let unit = xliff.get("GUI:Quit").unwrap();
+    /// assert_eq!(unit.target.as_deref(), Some("Beenden"));
+    /// assert_eq!(unit.state.as_deref(), Some("translated"));
+    /// ```
+    pub fn read_file(reader: impl BufRead) -> Result<XliffFile> {
+        let mut reader = Reader::from_reader(reader);
+        reader.config_mut().trim_text(false);
+        let mut xliff = XliffFile::default();
+        let mut unit: Option<XliffUnit> = None;
+        let mut text: Option<String> = None;
+        let mut files = 0;
+        // Depth of the current element, of the current unit and of an ignored element.
+        let mut depth = 0;
+        let mut unit_depth = 0;
+        let mut ignored: Option<usize> = None;
+        let mut buf = vec![];
+
+        loop {
+            let event = reader.read_event_into(&mut buf)?;
+            if let Event::Start(_) = event {
+                depth += 1;
+            }
+            let skip = ignored.is_some()
+                || match &event {
+                    Event::Start(e) | Event::Empty(e) => {
+                        unit.is_some()
+                            && depth == unit_depth + 1
+                            && Self::is_ignored(xliff.version, e)
+                    }
+                    _ => false,
+                };
+            match event {
+                Event::Eof => break,
+                Event::Start(_) if skip => {
+                    ignored.get_or_insert(depth);
+                }
+                Event::End(_) if skip => {
+                    if ignored == Some(depth) {
+                        ignored = None;
+                    }
+                    depth -= 1;
+                }
+                _ if skip => (),
+                Event::Start(e) => {
+                    Self::read_start(&mut xliff, &mut unit, &mut files, &e)?;
+                    if matches!(e.local_name().as_ref(), b"trans-unit" | b"unit") {
+                        unit_depth = depth;
+                    }
+                    if Self::has_text(&e) {
+                        text = Some(String::new());
+                    }
+                }
+                Event::Empty(e) => {
+                    Self::read_start(&mut xliff, &mut unit, &mut files, &e)?;
+                    if Self::has_text(&e) {
+                        Self::read_end(
+                            e.local_name().as_ref(),
+                            &mut xliff,
+                            &mut unit,
+                            Some(String::new()),
+                        );
+                    }
+                }
+                Event::Text(e) => {
+                    if let Some(text) = &mut text {
+                        text.push_str(&e.unescape()?);
+                    }
+                }
+                Event::CData(e) => {
+                    if let Some(text) = &mut text {
+                        text.push_str(&String::from_utf8_lossy(&e));
+                    }
+                }
+                Event::End(e) => {
+                    let name = e.local_name();
+                    let name = name.as_ref();
+                    let text = match name {
+                        b"source" | b"target" | b"note" => text.take(),
+                        _ => None,
+                    };
+                    Self::read_end(name, &mut xliff, &mut unit, text);
+                    depth -= 1;
+                }
+                _ => (),
+            }
+            buf.clear();
+        }
+        Ok(xliff)
+    }
+
+    /// Check if a child element of a unit should be skipped with all of its contents.
+    /// These are alternative translations in 1.2, and anything but segments and notes
+    /// in 2.0, like original data of inline codes or metadata.
+    fn is_ignored(version: XliffVersionEnum, e: &BytesStart) -> bool {
+        let name = e.local_name();
+        match version {
+            XliffVersionEnum::V12 => name.as_ref() == b"alt-trans",
+            XliffVersionEnum::V20 => !matches!(name.as_ref(), b"segment" | b"ignorable" | b"notes"),
+        }
+    }
+
+    /// Check if the element contains text we want to read.
+    fn has_text(e: &BytesStart) -> bool {
+        matches!(e.local_name().as_ref(), b"source" | b"target" | b"note")
+    }
+
+    /// Handle an opening tag.
+    fn read_start(
+        xliff: &mut XliffFile,
+        unit: &mut Option<XliffUnit>,
+        files: &mut usize,
+        e: &BytesStart,
+    ) -> Result<()> {
+        let v12 = xliff.version == XliffVersionEnum::V12;
+        match e.local_name().as_ref() {
+            b"xliff" => {
+                let version = Self::attribute(e, "version")?.unwrap_or_default();
+                xliff.version = match version.as_str() {
+                    "1.2" => XliffVersionEnum::V12,
+                    x if x.starts_with("2.") => XliffVersionEnum::V20,
+                    _ => return Err(Error::UnsupportedVersion(version)),
+                };
+                if xliff.version == XliffVersionEnum::V20 {
+                    xliff.source_language = Self::attribute(e, "srcLang")?.unwrap_or_default();
+                    xliff.target_language = Self::attribute(e, "trgLang")?;
+                }
+            }
+            b"file" => {
+                *files += 1;
+                if *files == 1 {
+                    xliff.original = Self::attribute(e, "original")?.unwrap_or_default();
+                    if v12 {
+                        xliff.source_language =
+                            Self::attribute(e, "source-language")?.unwrap_or_default();
+                        xliff.target_language = Self::attribute(e, "target-language")?;
+                    }
+                }
+            }
+            b"trans-unit" | b"unit" => {
+                let id = Self::attribute(e, "id")?.unwrap_or_default();
+                let name = Self::attribute(e, if v12 { "resname" } else { "name" })?;
+                *unit = Some(XliffUnit {
+                    name: name.unwrap_or_else(|| id.clone()),
+                    id,
+                    ..Default::default()
+                });
+            }
+            b"target" if v12 => {
+                if let Some(unit) = unit {
+                    unit.state = Self::attribute(e, "state")?;
+                }
+            }
+            b"segment" => {
+                if let Some(unit) = unit {
+                    unit.state = unit.state.take().or(Self::attribute(e, "state")?);
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Handle a closing tag, with the text inside of it if it's a text element.
+    fn read_end(
+        name: &[u8],
+        xliff: &mut XliffFile,
+        unit: &mut Option<XliffUnit>,
+        text: Option<String>,
+    ) {
+        match (name, unit.as_mut(), text) {
+            (b"source", Some(unit), Some(text)) => unit.source.push_str(&text),
+            (b"target", Some(unit), Some(text)) => {
+                unit.target.get_or_insert_default().push_str(&text)
+            }
+            (b"note", Some(unit), Some(text)) => unit.notes.push(text),
+            (b"trans-unit" | b"unit", Some(_), _) => {
+                xliff.units.extend(unit.take());
+            }
+            _ => (),
+        }
+    }
+
+    /// Get an unescaped attribute value.
+    fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>> {
+        let attribute = e.try_get_attribute(name).map_err(quick_xml::Error::from)?;
+        Ok(attribute
+            .map(|x| x.unescape_value().map(|x| x.into_owned()))
+            .transpose()?)
+    }
+}
+
+/// Provides static methods for writing XLIFF files.
+pub struct XliffWriter {}
+
+impl XliffWriter {
+    /// Write an XLIFF file to output, in the version set in the file.
+    /// Fails before writing anything if a string has a C0 control character
+    /// other than tab, newline and carriage return, as XML 1.0 can't hold them.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rust_alert::xliff::{io::{XliffReader, XliffWriter}, XliffFile, XliffUnit};
+    ///
+    /// let mut xliff = XliffFile::default();
+    /// xliff.source_language = "en-US".to_string();
+    /// xliff.units.push(XliffUnit::new("1", "GUI:Quit", "Quit"));
+    /// let mut writer = vec![];
+    ///
+    /// XliffWriter::write_file(&xliff, &mut writer).unwrap();
+    /// assert_eq!(XliffReader::read_file(writer.as_slice()).unwrap(), xliff);
+    /// ```
+    pub fn write_file(xliff: &XliffFile, writer: &mut impl Write) -> Result<()> {
+        validate(xliff)?;
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        match xliff.version {
+            XliffVersionEnum::V12 => Self::write_v12(xliff, writer),
+            XliffVersionEnum::V20 => Self::write_v20(xliff, writer),
+        }
+    }
+
+    fn write_v12(xliff: &XliffFile, writer: &mut impl Write) -> Result<()> {
+        writeln!(
+            writer,
+            r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">"#
+        )?;
+        write!(
+            writer,
+            r#"  <file original="{}" source-language="{}""#,
+            escape(&xliff.original, true),
+            escape(&xliff.source_language, true),
+        )?;
+        if let Some(target) = &xliff.target_language {
+            write!(writer, r#" target-language="{}""#, escape(target, true))?;
+        }
+        writeln!(writer, r#" datatype="plaintext">"#)?;
+        writeln!(writer, "    <body>")?;
+        for unit in &xliff.units {
+            writeln!(
+                writer,
+                r#"      <trans-unit id="{}" resname="{}" xml:space="preserve">"#,
+                escape(&unit.id, true),
+                escape(&unit.name, true),
+            )?;
+            writeln!(
+                writer,
+                "        <source>{}</source>",
+                escape(&unit.source, false)
+            )?;
+            if unit.target.is_some() || unit.state.is_some() {
+                write!(writer, "        <target")?;
+                if let Some(state) = &unit.state {
+                    write!(writer, r#" state="{}""#, escape(state, true))?;
+                }
+                let target = unit.target.as_deref().unwrap_or_default();
+                writeln!(writer, ">{}</target>", escape(target, false))?;
+            }
+            for note in &unit.notes {
+                writeln!(writer, "        <note>{}</note>", escape(note, false))?;
+            }
+            writeln!(writer, "      </trans-unit>")?;
+        }
+        writeln!(writer, "    </body>")?;
+        writeln!(writer, "  </file>")?;
+        writeln!(writer, "</xliff>")?;
+        Ok(())
+    }
+
+    fn write_v20(xliff: &XliffFile, writer: &mut impl Write) -> Result<()> {
+        write!(
+            writer,
+            r#"<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="{}""#,
+            escape(&xliff.source_language, true),
+        )?;
+        if let Some(target) = &xliff.target_language {
+            write!(writer, r#" trgLang="{}""#, escape(target, true))?;
+        }
+        writeln!(writer, ">")?;
+        writeln!(
+            writer,
+            r#"  <file id="f1" original="{}">"#,
+            escape(&xliff.original, true)
+        )?;
+        for unit in &xliff.units {
+            writeln!(
+                writer,
+                r#"    <unit id="{}" name="{}">"#,
+                escape(&unit.id, true),
+                escape(&unit.name, true),
+            )?;
+            if !unit.notes.is_empty() {
+                writeln!(writer, "      <notes>")?;
+                for note in &unit.notes {
+                    writeln!(writer, "        <note>{}</note>", escape(note, false))?;
+                }
+                writeln!(writer, "      </notes>")?;
+            }
+            match &unit.state {
+                Some(state) => {
+                    writeln!(writer, r#"      <segment state="{}">"#, escape(state, true))?
+                }
+                None => writeln!(writer, "      <segment>")?,
+            }
+            writeln!(
+                writer,
+                r#"        <source xml:space="preserve">{}</source>"#,
+                escape(&unit.source, false)
+            )?;
+            if let Some(target) = &unit.target {
+                writeln!(
+                    writer,
+                    r#"        <target xml:space="preserve">{}</target>"#,
+                    escape(target, false)
+                )?;
+            }
+            writeln!(writer, "      </segment>")?;
+            writeln!(writer, "    </unit>")?;
+        }
+        writeln!(writer, "  </file>")?;
+        writeln!(writer, "</xliff>")?;
+        Ok(())
+    }
+}
+
+/// Check that all strings of an XLIFF file can be written to XML 1.0.
+fn validate(xliff: &XliffFile) -> Result<()> {
+    let check = |name: &str, strings: &[&str]| match strings
+        .iter()
+        .find_map(|x| x.chars().find(|c| is_invalid(*c)))
+    {
+        Some(c) => Err(Error::InvalidCharacter(name.to_string(), c)),
+        None => Ok(()),
+    };
+    let mut strings = vec![xliff.original.as_str(), xliff.source_language.as_str()];
+    strings.extend(xliff.target_language.as_deref());
+    check(&xliff.original, &strings)?;
+    for unit in &xliff.units {
+        let mut strings = vec![unit.id.as_str(), unit.name.as_str(), unit.source.as_str()];
+        strings.extend(unit.target.as_deref());
+        strings.extend(unit.state.as_deref());
+        strings.extend(unit.notes.iter().map(|x| x.as_str()));
+        check(&unit.name, &strings)?;
+    }
+    Ok(())
+}
+
+/// Check if a character is outside the XML 1.0 character range.
+fn is_invalid(c: char) -> bool {
+    matches!(c, '\0'..='\x08' | '\x0B' | '\x0C' | '\x0E'..='\x1F' | '\u{FFFE}' | '\u{FFFF}')
+}
+
+/// Escape a string for XML text or attribute values. Carriage returns are
+/// escaped too, so that XML parsers don't normalize line breaks.
+fn escape(string: &str, attribute: bool) -> String {
+    let mut result = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' if attribute => result.push_str("&quot;"),
+            '\r' => result.push_str("&#13;"),
+            '\n' if attribute => result.push_str("&#10;"),
+            '\t' if attribute => result.push_str("&#9;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod examples {
+    use crate as rust_alert;
+
+    #[test]
+    fn read_file() {
+        use rust_alert::xliff::{io::XliffReader, XliffVersionEnum};
+
+        let buf = r#"<xliff version="2.0" srcLang="en-US" trgLang="de"><file id="f1">
+<unit id="1" name="GUI:Quit"><segment state="translated">
+<source>Quit</source><target>Beenden</target>
+</segment></unit></file></xliff>"#;
+
+        let xliff = XliffReader::read_file(buf.as_bytes()).unwrap();
+        assert_eq!(xliff.version, XliffVersionEnum::V20);
+        assert_eq!(xliff.target_language.as_deref(), Some("de"));
+        let unit = xliff.get("GUI:Quit").unwrap();
+        assert_eq!(unit.target.as_deref(), Some("Beenden"));
+        assert_eq!(unit.state.as_deref(), Some("translated"));
+    }
+
+    #[test]
+    fn write_file() {
+        use rust_alert::xliff::{
+            io::{XliffReader, XliffWriter},
+            XliffFile, XliffUnit,
+        };
+
+        let mut xliff = XliffFile {
+            source_language: "en-US".to_string(),
+            ..Default::default()
+        };
+        xliff.units.push(XliffUnit::new("1", "GUI:Quit", "Quit"));
+        let mut writer = vec![];
+
+        XliffWriter::write_file(&xliff, &mut writer).unwrap();
+        assert_eq!(XliffReader::read_file(writer.as_slice()).unwrap(), xliff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::xliff::{
+        io::{Error, XliffReader, XliffWriter},
+        XliffFile, XliffUnit, XliffVersionEnum,
+    };
+
+    fn sample(version: XliffVersionEnum) -> XliffFile {
+        let mut xliff = XliffFile {
+            version,
+            original: "ra2md.csf".to_string(),
+            source_language: "en-US".to_string(),
+            target_language: Some("de".to_string()),
+            units: vec![],
+        };
+        xliff.units.push(XliffUnit {
+            id: "1".to_string(),
+            name: "GUI:Text".to_string(),
+            source: "  <Line> & \"1\"\r\n\tLine 2  ".to_string(),
+            target: Some("Zeile 1\nZeile 2".to_string()),
+            state: Some("translated".to_string()),
+            notes: vec!["Keep it short".to_string(), "Checked".to_string()],
+        });
+        xliff
+            .units
+            .push(XliffUnit::new("2", "Name \"quoted\"\n", "Untranslated"));
+        xliff
+    }
+
+    #[test]
+    fn round_trip() {
+        for version in [XliffVersionEnum::V12, XliffVersionEnum::V20] {
+            let xliff = sample(version);
+            let mut buf = vec![];
+            XliffWriter::write_file(&xliff, &mut buf).unwrap();
+            let actual = XliffReader::read_file(buf.as_slice()).unwrap();
+            assert_eq!(actual, xliff, "{version}");
+        }
+    }
+
+    #[test]
+    fn read_v12() {
+        let buf = r#"<?xml version="1.0"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+<file original="a.csf" source-language="en-US" target-language="fr" datatype="plaintext"><body>
+<trans-unit id="t1"><source>A <g id="1">bold</g> text</source>
+<target state="needs-review-translation"><![CDATA[Un texte <gras>]]></target></trans-unit>
+</body></file>
+<file original="b.csf" source-language="de"><body><trans-unit id="t2"><source>B</source></trans-unit></body></file>
+</xliff>"#;
+        let xliff = XliffReader::read_file(buf.as_bytes()).unwrap();
+        assert_eq!(xliff.original, "a.csf");
+        assert_eq!(xliff.source_language, "en-US");
+        assert_eq!(xliff.target_language.as_deref(), Some("fr"));
+        assert_eq!(xliff.units.len(), 2);
+        let unit = xliff.get("t1").unwrap();
+        assert_eq!(unit.source, "A bold text");
+        assert_eq!(unit.target.as_deref(), Some("Un texte <gras>"));
+        assert!(unit.is_translated());
+        assert!(!xliff.get("t2").unwrap().is_translated());
+    }
+
+    #[test]
+    fn read_ignored() {
+        let buf = r#"<xliff version="1.2"><file original="a.csf" source-language="en-US"><body>
+<trans-unit id="t1"><source>Quit</source><target state="translated">Beenden</target>
+<alt-trans><source>Quit</source><target state="needs-translation">Ende</target>
+<note>From memory</note></alt-trans><note>Checked</note></trans-unit>
+</body></file></xliff>"#;
+        let xliff = XliffReader::read_file(buf.as_bytes()).unwrap();
+        let unit = xliff.get("t1").unwrap();
+        assert_eq!(unit.source, "Quit");
+        assert_eq!(unit.target.as_deref(), Some("Beenden"));
+        assert_eq!(unit.state.as_deref(), Some("translated"));
+        assert_eq!(unit.notes, vec!["Checked"]);
+
+        let buf = r##"<xliff version="2.0" srcLang="en-US" xmlns:mda="urn:oasis:names:tc:xliff:metadata:2.0"
+xmlns:mtc="urn:oasis:names:tc:xliff:matches:2.0"><file id="f1">
+<unit id="1" name="GUI:Quit"><mda:metadata><mda:metaGroup><mda:meta type="note">Old</mda:meta>
+</mda:metaGroup></mda:metadata><mtc:matches><mtc:match ref="#s1"><source>Quit</source>
+<target>Ende</target></mtc:match></mtc:matches><originalData><data id="d1">&lt;b&gt;</data>
+</originalData><notes><note>Checked</note></notes><segment id="s1" state="final">
+<source>Quit <ph id="1" dataRef="d1"/>now</source><target>Beenden</target></segment>
+<ignorable><source> </source></ignorable><mda:metadata/></unit></file></xliff>"##;
+        let xliff = XliffReader::read_file(buf.as_bytes()).unwrap();
+        assert_eq!(xliff.units.len(), 1);
+        let unit = xliff.get("GUI:Quit").unwrap();
+        assert_eq!(unit.source, "Quit now ");
+        assert_eq!(unit.target.as_deref(), Some("Beenden"));
+        assert_eq!(unit.state.as_deref(), Some("final"));
+        assert_eq!(unit.notes, vec!["Checked"]);
+    }
+
+    #[test]
+    fn read_errors() {
+        let truncated = r#"<xliff version="1.2"><file><body><trans-unit id="1"><alt-trans>"#;
+        assert!(XliffReader::read_file(truncated.as_bytes()).is_ok_and(|x| x.units.is_empty()));
+        assert!(matches!(
+            XliffReader::read_file(r#"<xliff version="3.0"/>"#.as_bytes()),
+            Err(Error::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            XliffReader::read_file(r#"<xliff version="2.0"><file></xliff>"#.as_bytes()),
+            Err(Error::Xml(_))
+        ));
+    }
+
+    #[test]
+    fn write_errors() {
+        for version in [XliffVersionEnum::V12, XliffVersionEnum::V20] {
+            let mut xliff = sample(version);
+            xliff.units[1].target = Some("Bell\x07".to_string());
+            let mut buf = vec![];
+            match XliffWriter::write_file(&xliff, &mut buf) {
+                Err(Error::InvalidCharacter(name, c)) => {
+                    assert_eq!(name, "Name \"quoted\"\n");
+                    assert_eq!(c, '\x07');
+                }
+                x => panic!("{x:?}"),
+            }
+            assert!(buf.is_empty());
+
+            xliff.units[1].target = Some("Tab\tDel\x7F".to_string());
+            XliffWriter::write_file(&xliff, &mut buf).unwrap();
+            let actual = XliffReader::read_file(buf.as_slice()).unwrap();
+            assert_eq!(actual, xliff, "{version}");
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! XLIFF (XML Localization Interchange File Format) module.
+//!
+//! Supports a subset of [XLIFF 1.2](https://docs.oasis-open.org/xliff/v1.2/os/xliff-core.html)
+//! and [XLIFF 2.0](https://docs.oasis-open.org/xliff/xliff-core/v2.0/xliff-core-v2.0.html):
+//! units with plain text sources and targets, notes and translation states.
+
+mod core;
+pub mod io;
+
+pub use core::*;
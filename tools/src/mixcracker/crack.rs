@@ -2,13 +2,11 @@ use std::path::PathBuf;
 
 use rust_alert::mix::{
     db::io::{LocalMixDbReader, LMD_HEADER_SIZE},
+    io::MixReader,
     Mix, MixHeaderFlags, LMD_KEY_TD, LMD_KEY_TS,
 };
 
-use crate::{
-    utils::{read_mix, write_mix},
-    Result, RunCommand,
-};
+use crate::{utils::write_mix, Result, RunCommand};
 
 #[derive(clap::Args)]
 pub struct CrackCommand {
@@ -20,7 +18,7 @@ pub struct CrackCommand {
 
 impl RunCommand for CrackCommand {
     fn run(self, force_new_format: bool) -> Result<()> {
-        let mut mix = read_mix(&self.input, force_new_format)?;
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
         mix.flags = mix
             .flags
             .intersection(MixHeaderFlags::ENCRYPTION | MixHeaderFlags::CHECKSUM);
@@ -2,9 +2,12 @@ use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
 
-use rust_alert::mix::{io::MixWriter, Mix, MixIndexEntry, LMD_KEY_TD, LMD_KEY_TS};
+use rust_alert::mix::{
+    io::{MixReader, MixWriter},
+    Mix, MixIndexEntry, LMD_KEY_TD, LMD_KEY_TS,
+};
 
-use crate::{utils::read_mix, Result, RunCommand};
+use crate::{Result, RunCommand};
 
 #[derive(clap::Args)]
 pub struct LockCommand {
@@ -25,7 +28,7 @@ pub struct LockCommand {
 
 impl RunCommand for LockCommand {
     fn run(self, force_new_format: bool) -> Result<()> {
-        let mut mix = read_mix(&self.input, force_new_format)?;
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
         if self.lmd_purge {
             lmd_purge(&mut mix);
         }
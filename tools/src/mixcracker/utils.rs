@@ -1,18 +1,9 @@
 use std::{fs::OpenOptions, path::PathBuf};
 
-use rust_alert::mix::{
-    io::{MixReader, MixWriter},
-    Mix,
-};
+use rust_alert::mix::{io::MixWriter, Mix};
 
 use crate::Result;
 
-pub fn read_mix(input: &PathBuf, new_mix: bool) -> Result<Mix> {
-    let mut reader = OpenOptions::new().read(true).open(input)?;
-    let mix = MixReader::read_file(&mut reader, new_mix)?;
-    Ok(mix)
-}
-
 pub fn write_mix(mix: &mut Mix, output: &PathBuf, new_mix: bool) -> Result<()> {
     let mut writer = OpenOptions::new()
         .write(true)
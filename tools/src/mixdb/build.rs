@@ -6,34 +6,44 @@ use rust_alert::{
     mix::db::{io::LocalMixDbWriter, LMDVersionEnum, LocalMixDatabase},
 };
 
-use crate::{Result, RunCommand};
+use crate::{
+    utils::{write_db, DbFormatEnum},
+    Result, RunCommand,
+};
 
 #[derive(clap::Args)]
 pub struct BuildCommand {
     /// Path to an input INI file.
     input: PathBuf,
-    /// Path to an output MIX database (.dat) file.
+    /// Path to an output MIX database file.
     output: PathBuf,
-    /// Name of the LMD version, defaults to YR / 6.
+    /// Name of the LMD version, defaults to YR / 6. Other formats store the matching game.
     #[arg(long, default_value_t = LMDVersionEnum::YR)]
     version: LMDVersionEnum,
+    /// Format of the output database.
+    #[arg(short, long, default_value_t = DbFormatEnum::Lmd)]
+    format: DbFormatEnum,
 }
 
 impl RunCommand for BuildCommand {
     fn run(self) -> Result<()> {
         let reader = OpenOptions::new().read(true).open(self.input)?;
         let mut reader = BufReader::new(reader);
-        let mut writer = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(self.output)?;
         let ini = IniReader::read_file(&mut reader)?;
-        let lmd = LocalMixDatabase {
-            db: ini2db(ini)?,
-            version: self.version,
-        };
-        LocalMixDbWriter::write_file(&mut writer, &lmd)?;
-        Ok(())
+        let db = ini2db(ini)?;
+        if let DbFormatEnum::Lmd = self.format {
+            let mut writer = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(self.output)?;
+            let lmd = LocalMixDatabase {
+                db,
+                version: self.version,
+            };
+            LocalMixDbWriter::write_file(&mut writer, &lmd)?;
+            return Ok(());
+        }
+        write_db(db, &self.output, self.format, self.version.into())
     }
 }
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashSet,
+    fs::{read_to_string, OpenOptions},
+    path::PathBuf,
+};
+
+use rust_alert::{
+    converters::db2ini,
+    core::GameEnum,
+    ini::io::IniWriter,
+    mix::{
+        db::crack::{crack, Error as TemplateError, NameTemplate},
+        io::MixReader,
+        LMD_KEY_TD, LMD_KEY_TS,
+    },
+    utils::hex2int,
+};
+
+use crate::{utils::read_lmd, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct CrackCommand {
+    /// Path to an input MIX file, or a file with one hex ID per line if `--ids` is set.
+    input: PathBuf,
+    /// Path to an output INI file.
+    output: PathBuf,
+    /// Name templates, e.g. "{a-z}{a-z}{0-9}{0-9}.shp" or "ice{0000-9999}.des".
+    templates: Vec<String>,
+    /// Read IDs from a list instead of a MIX.
+    #[arg(long, default_value_t = false)]
+    ids: bool,
+    /// Path to a file with more name templates, one per line.
+    #[arg(short, long)]
+    templates_file: Option<PathBuf>,
+    /// Comma-separated prefixes to put before every template.
+    #[arg(short, long, value_delimiter = ',')]
+    prefixes: Vec<String>,
+    /// Comma-separated suffixes to put after every template.
+    #[arg(short, long, value_delimiter = ',')]
+    suffixes: Vec<String>,
+    /// Game to calculate IDs for.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+    /// Force new mix format, useful if extra flags are non-0.
+    #[arg(long, default_value_t = false)]
+    new_mix: bool,
+}
+
+impl RunCommand for CrackCommand {
+    fn run(self) -> Result<()> {
+        let ids = if self.ids {
+            read_ids(&self.input)?
+        } else {
+            unknown_ids(&self.input, self.new_mix, self.game)?
+        };
+        let mut sources = self.templates;
+        if let Some(path) = self.templates_file {
+            sources.extend(
+                read_to_string(path)?
+                    .lines()
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(String::from),
+            );
+        }
+        let templates = sources
+            .iter()
+            .map(|x| {
+                x.parse::<NameTemplate>()?
+                    .with_affixes(&self.prefixes, &self.suffixes)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let total = templates
+            .iter()
+            .try_fold(0u64, |sum, x| sum.checked_add(x.len()))
+            .ok_or_else(|| TemplateError::TooManyCandidates(sources.join(", ")))?;
+        println!("Trying {} candidates for {} IDs", total, ids.len());
+
+        let db = crack(&ids, &templates, self.game);
+        println!("Recovered {} of {} IDs", db.len(), ids.len());
+        let mut ini = db2ini(db);
+        ini.sort_nested();
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.output)?;
+        IniWriter::write_file(&ini, &mut writer)?;
+        Ok(())
+    }
+}
+
+/// Read a list of hex IDs, one per line.
+fn read_ids(path: &PathBuf) -> Result<HashSet<i32>> {
+    let mut ids = HashSet::new();
+    for line in read_to_string(path)?.lines().map(str::trim) {
+        if !line.is_empty() {
+            ids.insert(hex2int(line)?);
+        }
+    }
+    Ok(ids)
+}
+
+/// Get IDs of all files in a MIX that are not named by its LMD.
+fn unknown_ids(path: &PathBuf, new_mix: bool, game: GameEnum) -> Result<HashSet<i32>> {
+    let mix = MixReader::read_path(path, new_mix)?;
+    let lmd = read_lmd(&mix, game).unwrap_or_default();
+    Ok(mix
+        .index
+        .keys()
+        .filter(|x| !matches!(**x, LMD_KEY_TD | LMD_KEY_TS) && !lmd.db.contains(**x))
+        .copied()
+        .collect())
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use rust_alert::{core::GameEnum, mix::db::MixDatabase};
+
+use crate::{
+    utils::{print_conflicts, read_db, write_db, DbFormatEnum},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct DiffCommand {
+    /// Path to the first database (LMD or INI).
+    first: PathBuf,
+    /// Path to the second database (LMD or INI).
+    second: PathBuf,
+    /// Path to an output database file with names known by only one of the databases.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Format of the output database.
+    #[arg(short, long, default_value_t = DbFormatEnum::Ini)]
+    format: DbFormatEnum,
+    /// Game to write the database for, if no input is tagged with one.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+}
+
+impl RunCommand for DiffCommand {
+    fn run(self) -> Result<()> {
+        let first = read_db(&self.first)?;
+        let second = read_db(&self.second)?;
+        let only_first = first.difference(&second);
+        let only_second = second.difference(&first);
+        print_names(&only_first, "<");
+        print_names(&only_second, ">");
+        let mut both = only_first;
+        both.merge(only_second);
+        // IDs known by both databases, but under different names.
+        let mut conflicts: Vec<_> = both
+            .entries
+            .keys()
+            .copied()
+            .filter(|x| first.contains(*x) && second.contains(*x))
+            .collect();
+        conflicts.sort_by_key(|x| *x as u32);
+        print_conflicts(&both, &conflicts);
+        if let Some(output) = self.output {
+            write_db(both, &output, self.format, self.game)?;
+        }
+        Ok(())
+    }
+}
+
+/// Print every name of the database, sorted by ID, with given marker.
+fn print_names(db: &MixDatabase, marker: &str) {
+    let mut names: Vec<_> = db.iter_all().collect();
+    names.sort_by_key(|(id, _)| **id as u32);
+    for (id, entry) in names {
+        println!("{} {:0>8X} {}", marker, id, entry.name);
+    }
+}
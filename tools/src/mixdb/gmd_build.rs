@@ -0,0 +1,29 @@
+use std::{fs::OpenOptions, io::BufReader, path::PathBuf};
+
+use rust_alert::{converters::ini2gmd, ini::io::IniReader, mix::db::io::GlobalMixDbWriter};
+
+use crate::{Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct GmdBuildCommand {
+    /// Path to an input INI file.
+    input: PathBuf,
+    /// Path to an output global MIX database (.dat) file.
+    output: PathBuf,
+}
+
+impl RunCommand for GmdBuildCommand {
+    fn run(self) -> Result<()> {
+        let reader = OpenOptions::new().read(true).open(self.input)?;
+        let mut reader = BufReader::new(reader);
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.output)?;
+        let ini = IniReader::read_file(&mut reader)?;
+        let gmd = ini2gmd(ini)?;
+        GlobalMixDbWriter::write_file(&mut writer, &gmd)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use std::{fs::OpenOptions, path::PathBuf};
+
+use rust_alert::{converters::gmd2ini, ini::io::IniWriter, mix::db::io::GlobalMixDbReader};
+
+use crate::{Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct GmdExtractCommand {
+    /// Path to an input global MIX database (.dat) file.
+    input: PathBuf,
+    /// Path to an output INI file.
+    output: PathBuf,
+}
+
+impl RunCommand for GmdExtractCommand {
+    fn run(self) -> Result<()> {
+        let mut reader = OpenOptions::new().read(true).open(self.input)?;
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.output)?;
+        let gmd = GlobalMixDbReader::read_file(&mut reader)?;
+        let ini = gmd2ini(gmd);
+        IniWriter::write_file(&ini, &mut writer)?;
+        Ok(())
+    }
+}
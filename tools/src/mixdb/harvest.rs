@@ -0,0 +1,53 @@
+use std::{fs::OpenOptions, path::PathBuf};
+
+use rust_alert::{
+    converters::db2ini,
+    core::GameEnum,
+    ini::io::IniWriter,
+    mix::{db::harvest::Harvester, io::MixReader},
+};
+
+use crate::{Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct HarvestCommand {
+    /// Path to an input MIX file.
+    input: PathBuf,
+    /// Path to an output INI file.
+    output: PathBuf,
+    /// Paths to extra INI files to harvest names from, e.g. rules from another MIX.
+    #[arg(short, long)]
+    ini: Vec<PathBuf>,
+    /// Game to calculate IDs for.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+    /// Force new mix format, useful if extra flags are non-0.
+    #[arg(long, default_value_t = false)]
+    new_mix: bool,
+}
+
+impl RunCommand for HarvestCommand {
+    fn run(self) -> Result<()> {
+        let mix = MixReader::read_path(&self.input, self.new_mix)?;
+        let mut harvester = Harvester::new(self.game);
+        harvester.add_mix(&mix, self.new_mix);
+        for path in self.ini {
+            harvester.add_ini_bytes(&std::fs::read(path)?);
+        }
+        let db = harvester.finish();
+        println!(
+            "Found {} names out of {} candidates",
+            db.len(),
+            harvester.candidates_len()
+        );
+        let mut ini = db2ini(db);
+        ini.sort_nested();
+        let mut writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.output)?;
+        IniWriter::write_file(&ini, &mut writer)?;
+        Ok(())
+    }
+}
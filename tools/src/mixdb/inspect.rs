@@ -37,13 +37,14 @@ impl std::fmt::Display for InspectSortOrderEnum {
 impl RunCommand for InspectCommand {
     fn run(self) -> Result<()> {
         let mut reader = OpenOptions::new().read(true).open(self.input)?;
-        let mut mixdb = LocalMixDbReader::read_file(&mut reader)?;
+        let mixdb = LocalMixDbReader::read_file(&mut reader)?;
         if !self.no_header {
             println!(
-                "MIX DB version: {:?} ({})",
+                "MIX DB version:  {:?} ({})",
                 mixdb.version, mixdb.version as u32
             );
-            println!("# of entries:   {}", mixdb.db.names.len());
+            println!("# of entries:    {}", mixdb.db.len());
+            println!("# of collisions: {}", mixdb.db.collisions().count());
             if !self.no_names {
                 println!();
             }
@@ -52,7 +53,7 @@ impl RunCommand for InspectCommand {
             let names = match self.sort {
                 InspectSortOrderEnum::Id => sort(mixdb.db, |(id, _)| *id),
                 InspectSortOrderEnum::Name => sort(mixdb.db, |(_, name)| name.to_lowercase()),
-                InspectSortOrderEnum::Offset => mixdb.db.names.drain().collect(),
+                InspectSortOrderEnum::Offset => into_names(mixdb.db),
                 InspectSortOrderEnum::Size => sort(mixdb.db, |(_, name)| name.len()),
             };
             for (id, name) in names {
@@ -63,12 +64,19 @@ impl RunCommand for InspectCommand {
     }
 }
 
-fn sort<F, K>(mut db: MixDatabase, pred: F) -> Vec<(i32, String)>
+fn sort<F, K>(db: MixDatabase, pred: F) -> Vec<(i32, String)>
 where
     F: FnMut(&(i32, String)) -> K,
     K: Ord,
 {
-    let mut vec: Vec<_> = db.names.drain().collect();
+    let mut vec = into_names(db);
     vec.sort_by_key(pred);
     vec
 }
+
+fn into_names(mut db: MixDatabase) -> Vec<(i32, String)> {
+    db.entries
+        .drain()
+        .flat_map(|(id, x)| x.into_iter().map(move |x| (id, x.name)))
+        .collect()
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use rust_alert::{
+    core::{crc, GameEnum},
+    mix::{
+        db::{MixDatabase, MixDatabaseEntry},
+        guess_file_type,
+        io::MixReader,
+        FileTypeEnum, Mix, LMD_KEY_TD, LMD_KEY_TS,
+    },
+    utils::path_to_filename,
+};
+
+use crate::{
+    utils::{read_lmd, write_db, DbFormatEnum},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct LearnCommand {
+    /// Path to an input game directory.
+    input: PathBuf,
+    /// Path to an output database file.
+    output: PathBuf,
+    /// Format of the output database.
+    #[arg(short, long, default_value_t = DbFormatEnum::Ini)]
+    format: DbFormatEnum,
+    /// Game to calculate IDs for.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+    /// Force new mix format, useful if extra flags are non-0.
+    #[arg(long, default_value_t = false)]
+    new_mix: bool,
+}
+
+impl RunCommand for LearnCommand {
+    fn run(self) -> Result<()> {
+        let mut learner = Learner {
+            db: MixDatabase::with_game(self.game),
+            game: self.game,
+            new_mix: self.new_mix,
+            archives: 0,
+        };
+        learner.add_dir(&self.input, &self.input)?;
+        println!(
+            "Learned {} names from {} archives",
+            learner.db.len(),
+            learner.archives
+        );
+        write_db(learner.db, &self.output, self.format, self.game)
+    }
+}
+
+/// Separates archive paths in descriptions of learned names.
+const ARCHIVE_SEPARATOR: &str = ", ";
+
+/// Collects names of loose files and names from LMDs of all MIXes in a directory.
+struct Learner {
+    db: MixDatabase,
+    game: GameEnum,
+    new_mix: bool,
+    archives: usize,
+}
+
+impl Learner {
+    /// Add names of all files in a directory and its subdirectories.
+    fn add_dir(&mut self, root: &Path, dir: &Path) -> Result<()> {
+        let mut paths = std::fs::read_dir(dir)?
+            .map(|x| x.map(|x| x.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.sort();
+        for path in paths {
+            if path.is_dir() {
+                self.add_dir(root, &path)?;
+                continue;
+            }
+            let name = path_to_filename(&path)?.to_lowercase();
+            self.db.add(crc(&name, self.game), name.clone());
+            if !name.ends_with(".mix") {
+                continue;
+            }
+            let archive = path.strip_prefix(root).unwrap_or(&path);
+            let archive = archive.to_string_lossy().replace('\\', "/");
+            match MixReader::read_path(&path, self.new_mix) {
+                Ok(mix) => self.add_mix(&mix, &archive),
+                Err(e) => println!("Warning: failed to read {}. Reason: {}", archive, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Add names from the LMD of a MIX and its nested MIXes, describing them with the archive path.
+    fn add_mix(&mut self, mix: &Mix, archive: &str) {
+        self.archives += 1;
+        let lmd = read_lmd(mix, self.game).map(|x| x.db).unwrap_or_default();
+        for (_, entry) in lmd.iter_all() {
+            self.add_from_archive(entry, archive);
+        }
+        for (id, data) in mix
+            .index
+            .keys()
+            .filter_map(|id| Some((*id, mix.get_file(*id)?)))
+        {
+            if matches!(id, LMD_KEY_TD | LMD_KEY_TS)
+                || guess_file_type(data) != Some(FileTypeEnum::Mix)
+            {
+                continue;
+            }
+            let name = lmd
+                .get_name(id)
+                .map_or_else(|| format!("{:0>8X}", id), |x| x.to_lowercase());
+            let archive = format!("{archive}/{name}");
+            match MixReader::read_file(&mut &data[..], self.new_mix) {
+                Ok(inner) => self.add_mix(&inner, &archive),
+                Err(e) => println!("Warning: failed to read {}. Reason: {}", archive, e),
+            }
+        }
+    }
+
+    /// Add a name found in an archive. If the name is already known, the archive is
+    /// appended to its description, so it lists every archive the name was seen in.
+    fn add_from_archive(&mut self, entry: &MixDatabaseEntry, archive: &str) {
+        // The LMD might have been made for another game, so recalculate the ID.
+        let id = crc(&entry.name, self.game);
+        let known = self.db.entries.get_mut(&id).and_then(|x| {
+            x.iter_mut()
+                .find(|x| x.name.eq_ignore_ascii_case(&entry.name))
+        });
+        match known {
+            Some(known) => match &mut known.description {
+                Some(x) if x.split(ARCHIVE_SEPARATOR).any(|x| x == archive) => {}
+                Some(x) => {
+                    x.push_str(ARCHIVE_SEPARATOR);
+                    x.push_str(archive);
+                }
+                None => known.description = Some(archive.to_string()),
+            },
+            None => {
+                let mut entry = entry.clone();
+                entry.description = Some(archive.to_string());
+                self.db.add_entry(id, entry);
+            }
+        }
+    }
+}
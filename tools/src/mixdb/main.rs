@@ -3,19 +3,40 @@
 use clap::{Parser, Subcommand};
 
 mod build;
+mod crack;
+mod diff;
 mod extract;
+mod gmd_build;
+mod gmd_extract;
+mod harvest;
 mod inspect;
+mod learn;
+mod merge;
 mod process;
 mod query;
 mod scan;
+mod search;
+mod solve;
+mod subtract;
+mod utils;
 
 use build::BuildCommand;
+use crack::CrackCommand;
+use diff::DiffCommand;
 use extract::ExtractCommand;
+use gmd_build::GmdBuildCommand;
+use gmd_extract::GmdExtractCommand;
+use harvest::HarvestCommand;
 use inspect::InspectCommand;
+use learn::LearnCommand;
+use merge::MergeCommand;
 use process::ProcessCommand;
 use query::QueryCommand;
 use rust_alert::make_app;
 use scan::ScanCommand;
+use search::SearchCommand;
+use solve::SolveCommand;
+use subtract::SubtractCommand;
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -31,6 +52,18 @@ enum Error {
     ParseIntError(#[from] rust_alert::utils::ParseIntError),
     #[error("{0}")]
     PathToStringError(#[from] rust_alert::utils::PathToStringError),
+    #[error("{0}")]
+    MixIO(#[from] rust_alert::mix::io::Error),
+    #[error("{0}")]
+    Crack(#[from] rust_alert::mix::db::crack::Error),
+    #[error("{0}")]
+    Preimage(#[from] rust_alert::core::PreimageError),
+    #[error("{0}")]
+    Compact(#[from] rust_alert::mix::db::compact::Error),
+    #[error("{0}")]
+    Search(#[from] rust_alert::mix::db::search::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -51,16 +84,37 @@ struct Args {
 enum Commands {
     /// Build a database from an INI DB file.
     Build(BuildCommand),
+    /// Recover unknown names by trying candidates from name templates.
+    Crack(CrackCommand),
+    /// Compare two databases, showing names known by only one of them.
+    Diff(DiffCommand),
     /// Extract names from the database into an INI file.
     Extract(ExtractCommand),
+    /// Build a global database from an INI file, one database per section.
+    GmdBuild(GmdBuildCommand),
+    /// Extract names and descriptions from a global database into an INI file.
+    GmdExtract(GmdExtractCommand),
+    /// Recover unknown names from names referenced in INI files inside a MIX.
+    Harvest(HarvestCommand),
     /// Process names from an INI file to an INI DB file.
     Process(ProcessCommand),
     /// Inspect the database header contents.
     Inspect(InspectCommand),
+    /// Learn names from LMDs of all MIXes and loose files in a game directory.
+    /// Names are described with all archives they were found in.
+    Learn(LearnCommand),
+    /// Merge several databases into one, reporting conflicting names.
+    Merge(MergeCommand),
     /// Query the database for an index or name.
     Query(QueryCommand),
     /// Scan directory and construct an INI DB file.
     Scan(ScanCommand),
+    /// Search databases for names or IDs matching a pattern.
+    Search(SearchCommand),
+    /// Find names with given ID, knowing their beginning and end (TS and later only).
+    Solve(SolveCommand),
+    /// Remove names known by another database.
+    Subtract(SubtractCommand),
 }
 
 trait RunCommand {
@@ -71,11 +125,21 @@ impl RunCommand for Commands {
     fn run(self) -> Result<()> {
         match self {
             Commands::Build(x) => x.run(),
+            Commands::Crack(x) => x.run(),
+            Commands::Diff(x) => x.run(),
             Commands::Extract(x) => x.run(),
+            Commands::GmdBuild(x) => x.run(),
+            Commands::GmdExtract(x) => x.run(),
+            Commands::Harvest(x) => x.run(),
             Commands::Process(x) => x.run(),
             Commands::Inspect(x) => x.run(),
+            Commands::Learn(x) => x.run(),
+            Commands::Merge(x) => x.run(),
             Commands::Query(x) => x.run(),
             Commands::Scan(x) => x.run(),
+            Commands::Search(x) => x.run(),
+            Commands::Solve(x) => x.run(),
+            Commands::Subtract(x) => x.run(),
         }
     }
 }
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use rust_alert::core::GameEnum;
+
+use crate::{
+    utils::{print_conflicts, read_db, write_db, DbFormatEnum},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct MergeCommand {
+    /// Path to an output database file.
+    output: PathBuf,
+    /// Paths to input databases (LMD or INI), in order of priority.
+    #[arg(num_args = 1.., required = true)]
+    inputs: Vec<PathBuf>,
+    /// Format of the output database.
+    #[arg(short, long, default_value_t = DbFormatEnum::Ini)]
+    format: DbFormatEnum,
+    /// Game to write the database for, if no input is tagged with one.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+}
+
+impl RunCommand for MergeCommand {
+    fn run(self) -> Result<()> {
+        let mut inputs = self.inputs.iter();
+        let mut db = match inputs.next() {
+            Some(path) => read_db(path)?,
+            None => Default::default(),
+        };
+        for path in inputs {
+            let conflicts = db.merge(read_db(path)?);
+            print_conflicts(&db, &conflicts);
+        }
+        println!("Merged {} names", db.len());
+        write_db(db, &self.output, self.format, self.game)
+    }
+}
@@ -16,9 +16,9 @@ pub struct ProcessCommand {
     input: PathBuf,
     /// Path to an output INI file.
     output: PathBuf,
-    /// Use old (TD/RA) CRC algorithm.
-    #[arg(short, long, default_value_t = false)]
-    old_crc: bool,
+    /// Game to calculate IDs for.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
 }
 
 impl RunCommand for ProcessCommand {
@@ -27,15 +27,13 @@ impl RunCommand for ProcessCommand {
         let mut reader = BufReader::new(reader);
         let mut ini = IniReader::read_file(&mut reader)?;
         let mut new_ini = IniFile::default();
-        let game = if self.old_crc {
-            GameEnum::TD
-        } else {
-            GameEnum::YR
-        };
         for (section_name, mut section) in ini.drain() {
             let mut new_section = IniSection::new(section_name);
             for (_, entry) in section.drain() {
-                new_section.create_entry(format!("{:0>8X}", crc(&entry.value, game)), entry.value);
+                new_section.create_entry(
+                    format!("{:0>8X}", crc(&entry.value, self.game)),
+                    entry.value,
+                );
             }
             new_ini.add_section(new_section);
         }
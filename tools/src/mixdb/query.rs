@@ -1,8 +1,12 @@
-use std::{fs::OpenOptions, path::PathBuf};
+use std::path::PathBuf;
 
 use rust_alert::{
-    core::crc,
-    mix::db::{io::LocalMixDbReader, LocalMixDatabase},
+    core::{crc, GameEnum},
+    mix::db::{
+        compact::{CompactMixDatabase, COMPACT_MAGIC},
+        io::LocalMixDbReader,
+        MixDatabaseLookup,
+    },
     utils::hex2int,
 };
 
@@ -10,7 +14,7 @@ use crate::{Result, RunCommand};
 
 #[derive(clap::Args)]
 pub struct QueryCommand {
-    /// Path to an input MIX DB file.
+    /// Path to an input MIX DB file, either LMD or compact.
     input: PathBuf,
     /// Query name or ID.
     query: String,
@@ -24,13 +28,16 @@ pub struct QueryCommand {
 
 impl RunCommand for QueryCommand {
     fn run(self) -> Result<()> {
-        let mut reader = OpenOptions::new().read(true).open(self.input)?;
-        let mixdb = LocalMixDbReader::read_file(&mut reader)?;
-        let res = if self.by_id {
-            let id = hex2int(&self.query)?;
-            query_by_id(&mixdb, id)
+        let data = std::fs::read(&self.input)?;
+        let res = if data.starts_with(COMPACT_MAGIC) {
+            // Compact databases are queried in place, without building a hash map.
+            let view = CompactMixDatabase::new(&data)?;
+            let game = view.game().unwrap_or_default();
+            self.query(&view, game)?
         } else {
-            query_by_name(&mixdb, &self.query, self.calculate)
+            let mixdb = LocalMixDbReader::read_file(&mut &data[..])?;
+            let game = mixdb.version.into();
+            self.query(&mixdb.db, game)?
         };
         if let Some(res) = res {
             println!("{}", res);
@@ -41,13 +48,31 @@ impl RunCommand for QueryCommand {
     }
 }
 
-fn query_by_id(mixdb: &LocalMixDatabase, query: i32) -> Option<String> {
-    mixdb.db.names.get(&query).cloned()
+impl QueryCommand {
+    fn query(&self, db: &dyn MixDatabaseLookup, game: GameEnum) -> Result<Option<String>> {
+        if self.by_id {
+            let id = hex2int(&self.query)?;
+            Ok(db.lookup(id).first().map(|x| x.name.clone()))
+        } else {
+            Ok(query_by_name(db, &self.query, self.calculate, game))
+        }
+    }
 }
 
-fn query_by_name(mixdb: &LocalMixDatabase, query: &String, calculate: bool) -> Option<String> {
-    let real = crc(query, mixdb.version.into());
-    if calculate || mixdb.db.names.get(&real).is_some_and(|res| res == query) {
+/// Get the ID of a name, if it's any of the candidates for that ID, ignoring case.
+fn query_by_name(
+    db: &dyn MixDatabaseLookup,
+    query: &str,
+    calculate: bool,
+    game: GameEnum,
+) -> Option<String> {
+    let real = crc(query, game);
+    let known = || {
+        db.lookup(real)
+            .iter()
+            .any(|x| x.name.eq_ignore_ascii_case(query))
+    };
+    if calculate || known() {
         Some(format!("{:0>8X}", real))
     } else {
         None
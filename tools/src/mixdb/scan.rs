@@ -14,21 +14,16 @@ pub struct ScanCommand {
     input: PathBuf,
     /// Path to an output INI file.
     output: PathBuf,
-    /// Use old (TD/RA) CRC algorithm.
-    #[arg(short, long, default_value_t = false)]
-    old_crc: bool,
+    /// Game to calculate IDs for.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
 }
 
 impl RunCommand for ScanCommand {
     fn run(self) -> Result<()> {
-        let game = if self.old_crc {
-            GameEnum::TD
-        } else {
-            GameEnum::YR
-        };
         let mut ini = IniFile::default();
         let mut section = IniSection::new("MixDatabase");
-        inner(self.input, &mut section, game)?;
+        inner(self.input, &mut section, self.game)?;
         ini.add_section(section);
         let mut writer = OpenOptions::new()
             .create(true)
@@ -0,0 +1,135 @@
+use std::{fs::OpenOptions, path::PathBuf};
+
+use rust_alert::{
+    core::GameEnum,
+    mix::db::{
+        io::GlobalMixDbReader,
+        search::{SearchHit, SearchPatternEnum},
+        GlobalMixDatabase,
+    },
+};
+
+use crate::{utils::read_gmd, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct SearchCommand {
+    /// Pattern to search for, interpreted according to the mode.
+    pattern: String,
+    /// Paths to databases (LMD, compact or INI). Every INI section is searched separately.
+    inputs: Vec<PathBuf>,
+    /// Paths to global databases (XCC's global mix database.dat) to search too.
+    #[arg(long)]
+    gmd: Vec<PathBuf>,
+    /// Search the built-in databases of all games too.
+    #[arg(short, long, default_value_t = false)]
+    builtin: bool,
+    /// How to interpret the pattern.
+    #[arg(short, long, default_value_t = Default::default())]
+    mode: SearchModeEnum,
+    /// Print results as JSON.
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum SearchModeEnum {
+    /// Wildcard pattern with `*` and `?`.
+    #[default]
+    Glob,
+    /// Regular expression.
+    Regex,
+    /// File extension.
+    Ext,
+    /// Prefix of the hex ID.
+    Id,
+}
+
+impl std::fmt::Display for SearchModeEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// A search result, as printed in JSON.
+#[derive(serde::Serialize)]
+struct SearchResult<'a> {
+    database: &'a str,
+    index: usize,
+    game: Option<String>,
+    id: String,
+    name: &'a str,
+    description: Option<&'a str>,
+    source: String,
+}
+
+impl RunCommand for SearchCommand {
+    fn run(self) -> Result<()> {
+        let pattern = match self.mode {
+            SearchModeEnum::Glob => SearchPatternEnum::glob(&self.pattern),
+            SearchModeEnum::Regex => SearchPatternEnum::regex(&self.pattern)?,
+            SearchModeEnum::Ext => SearchPatternEnum::extension(&self.pattern),
+            SearchModeEnum::Id => SearchPatternEnum::id_prefix(&self.pattern)?,
+        };
+        let mut sources: Vec<(String, GlobalMixDatabase)> = vec![];
+        for input in &self.inputs {
+            sources.push((input.display().to_string(), read_gmd(input)?));
+        }
+        for input in &self.gmd {
+            let mut reader = OpenOptions::new().read(true).open(input)?;
+            let gmd = GlobalMixDbReader::read_file(&mut reader)?;
+            sources.push((input.display().to_string(), gmd));
+        }
+        if self.builtin {
+            for game in [GameEnum::TD, GameEnum::RA, GameEnum::TS, GameEnum::YR] {
+                sources.push(("builtin".to_string(), GlobalMixDatabase::builtin(game)));
+            }
+        }
+
+        let hits: Vec<_> = sources
+            .iter()
+            .flat_map(|(label, gmd)| {
+                gmd.search(&pattern)
+                    .into_iter()
+                    .map(move |x| (label.as_str(), x))
+            })
+            .collect();
+        if self.json {
+            let results: Vec<_> = hits.iter().map(|(l, x)| to_result(l, x)).collect();
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        } else {
+            for (label, hit) in &hits {
+                print_hit(label, hit);
+            }
+            println!("# of hits: {}", hits.len());
+        }
+        Ok(())
+    }
+}
+
+fn game_name(game: Option<GameEnum>) -> Option<String> {
+    game.map(|x| format!("{:?}", x))
+}
+
+fn to_result<'a>(label: &'a str, hit: &SearchHit<'a>) -> SearchResult<'a> {
+    SearchResult {
+        database: label,
+        index: hit.db,
+        game: game_name(hit.game),
+        id: format!("{:0>8X}", hit.id),
+        name: &hit.entry.name,
+        description: hit.entry.description.as_deref(),
+        source: hit.entry.source.to_string(),
+    }
+}
+
+fn print_hit(label: &str, hit: &SearchHit) {
+    println!(
+        "{:0>8X} {} ({}, {}#{}, {})",
+        hit.id,
+        hit.entry.name,
+        game_name(hit.game).unwrap_or("any game".to_string()),
+        label,
+        hit.db,
+        hit.entry.source,
+    );
+}
@@ -0,0 +1,43 @@
+use rust_alert::{
+    core::{crc_ts_preimage, MAX_PREIMAGE_LENGTH},
+    utils::hex2int,
+};
+
+use crate::{Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct SolveCommand {
+    /// ID (in hex) to find names for.
+    id: String,
+    /// Known beginning of the name.
+    #[arg(short, long, default_value_t = String::default())]
+    prefix: String,
+    /// Known end of the name, usually the extension.
+    #[arg(short, long, default_value_t = String::default())]
+    suffix: String,
+    /// Minimum length of the unknown middle part.
+    #[arg(long, default_value_t = 1)]
+    min_length: usize,
+    /// Maximum length of the unknown middle part.
+    #[arg(long, default_value_t = MAX_PREIMAGE_LENGTH)]
+    max_length: usize,
+}
+
+impl RunCommand for SolveCommand {
+    fn run(self) -> Result<()> {
+        let id = hex2int(&self.id)?;
+        let names = crc_ts_preimage(
+            id,
+            &self.prefix,
+            &self.suffix,
+            self.min_length..=self.max_length,
+        )?;
+        if names.is_empty() {
+            println!("Not found");
+        }
+        for name in names {
+            println!("{}", name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use rust_alert::core::GameEnum;
+
+use crate::{
+    utils::{read_db, write_db, DbFormatEnum},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct SubtractCommand {
+    /// Path to an input database (LMD or INI).
+    input: PathBuf,
+    /// Path to a database (LMD or INI) with names to remove from the input.
+    other: PathBuf,
+    /// Path to an output database file.
+    output: PathBuf,
+    /// Format of the output database.
+    #[arg(short, long, default_value_t = DbFormatEnum::Ini)]
+    format: DbFormatEnum,
+    /// Game to write the database for, if the input is not tagged with one.
+    #[arg(short, long, default_value_t = GameEnum::YR)]
+    game: GameEnum,
+}
+
+impl RunCommand for SubtractCommand {
+    fn run(self) -> Result<()> {
+        let mut db = read_db(&self.input)?;
+        let total = db.iter_all().count();
+        db.subtract(&read_db(&self.other)?);
+        println!(
+            "Removed {} of {} names",
+            total - db.iter_all().count(),
+            total
+        );
+        write_db(db, &self.output, self.format, self.game)
+    }
+}
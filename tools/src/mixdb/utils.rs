@@ -0,0 +1,135 @@
+use std::{fs::OpenOptions, io::BufReader, path::PathBuf};
+
+use rust_alert::{
+    converters::{db2ini, ini2db, ini2gmd},
+    core::GameEnum,
+    ini::io::{IniReader, IniWriter},
+    mix::{
+        db::{
+            compact::{CompactMixDatabase, CompactMixDbWriter, COMPACT_MAGIC},
+            io::{
+                gmd_index, GlobalMixDbWriter, LocalMixDbReader, LocalMixDbWriter, GMD_GAMES,
+                LMD_PREFIX,
+            },
+            GlobalMixDatabase, LocalMixDatabase, MixDatabase,
+        },
+        Mix,
+    },
+};
+
+use crate::Result;
+
+/// Formats of database files written by commands.
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum DbFormatEnum {
+    /// INI DB file. Extra candidate names are stored under `ID|N` keys.
+    #[default]
+    Ini,
+    /// XCC local mix database.
+    Lmd,
+    /// XCC global mix database.
+    Gmd,
+    /// Compact binary database.
+    Compact,
+}
+
+impl std::fmt::Display for DbFormatEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// Read the LMD of a MIX, warning about an LMD that can't be read.
+pub fn read_lmd(mix: &Mix, game: GameEnum) -> Option<LocalMixDatabase> {
+    LocalMixDbReader::read_from_mix(mix, game).unwrap_or_else(|e| {
+        println!("Warning: found LMD, but failed to read it. Reason: {}", e);
+        None
+    })
+}
+
+/// Read a database from an LMD, a compact database or an INI DB file, flattening all sections.
+/// The format is detected from the contents.
+pub fn read_db(input: &PathBuf) -> Result<MixDatabase> {
+    let data = std::fs::read(input)?;
+    if data.starts_with(COMPACT_MAGIC) {
+        Ok(CompactMixDatabase::new(&data)?.to_db())
+    } else if data.starts_with(LMD_PREFIX) {
+        let lmd = LocalMixDbReader::read_file(&mut &data[..])?;
+        let mut db = lmd.db;
+        db.game = Some(lmd.version.into());
+        Ok(db)
+    } else {
+        let ini = IniReader::read_file(BufReader::new(&data[..]))?;
+        Ok(ini2db(ini)?)
+    }
+}
+
+/// Read databases from an LMD, a compact database or an INI DB file, keeping
+/// every INI section as a separate database. The format is detected from the contents.
+pub fn read_gmd(input: &PathBuf) -> Result<GlobalMixDatabase> {
+    let data = std::fs::read(input)?;
+    if data.starts_with(COMPACT_MAGIC) || data.starts_with(LMD_PREFIX) {
+        Ok(GlobalMixDatabase {
+            dbs: vec![read_db(input)?],
+        })
+    } else {
+        let ini = IniReader::read_file(BufReader::new(&data[..]))?;
+        Ok(ini2gmd(ini)?)
+    }
+}
+
+/// Write a database in given format. In an LMD and a GMD, the database is stored
+/// for its game, or for given game if it has none. Every format keeps all candidate
+/// names of an ID, so conflicts reported by `merge` and `diff` survive in the output.
+/// INI files store candidates after the first one under `ID|1`, `ID|2` and so on.
+pub fn write_db(
+    db: MixDatabase,
+    output: &PathBuf,
+    format: DbFormatEnum,
+    game: GameEnum,
+) -> Result<()> {
+    let mut writer = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(output)?;
+    match format {
+        DbFormatEnum::Ini => {
+            let mut ini = db2ini(db);
+            ini.sort_nested();
+            IniWriter::write_file(&ini, &mut writer)?;
+        }
+        DbFormatEnum::Lmd => {
+            let lmd = LocalMixDatabase {
+                version: db.game.unwrap_or(game).into(),
+                db,
+            };
+            LocalMixDbWriter::write_file(&mut writer, &lmd)?;
+        }
+        DbFormatEnum::Compact => {
+            let mut db = db;
+            db.game = db.game.or(Some(game));
+            CompactMixDbWriter::write_file(&mut writer, &db)?;
+        }
+        DbFormatEnum::Gmd => {
+            let mut gmd = GlobalMixDatabase {
+                dbs: GMD_GAMES
+                    .iter()
+                    .map(|x| MixDatabase::with_game(*x))
+                    .collect(),
+            };
+            let index = gmd_index(db.game.unwrap_or(game));
+            gmd.dbs[index] = db;
+            GlobalMixDbWriter::write_file(&mut writer, &gmd)?;
+        }
+    }
+    Ok(())
+}
+
+/// Print all candidate names of conflicting IDs.
+pub fn print_conflicts(db: &MixDatabase, conflicts: &[i32]) {
+    for id in conflicts {
+        let names: Vec<_> = db.get_all(*id).iter().map(|x| x.name.as_str()).collect();
+        println!("Conflict: {:0>8X} is {}", id, names.join(" or "));
+    }
+}
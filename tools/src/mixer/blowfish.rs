@@ -1,9 +1,12 @@
 use std::{fs::OpenOptions, io::Write, path::PathBuf};
 
-use rust_alert::mix::Mix;
+use rust_alert::{
+    core::GameEnum,
+    mix::{io::MixReader, Mix},
+};
 
 use crate::{
-    utils::{encrypt_mix, read_mix, write_mix},
+    utils::{encrypt_mix, write_mix},
     Error, Result, RunCommand,
 };
 
@@ -24,8 +27,8 @@ pub struct BlowfishCommand {
 
 impl RunCommand for BlowfishCommand {
     /// Encrypt, decrypt MIX or extract the key.
-    fn run(self, force_new_format: bool, _safe_mode: bool) -> Result<()> {
-        let mut mix = read_mix(&self.input, force_new_format)?;
+    fn run(self, force_new_format: bool, _safe_mode: bool, _game: GameEnum) -> Result<()> {
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
         match self.mode {
             BlowfishMode::Decrypt => {
                 mix.set_blowfish_key(None);
@@ -5,7 +5,7 @@ use rust_alert::{
     mix::{
         db::{io::LocalMixDbWriter, LocalMixDatabase},
         io::MixWriter,
-        Mix, LMD_KEY_TD, LMD_KEY_TS,
+        Mix,
     },
     utils::path_to_filename,
 };
@@ -34,9 +34,6 @@ pub struct BuildCommand {
     /// Build LMD for the MIX file.
     #[arg(short, long, default_value_t = false)]
     lmd: bool,
-    /// Use old CRC function (TD/RA).
-    #[arg(short, long, default_value_t = false)]
-    old_crc: bool,
     /// Allow to overwrite files with the same name.
     #[arg(long, default_value_t = false)]
     overwrite: bool,
@@ -44,13 +41,13 @@ pub struct BuildCommand {
 
 impl RunCommand for BuildCommand {
     /// Build a MIX from files.
-    fn run(self, force_new_format: bool, _safe_mode: bool) -> Result<()> {
+    fn run(self, force_new_format: bool, _safe_mode: bool, game: GameEnum) -> Result<()> {
         let mut writer = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
             .open(&self.output)?;
-        build_inner(&mut writer, &self.input, &self, force_new_format)
+        build_inner(&mut writer, &self.input, &self, force_new_format, game)
     }
 }
 
@@ -59,36 +56,35 @@ fn build_inner(
     input: &PathBuf,
     args: &BuildCommand,
     new_mix: bool,
+    game: GameEnum,
 ) -> Result<()> {
     let paths = std::fs::read_dir(input)?;
-    let mut mix = Mix::default();
-    let mut lmd = LocalMixDatabase::default();
-    let crc_version = match args.old_crc {
-        true => GameEnum::TD,
-        false => GameEnum::YR,
+    let mut mix = Mix {
+        index_order: game.profile().index_order,
+        ..Default::default()
+    };
+    let mut lmd = LocalMixDatabase {
+        version: game.into(),
+        ..Default::default()
     };
     for res in paths {
         let path = res?.path();
         let str = path_to_filename(&path)?;
         if path.is_dir() {
             let mut temp: Vec<u8> = vec![];
-            build_inner(&mut temp, &path, args, new_mix)?;
-            mix.add_file_raw(temp, crc(&str, crc_version), false)?;
+            build_inner(&mut temp, &path, args, new_mix, game)?;
+            mix.add_file_raw(temp, crc(&str, game), false)?;
         } else {
-            mix.add_file_from_path(path, crc_version, args.overwrite)?;
+            mix.add_file_from_path(path, game, args.overwrite)?;
         }
         if args.lmd {
-            lmd.db.names.insert(crc(&str, crc_version), str);
+            lmd.db.insert(crc(&str, game), str);
         }
     }
     if args.lmd {
         let mut temp: Vec<u8> = vec![];
         LocalMixDbWriter::write_file(&mut temp, &lmd)?;
-        let lmd_id = match args.old_crc {
-            true => LMD_KEY_TD,
-            false => LMD_KEY_TS,
-        };
-        mix.add_file_raw(temp, lmd_id, false)?;
+        mix.add_file_raw(temp, game.profile().lmd_key, false)?;
     }
     if args.encrypt {
         encrypt_mix(&mut mix, &args.key)?;
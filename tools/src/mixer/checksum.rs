@@ -2,13 +2,13 @@ use std::path::PathBuf;
 
 use clap::Subcommand;
 
-use rust_alert::mix::{Mix, MixHeaderFlags};
-
-use crate::{
-    utils::{read_mix, write_mix},
-    Error, Result, RunCommand,
+use rust_alert::{
+    core::GameEnum,
+    mix::{io::MixReader, Mix, MixHeaderFlags},
 };
 
+use crate::{utils::write_mix, Error, Result, RunCommand};
+
 #[derive(clap::Args)]
 pub struct ChecksumCommand {
     /// Mode of operation.
@@ -22,8 +22,8 @@ pub struct ChecksumCommand {
 
 impl RunCommand for ChecksumCommand {
     /// Add checksum to MIX, remove checksum from MIX, or check if checksum in the MIX is true.
-    fn run(self, force_new_format: bool, _safe_mode: bool) -> Result<()> {
-        let mut mix = read_mix(&self.input, force_new_format)?;
+    fn run(self, force_new_format: bool, _safe_mode: bool, _game: GameEnum) -> Result<()> {
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
         match self.mode {
             ChecksumMode::Add => {
                 mix.calc_checksum();
@@ -1,9 +1,8 @@
 use std::path::PathBuf;
 
-use crate::{
-    utils::{read_mix, write_mix},
-    Result, RunCommand,
-};
+use rust_alert::{core::GameEnum, mix::io::MixReader};
+
+use crate::{utils::write_mix, Result, RunCommand};
 
 #[derive(clap::Args)]
 pub struct CompactCommand {
@@ -15,8 +14,8 @@ pub struct CompactCommand {
 
 impl RunCommand for CompactCommand {
     /// Compact the MIX: remove all data not belonging to any file.
-    fn run(self, force_new_format: bool, _safe_mode: bool) -> Result<()> {
-        let mut mix = read_mix(&self.input, force_new_format)?;
+    fn run(self, force_new_format: bool, _safe_mode: bool, _game: GameEnum) -> Result<()> {
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
         mix.recalc();
         write_mix(
             &mut mix,
@@ -4,7 +4,15 @@ use std::{
     path::PathBuf,
 };
 
-use rust_alert::mix::{db::MixDatabase, io::MixReader};
+use rust_alert::{
+    core::GameEnum,
+    mix::{
+        db::{GlobalMixDatabase, MixDatabaseLookup},
+        guess_file_type,
+        io::MixReader,
+        FileTypeEnum,
+    },
+};
 
 #[derive(clap::Args)]
 pub struct ExtractCommand {
@@ -18,33 +26,33 @@ pub struct ExtractCommand {
     /// Recursively extract MIXes from MIXes to subfolders.
     #[arg(short, long, default_value_t = false)]
     recursive: bool,
-    /// Path to a MIX database in INI format.
+    /// Path to a MIX database in INI or compact format. Defaults to the built-in database of the game.
     #[arg(short, long)]
     db: Option<PathBuf>,
 }
 
 use crate::{
-    utils::{prepare_databases, read_db},
+    utils::{chain_databases, prepare_lmd, read_db, DbEnum},
     Result, RunCommand,
 };
 
 impl RunCommand for ExtractCommand {
     /// Extract all files from a MIX.
-    fn run(self, force_new_format: bool, safe_mode: bool) -> Result<()> {
+    fn run(self, force_new_format: bool, safe_mode: bool, game: GameEnum) -> Result<()> {
         let mut reader = OpenOptions::new().read(true).open(&self.input)?;
-        let gmd = self
-            .db
-            .clone()
-            .map(|p| read_db(&p))
-            .transpose()?
-            .unwrap_or_default();
+        let data = self.db.as_ref().map(std::fs::read).transpose()?;
+        let db = match &data {
+            Some(data) => read_db(data)?,
+            None => DbEnum::Loaded(GlobalMixDatabase::builtin(game)),
+        };
         extract_inner(
             &mut reader,
             &self.output,
             &self,
             force_new_format,
-            &gmd,
+            &db.lookups(),
             safe_mode,
+            game,
         )?;
         Ok(())
     }
@@ -55,32 +63,45 @@ fn extract_inner(
     output_dir: &PathBuf,
     args: &ExtractCommand,
     new_mix: bool,
-    gmd: &MixDatabase,
+    dbs: &[&dyn MixDatabaseLookup],
     safe_mode: bool,
+    game: GameEnum,
 ) -> Result<()> {
     let mix = MixReader::read_file(reader, new_mix)?;
     std::fs::create_dir_all(output_dir)?;
-    let (mixdb, _) = prepare_databases(&mix, gmd.clone(), safe_mode)?;
+    let lmd = prepare_lmd(&mix, safe_mode, game);
+    let mixdb = chain_databases(lmd.as_ref(), dbs);
 
     for file in mix.index.values() {
-        let filename = mixdb.get_name_or_id(file.id);
+        let data = mix.get_file(file.id).unwrap_or_else(|| unreachable!());
+        let filetype = guess_file_type(data);
+        // Unnamed files get an extension based on their guessed type.
+        let candidates = mixdb.get_all(file.id, game);
+        let filename = match (candidates.first(), filetype) {
+            (Some(entry), _) => entry.name.clone(),
+            (None, Some(t)) => format!("{:0>8X}.{}", file.id, t.extension()),
+            (None, None) => format!("{:0>8X}", file.id),
+        };
 
         if !args.quiet {
             println!("{}, {} bytes", filename, file.size);
+            if candidates.len() > 1 {
+                let others: Vec<_> = candidates[1..].iter().map(|x| x.name.as_str()).collect();
+                println!("Warning: {} might also be {}", filename, others.join(", "));
+            }
         }
-        if args.recursive && filename.ends_with(".mix") {
-            let mix_reader: &mut dyn Read =
-                &mut mix.get_file(file.id).unwrap_or_else(|| unreachable!());
+        if args.recursive && (filename.ends_with(".mix") || filetype == Some(FileTypeEnum::Mix)) {
+            let mix_reader: &mut dyn Read = &mut &data[..];
             extract_inner(
                 mix_reader,
                 &output_dir.join(filename),
                 args,
                 new_mix,
-                gmd,
+                dbs,
                 safe_mode,
+                game,
             )?;
         } else {
-            let data = mix.get_file(file.id).unwrap_or_else(|| unreachable!());
             write(output_dir.join(filename), data)?;
         }
     }
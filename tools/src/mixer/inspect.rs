@@ -1,12 +1,18 @@
 use std::{fs::OpenOptions, path::PathBuf};
 
 use rust_alert::{
-    mix::{db::GlobalMixDatabase, io::MixReader, BlowfishKey, Checksum, Mix},
+    core::GameEnum,
+    mix::{
+        db::{GlobalMixDatabase, MixDatabaseChain},
+        guess_file_type,
+        io::MixReader,
+        BlowfishKey, Checksum, Mix,
+    },
     printoptionmapln,
 };
 
 use crate::{
-    utils::{prepare_databases, read_db},
+    utils::{chain_databases, prepare_lmd, read_db, DbEnum},
     Result, RunCommand,
 };
 
@@ -20,7 +26,8 @@ pub struct InspectCommand {
     /// Do not print the file index.
     #[arg(long, default_value_t = false)]
     no_index: bool,
-    /// Path to a MIX database (containing filenames) in INI format.
+    /// Path to a MIX database (containing filenames) in INI or compact format.
+    /// Defaults to the built-in database of the game.
     #[arg(short, long)]
     db: Option<PathBuf>,
     /// Sort file index (in ascending order) by given column.
@@ -45,40 +52,33 @@ impl std::fmt::Display for InspectSortOrderEnum {
 
 impl RunCommand for InspectCommand {
     /// Inspect the MIX, printing useful header information and/or index contents.
-    fn run(self, force_new_format: bool, safe_mode: bool) -> Result<()> {
+    fn run(self, force_new_format: bool, safe_mode: bool, game: GameEnum) -> Result<()> {
         let mut reader = OpenOptions::new().read(true).open(self.input)?;
         let mut mix = MixReader::read_file(&mut reader, force_new_format)?;
-        let gmd = self
-            .db
-            .map(|p| read_db(&p))
-            .transpose()?
-            .unwrap_or_default();
-        let (mixdb, has_lmd) = prepare_databases(&mix, gmd, safe_mode)?;
+        let data = self.db.map(std::fs::read).transpose()?;
+        let db = match &data {
+            Some(data) => read_db(data)?,
+            None => DbEnum::Loaded(GlobalMixDatabase::builtin(game)),
+        };
+        let lmd = prepare_lmd(&mix, safe_mode, game);
+        let mixdb = chain_databases(lmd.as_ref(), &db.lookups());
         if !self.no_header {
-            inspect_header(&mut mix, has_lmd, safe_mode);
+            inspect_header(&mut mix, lmd.is_some(), safe_mode);
             if !self.no_index {
                 println!();
             }
         }
         if !self.no_index {
-            inspect_index(&mut mix, &mixdb, self.sort);
+            inspect_index(&mut mix, &mixdb, self.sort, game);
         }
         Ok(())
     }
 }
 
 /// Sort given MIX by names from given GMD.
-fn sort_by_name(mix: &mut Mix, db: &GlobalMixDatabase) {
-    mix.index.sort_by(|_, f1, _, f2| {
-        db.get_name(f1.id)
-            .unwrap_or(&String::default())
-            .to_lowercase()
-            .cmp(
-                &db.get_name(f2.id)
-                    .unwrap_or(&String::default())
-                    .to_lowercase(),
-            )
-    });
+fn sort_by_name(mix: &mut Mix, db: &MixDatabaseChain, game: GameEnum) {
+    mix.index
+        .sort_by_cached_key(|_, f| db.get_name(f.id, game).unwrap_or_default().to_lowercase());
 }
 
 fn inspect_header(mix: &mut Mix, has_lmd: bool, safe_mode: bool) {
@@ -113,36 +113,76 @@ fn inspect_header(mix: &mut Mix, has_lmd: bool, safe_mode: bool) {
     println!("Has LMD:            {}", msg);
 }
 
-fn inspect_index(mix: &mut Mix, mixdb: &GlobalMixDatabase, sort: InspectSortOrderEnum) {
+fn inspect_index(
+    mix: &mut Mix,
+    mixdb: &MixDatabaseChain,
+    sort: InspectSortOrderEnum,
+    game: GameEnum,
+) {
     match sort {
         InspectSortOrderEnum::Id => mix.sort_by_id(),
-        InspectSortOrderEnum::Name => sort_by_name(mix, mixdb),
+        InspectSortOrderEnum::Name => sort_by_name(mix, mixdb, game),
         InspectSortOrderEnum::Offset => mix.sort_by_offset(),
         InspectSortOrderEnum::Size => mix.sort_by_size(),
     }
-    let names: Vec<_> = mix
+    let candidates: Vec<_> = mix
+        .index
+        .values()
+        .map(|f| mixdb.get_all(f.id, game))
+        .collect();
+    let names: Vec<_> = candidates
+        .iter()
+        .map(|x| x.first().map(|x| x.name.clone()).unwrap_or_default())
+        .collect();
+    let sources: Vec<_> = candidates
+        .iter()
+        .map(|x| x.first().map(|x| x.source.to_string()).unwrap_or_default())
+        .collect();
+    // Other names with the same ID, which might be the real one instead.
+    let others: Vec<_> = candidates
+        .iter()
+        .map(|x| {
+            x.iter()
+                .skip(1)
+                .map(|x| format!("{} ({})", x.name, x.source))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect();
+    let types: Vec<_> = mix
         .index
         .values()
-        .map(|f| mixdb.get_name(f.id).cloned().unwrap_or(String::default()))
+        .map(|f| {
+            mix.get_file(f.id)
+                .and_then(guess_file_type)
+                .map(|t| t.to_string())
+                .unwrap_or_default()
+        })
         .collect();
     let maxname = names.iter().map(|x| x.len()).max().unwrap_or_default();
     println!(
-        "{: <maxname$} {: <8} {: >10} {: >10}",
+        "{: <maxname$} {: <8} {: >10} {: >10} {: <8} {: <8} Other names",
         "Name",
         "ID",
         "Offset",
         "Size",
+        "Type",
+        "Source",
         maxname = maxname
     );
-    let total_len = maxname + 28 + 3;
+    let total_len = maxname + 55 + 6;
     println!("{:=<len$}", "", len = total_len);
-    for (f, name) in mix.index.values().zip(names) {
+    let rows = names.into_iter().zip(types).zip(sources).zip(others);
+    for (f, (((name, filetype), source), others)) in mix.index.values().zip(rows) {
         println!(
-            "{: <len$} {:0>8X} {: >10?} {: >10?}",
+            "{: <len$} {:0>8X} {: >10?} {: >10?} {: <8} {: <8} {}",
             name,
             f.id,
             f.offset,
             f.size,
+            filetype,
+            source,
+            others,
             len = maxname,
         )
     }
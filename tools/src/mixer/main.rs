@@ -2,7 +2,7 @@
 
 use clap::{Parser, Subcommand};
 
-use rust_alert::{make_app, utils::PathToStringError};
+use rust_alert::{core::GameEnum, make_app, utils::PathToStringError};
 
 mod blowfish;
 mod build;
@@ -11,6 +11,7 @@ mod compact;
 mod extract;
 mod inspect;
 mod utils;
+mod validate;
 
 use blowfish::BlowfishCommand;
 use build::BuildCommand;
@@ -18,6 +19,7 @@ use checksum::ChecksumCommand;
 use compact::CompactCommand;
 use extract::ExtractCommand;
 use inspect::InspectCommand;
+use validate::ValidateCommand;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -37,10 +39,14 @@ pub enum Error {
     InvalidChecksum,
     #[error("Cannot extract key out of a decrypted MIX")]
     MissingKey,
+    #[error("Found {0} problem(s) in the MIX")]
+    InvalidMix(usize),
     #[error("{0}")]
     PathToStringError(#[from] PathToStringError),
     #[error("{0}")]
     LMDIOError(#[from] rust_alert::mix::db::io::Error),
+    #[error("{0}")]
+    Compact(#[from] rust_alert::mix::db::compact::Error),
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -60,6 +66,11 @@ struct Args {
     /// Safe mode ignores LMDs, but may prevent crashes.
     #[arg(long, default_value_t = false)]
     safe_mode: bool,
+    /// Game the MIX is for. Decides the CRC algorithm and LMD key. Defaults to YR.
+    /// New MIXes are built in the MIX format of the game only if it's given,
+    /// and in the old format otherwise.
+    #[arg(short, long, global = true)]
+    game: Option<GameEnum>,
 }
 
 /// Modes of operation.
@@ -78,23 +89,28 @@ enum Commands {
     /// Inspect MIX file. Print general information such as header values,
     /// checksum, encryption key, as well as the file index.
     Inspect(InspectCommand),
+    /// Check if the MIX can be loaded by the game, e.g. if its index is sorted the way the game expects.
+    Validate(ValidateCommand),
 }
 
-impl RunCommand for Commands {
-    fn run(self, force_new_format: bool, safe_mode: bool) -> Result<()> {
+impl Commands {
+    fn run(self, force_new_format: bool, safe_mode: bool, game: Option<GameEnum>) -> Result<()> {
+        let build_new_format = force_new_format || game.is_some_and(|x| x.profile().new_mix_format);
+        let game = game.unwrap_or(GameEnum::YR);
         match self {
-            Commands::Build(x) => x.run(force_new_format, safe_mode),
-            Commands::Checksum(x) => x.run(force_new_format, safe_mode),
-            Commands::Compact(x) => x.run(force_new_format, safe_mode),
-            Commands::Blowfish(x) => x.run(force_new_format, safe_mode),
-            Commands::Extract(x) => x.run(force_new_format, safe_mode),
-            Commands::Inspect(x) => x.run(force_new_format, safe_mode),
+            Commands::Build(x) => x.run(build_new_format, safe_mode, game),
+            Commands::Checksum(x) => x.run(force_new_format, safe_mode, game),
+            Commands::Compact(x) => x.run(force_new_format, safe_mode, game),
+            Commands::Blowfish(x) => x.run(force_new_format, safe_mode, game),
+            Commands::Extract(x) => x.run(force_new_format, safe_mode, game),
+            Commands::Inspect(x) => x.run(force_new_format, safe_mode, game),
+            Commands::Validate(x) => x.run(force_new_format, safe_mode, game),
         }
     }
 }
 
 trait RunCommand {
-    fn run(self, force_new_format: bool, safe_mode: bool) -> Result<()>;
+    fn run(self, force_new_format: bool, safe_mode: bool, game: GameEnum) -> Result<()>;
 }
 
-make_app!(Args, new_mix, safe_mode);
+make_app!(Args, new_mix, safe_mode, game);
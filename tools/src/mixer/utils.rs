@@ -1,28 +1,23 @@
-use std::{
-    fs::OpenOptions,
-    io::{BufReader, Read},
-    path::PathBuf,
-};
+use std::{fs::OpenOptions, io::Read, path::PathBuf};
 
 use rust_alert::{
-    converters::ini2db,
+    converters::ini2gmd,
+    core::GameEnum,
     defaultarray,
     ini::io::IniReader,
     mix::{
-        db::{io::LocalMixDbReader, GlobalMixDatabase, LocalMixDatabase, MixDatabase},
-        io::{generate_blowfish, MixReader, MixWriter},
-        BlowfishKey, Mix, LMD_KEY_TD, LMD_KEY_TS,
+        db::{
+            compact::{CompactMixDatabase, COMPACT_MAGIC},
+            io::LocalMixDbReader,
+            GlobalMixDatabase, MixDatabase, MixDatabaseChain, MixDatabaseLookup,
+        },
+        io::{generate_blowfish, MixWriter},
+        BlowfishKey, Mix,
     },
 };
 
 use crate::Result;
 
-pub fn read_mix(input: &PathBuf, new_mix: bool) -> Result<Mix> {
-    let mut reader = OpenOptions::new().read(true).open(input)?;
-    let mix = MixReader::read_file(&mut reader, new_mix)?;
-    Ok(mix)
-}
-
 pub fn write_mix(mix: &mut Mix, output: &PathBuf, new_mix: bool) -> Result<()> {
     let mut writer = OpenOptions::new()
         .write(true)
@@ -46,53 +41,59 @@ pub fn encrypt_mix(mix: &mut Mix, key: &Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-/// Read a MIX database from an INI file.
-pub fn read_db(path: &PathBuf) -> Result<MixDatabase> {
-    let reader = OpenOptions::new().read(true).open(path)?;
-    let reader = BufReader::new(reader);
-    let ini = IniReader::read_file(reader)?;
-    let db = ini2db(ini)?;
-    Ok(db)
+/// MIX databases given to a command.
+pub enum DbEnum<'a> {
+    /// Databases read from an INI file, or the built-in ones.
+    Loaded(GlobalMixDatabase),
+    /// A compact database, looked up in place.
+    Compact(CompactMixDatabase<'a>),
 }
 
-/// Read an LMD from inside a MIX.
-pub fn read_lmd(mix: &Mix) -> Option<LocalMixDatabase> {
-    let lmd = mix.get_file(if mix.is_new_format {
-        LMD_KEY_TS
-    } else {
-        LMD_KEY_TD
-    });
-
-    let lmd = if let Some(mut lmd) = lmd {
-        let x: &mut dyn Read = &mut lmd;
-        Some(LocalMixDbReader::read_file(x))
-    } else {
-        None
-    };
-
-    match lmd.transpose() {
-        Ok(x) => x,
-        Err(x) => {
-            println!("Warning: found LMD, but failed to read it. Reason: {}", x);
-            None
+impl DbEnum<'_> {
+    /// Returns all databases, ready for lookups.
+    pub fn lookups(&self) -> Vec<&dyn MixDatabaseLookup> {
+        match self {
+            Self::Loaded(gmd) => gmd.dbs.iter().map(|x| x as _).collect(),
+            Self::Compact(view) => vec![view],
         }
     }
 }
 
-/// Read GMD & LMD and merge them.
-pub fn prepare_databases(
-    mix: &Mix,
-    gmd: MixDatabase,
-    safe_mode: bool,
-) -> Result<(GlobalMixDatabase, bool)> {
-    let mut mixdb = GlobalMixDatabase::default();
-    let mut has_lmd = false;
-    if !safe_mode {
-        if let Some(lmd) = read_lmd(mix) {
-            mixdb.dbs.push(lmd.db);
-            has_lmd = true;
-        }
+/// Read MIX databases from data of an INI file, one per section. Sections named after
+/// a game (like `[TD]`) are tagged with that game. Compact databases are not copied,
+/// but looked up in place with a binary search.
+pub fn read_db(data: &[u8]) -> Result<DbEnum<'_>> {
+    if data.starts_with(COMPACT_MAGIC) {
+        return Ok(DbEnum::Compact(CompactMixDatabase::new(data)?));
+    }
+    let ini = IniReader::read_file(data)?;
+    let gmd = ini2gmd(ini)?;
+    Ok(DbEnum::Loaded(gmd))
+}
+
+/// Read the LMD of a MIX, unless in safe mode. The LMD is tagged with given game,
+/// so it's searched before any other database.
+pub fn prepare_lmd(mix: &Mix, safe_mode: bool, game: GameEnum) -> Option<MixDatabase> {
+    if safe_mode {
+        return None;
     }
-    mixdb.dbs.push(gmd);
-    Ok((mixdb, has_lmd))
+    let lmd = LocalMixDbReader::read_from_mix(mix, game).unwrap_or_else(|e| {
+        println!("Warning: found LMD, but failed to read it. Reason: {}", e);
+        None
+    });
+    lmd.map(|x| MixDatabase {
+        game: Some(game),
+        ..x.db
+    })
+}
+
+/// Chain the LMD of a MIX with other databases, searching it first.
+pub fn chain_databases<'a>(
+    lmd: Option<&'a MixDatabase>,
+    dbs: &[&'a dyn MixDatabaseLookup],
+) -> MixDatabaseChain<'a> {
+    let mut chain = MixDatabaseChain::default();
+    chain.dbs.extend(lmd.map(|x| x as &dyn MixDatabaseLookup));
+    chain.dbs.extend_from_slice(dbs);
+    chain
 }
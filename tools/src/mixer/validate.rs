@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use rust_alert::{core::GameEnum, mix::io::MixReader};
+
+use crate::{Error, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct ValidateCommand {
+    /// Path to an input MIX file.
+    input: PathBuf,
+}
+
+impl RunCommand for ValidateCommand {
+    /// Check the MIX for problems, expecting the index order of given game.
+    /// Raises error when any problem is found.
+    fn run(self, force_new_format: bool, _safe_mode: bool, game: GameEnum) -> Result<()> {
+        let mut mix = MixReader::read_path(&self.input, force_new_format)?;
+        mix.index_order = game.profile().index_order;
+        let issues = mix.validate();
+        if issues.is_empty() {
+            println!("No problems found.");
+            return Ok(());
+        }
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        Err(Error::InvalidMix(issues.len()))
+    }
+}
@@ -17,12 +17,12 @@ pub struct BuildCommand {
     input: PathBuf,
     /// Path to an output CSF file.
     output: PathBuf,
-    /// CSF language ID.
-    #[arg(short, long, value_enum, default_value_t = CsfLanguageEnum::ENUS)]
-    language: CsfLanguageEnum,
-    /// CSF format version.
-    #[arg(short, long, value_enum, default_value_t = CsfVersionEnum::Cnc)]
-    version: CsfVersionEnum,
+    /// CSF language ID. Overrides the language stored in the INI file, if any.
+    #[arg(short, long, value_enum)]
+    language: Option<CsfLanguageEnum>,
+    /// CSF format version. Overrides the version stored in the INI file, if any.
+    #[arg(short, long, value_enum)]
+    version: Option<CsfVersionEnum>,
     /// Sort all strings.
     #[arg(short, long, default_value_t = false)]
     sort: bool,
@@ -41,9 +41,13 @@ impl RunCommand for BuildCommand {
         if self.sort {
             ini.sort_nested()
         }
-        let mut csf = ini2csf(ini);
-        csf.language = self.language;
-        csf.version = self.version;
+        let mut csf = ini2csf(ini)?;
+        if let Some(language) = self.language {
+            csf.language = language;
+        }
+        if let Some(version) = self.version {
+            csf.version = version;
+        }
         CsfReader::new().write(&csf, &mut writer)?;
         Ok(())
     }
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use rust_alert::csf::check_translation;
+
+use crate::{utils::read_csf, Error, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct CheckCommand {
+    /// Path to a CSF in the base language.
+    base: PathBuf,
+    /// Paths to translated CSFs.
+    #[arg(required = true)]
+    translations: Vec<PathBuf>,
+}
+
+impl RunCommand for CheckCommand {
+    /// Compare placeholders, escapes and newlines of translations with the base CSF.
+    /// Raises error when any problem is found.
+    fn run(self) -> Result<()> {
+        let base = read_csf(&self.base)?;
+        let mut count = 0;
+        for path in &self.translations {
+            let translation = read_csf(path)?;
+            let issues = check_translation(&base, &translation);
+            for issue in &issues {
+                println!("{}: {}", path.display(), issue);
+            }
+            count += issues.len();
+        }
+        if count == 0 {
+            println!("No problems found.");
+            return Ok(());
+        }
+        Err(Error::InvalidTranslation(count))
+    }
+}
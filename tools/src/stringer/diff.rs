@@ -0,0 +1,98 @@
+use std::{collections::HashSet, fs::OpenOptions, path::PathBuf};
+
+use rust_alert::csf::{
+    fingerprints,
+    io::{CsfReader, CsfWrite},
+    CsfDiff,
+};
+
+use crate::{
+    utils::{
+        get_fingerprints, language_key, read_csf, read_fingerprints, set_fingerprints,
+        write_fingerprints,
+    },
+    Error, Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct DiffCommand {
+    /// Path to a CSF in the base language.
+    base: PathBuf,
+    /// Paths to translated CSFs.
+    #[arg(required = true)]
+    translations: Vec<PathBuf>,
+    /// Path to a fingerprint file, used to find labels whose base strings changed.
+    #[arg(short, long)]
+    fingerprints: Option<PathBuf>,
+    /// Store fingerprints of the base labels present in every translation,
+    /// marking them as up to date. Fingerprints are stored per language,
+    /// so every translation must be in a different language.
+    #[arg(short, long, default_value_t = false, requires = "fingerprints")]
+    update: bool,
+    /// Overwrite every translation with base labels it was missing added,
+    /// and labels missing from the base CSF removed. Added labels keep base strings.
+    #[arg(short, long, default_value_t = false)]
+    merge: bool,
+}
+
+impl RunCommand for DiffCommand {
+    fn run(self) -> Result<()> {
+        let base = read_csf(&self.base)?;
+        let mut stored = match &self.fingerprints {
+            Some(path) => read_fingerprints(path)?,
+            None => Default::default(),
+        };
+        let translations = self
+            .translations
+            .iter()
+            .map(read_csf)
+            .collect::<Result<Vec<_>>>()?;
+        if self.update {
+            let mut languages = HashSet::new();
+            for translation in &translations {
+                if !languages.insert(translation.language) {
+                    return Err(Error::DuplicateLanguage(language_key(translation.language)));
+                }
+            }
+        }
+        for (path, mut translation) in self.translations.iter().zip(translations) {
+            let fp = get_fingerprints(&stored, translation.language)?;
+            let diff = CsfDiff::new(&base, &translation, Some(&fp));
+            println!(
+                "{} ({}):",
+                path.display(),
+                language_key(translation.language)
+            );
+            for name in &diff.missing {
+                println!("- {name}");
+            }
+            for name in &diff.extra {
+                println!("+ {name}");
+            }
+            for name in &diff.changed {
+                println!("~ {name}");
+            }
+            if diff.is_empty() {
+                println!("No differences");
+            }
+            if self.update {
+                let mut fp = fingerprints(&base);
+                fp.retain(|name, _| translation.get(name.as_str()).is_some());
+                set_fingerprints(&mut stored, translation.language, &fp);
+            }
+            if self.merge && !(diff.missing.is_empty() && diff.extra.is_empty()) {
+                diff.merge(&base, &mut translation);
+                let mut writer = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(path)?;
+                CsfReader::new().write(&translation, &mut writer)?;
+            }
+        }
+        if let (true, Some(path)) = (self.update, &self.fingerprints) {
+            write_fingerprints(&stored, path)?;
+        }
+        Ok(())
+    }
+}
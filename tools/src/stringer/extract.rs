@@ -28,7 +28,7 @@ impl RunCommand for ExtractCommand {
             .truncate(true)
             .open(&self.output)?;
         let csf = CsfReader::new().read(&mut reader)?;
-        let mut ini = csf2ini(csf)?;
+        let mut ini = csf2ini(csf);
         if self.sort {
             ini.sort_nested();
         }
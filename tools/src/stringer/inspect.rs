@@ -17,12 +17,12 @@ impl RunCommand for InspectCommand {
         println!(
             "Version:      {:?} ({})",
             csf.version,
-            TryInto::<u32>::try_into(csf.version)?
+            u32::from(csf.version)
         );
         println!(
             "Language:     {:?} ({})",
             csf.language,
-            TryInto::<u32>::try_into(csf.language)?
+            u32::from(csf.language)
         );
         println!("Extra data:   {:X}", csf.extra);
         println!("# of labels:  {:?}", csf.len());
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use rust_alert::csf::CsfLintOptions;
+
+use crate::{utils::read_csf, Error, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct LintCommand {
+    /// Path to an input CSF file.
+    input: PathBuf,
+    /// Size of the engine string buffer in UTF-16 code units, including the terminating null.
+    #[arg(short, long, default_value_t = CsfLintOptions::default().string_buffer_size)]
+    buffer_size: usize,
+}
+
+impl RunCommand for LintCommand {
+    /// Check the CSF for labels and strings the game can't handle.
+    /// Raises error when any problem is found.
+    fn run(self) -> Result<()> {
+        let csf = read_csf(&self.input)?;
+        let options = CsfLintOptions {
+            string_buffer_size: self.buffer_size,
+        };
+        let issues = csf.lint(&options);
+        if issues.is_empty() {
+            println!("No problems found.");
+            return Ok(());
+        }
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        Err(Error::InvalidStringtable(issues.len()))
+    }
+}
@@ -3,13 +3,28 @@
 use clap::{Parser, Subcommand};
 
 mod build;
+mod check;
+mod diff;
 mod extract;
 mod inspect;
+mod lint;
+mod po;
+mod status;
+mod table;
+mod utils;
+mod xliff;
 
 use build::BuildCommand;
+use check::CheckCommand;
+use diff::DiffCommand;
 use extract::ExtractCommand;
 use inspect::InspectCommand;
+use lint::LintCommand;
+use po::PoCommand;
 use rust_alert::make_app;
+use status::StatusCommand;
+use table::TableCommand;
+use xliff::XliffCommand;
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -20,9 +35,27 @@ enum Error {
     #[error("{0}")]
     IniIO(#[from] rust_alert::ini::io::Error),
     #[error("{0}")]
-    Csf(#[from] rust_alert::csf::Error),
-    #[error("{0}")]
     Conversion(#[from] rust_alert::converters::CSFConversionError),
+    #[error("{0}")]
+    PoIO(#[from] rust_alert::po::io::Error),
+    #[error("{0}")]
+    PoConversion(#[from] rust_alert::converters::POConversionError),
+    #[error("{0}")]
+    XliffIO(#[from] rust_alert::xliff::io::Error),
+    #[error("{0}")]
+    XliffConversion(#[from] rust_alert::converters::XliffConversionError),
+    #[error("Invalid fingerprint {1:?} of label {0}")]
+    InvalidFingerprint(String, String),
+    #[error("More than one translation is in language {0}")]
+    DuplicateLanguage(String),
+    #[error("Found {0} problem(s) in translations")]
+    InvalidTranslation(usize),
+    #[error("Found {0} problem(s) in the CSF")]
+    InvalidStringtable(usize),
+    #[error("{0}")]
+    TableIO(#[from] rust_alert::table::io::Error),
+    #[error("{0}")]
+    TableConversion(#[from] rust_alert::converters::TableConversionError),
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -47,6 +80,20 @@ enum Commands {
     Extract(ExtractCommand),
     /// Inspect CSF file.
     Inspect(InspectCommand),
+    /// Export CSF to gettext PO files and import them back.
+    Po(PoCommand),
+    /// Export CSF to XLIFF files and import them back.
+    Xliff(XliffCommand),
+    /// List labels missing from, extra in or outdated in translated CSFs, and merge them.
+    Diff(DiffCommand),
+    /// Show translation completion of translated CSFs, per language and category.
+    Status(StatusCommand),
+    /// Check placeholders, escapes and newlines of translated CSFs against the base CSF.
+    Check(CheckCommand),
+    /// Check CSF for labels and strings the game can't handle.
+    Lint(LintCommand),
+    /// Export CSFs to a CSV or TSV table with one column per language and import them back.
+    Table(TableCommand),
 }
 
 trait RunCommand {
@@ -59,6 +106,13 @@ impl RunCommand for Commands {
             Commands::Build(x) => x.run(),
             Commands::Extract(x) => x.run(),
             Commands::Inspect(x) => x.run(),
+            Commands::Po(x) => x.run(),
+            Commands::Xliff(x) => x.run(),
+            Commands::Diff(x) => x.run(),
+            Commands::Status(x) => x.run(),
+            Commands::Check(x) => x.run(),
+            Commands::Lint(x) => x.run(),
+            Commands::Table(x) => x.run(),
         }
     }
 }
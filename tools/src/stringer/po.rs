@@ -0,0 +1,126 @@
+use std::{
+    fs::{create_dir_all, OpenOptions},
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+
+use rust_alert::{
+    converters::{csf2po, csf2pot, po2csf},
+    csf::{
+        io::{CsfReader, CsfWrite},
+        CsfLanguageEnum, CsfVersionEnum,
+    },
+    po::{
+        io::{PoReader, PoWriter},
+        PoFile,
+    },
+};
+
+use crate::{utils::read_csf, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct PoCommand {
+    /// Mode of operation.
+    #[command(subcommand)]
+    mode: PoMode,
+}
+
+#[derive(Subcommand)]
+enum PoMode {
+    /// Write a POT template from a base CSF, and a PO file for every translated CSF.
+    Export(ExportArgs),
+    /// Build CSF from a PO file. Untranslated and fuzzy strings are taken from the base language.
+    Import(ImportArgs),
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    /// Path to a CSF in the base language.
+    base: PathBuf,
+    /// Path to an output directory.
+    output: PathBuf,
+    /// Paths to translated CSFs. PO files are named after their language code, or the CSF name.
+    translations: Vec<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct ImportArgs {
+    /// Path to an input PO file.
+    input: PathBuf,
+    /// Path to an output CSF file.
+    output: PathBuf,
+    /// CSF language ID.
+    #[arg(short, long, value_enum)]
+    language: CsfLanguageEnum,
+    /// CSF format version.
+    #[arg(short, long, value_enum, default_value_t = CsfVersionEnum::Cnc)]
+    version: CsfVersionEnum,
+}
+
+impl RunCommand for PoCommand {
+    fn run(self) -> Result<()> {
+        match self.mode {
+            PoMode::Export(x) => export(x),
+            PoMode::Import(x) => import(x),
+        }
+    }
+}
+
+fn export(args: ExportArgs) -> Result<()> {
+    let base = read_csf(&args.base)?;
+    create_dir_all(&args.output)?;
+    let stem = |path: &Path| {
+        path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    let pot_path = args.output.join(format!("{}.pot", stem(&args.base)));
+    write_po(&csf2pot(&base), &pot_path)?;
+    for path in &args.translations {
+        let translation = read_csf(path)?;
+        let name = match translation.language.code() {
+            Some(code) => code.to_string(),
+            None => stem(path),
+        };
+        let po_path = args.output.join(format!("{name}.po"));
+        write_po(&csf2po(&base, &translation), &po_path)?;
+        println!("{} -> {}", path.display(), po_path.display());
+    }
+    Ok(())
+}
+
+fn import(args: ImportArgs) -> Result<()> {
+    let reader = OpenOptions::new().read(true).open(&args.input)?;
+    let po = PoReader::read_file(BufReader::new(reader))?;
+    let (mut csf, report) = po2csf(&po, args.language)?;
+    csf.version = args.version;
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&args.output)?;
+    CsfReader::new().write(&csf, &mut writer)?;
+
+    for context in &report.fuzzy {
+        println!("Fuzzy: {context}");
+    }
+    for id in &report.skipped {
+        println!("Skipped entry with no context: {id:?}");
+    }
+    println!("# of fuzzy strings:        {}", report.fuzzy.len());
+    println!("# of untranslated strings: {}", report.missing.len());
+    Ok(())
+}
+
+fn write_po(po: &PoFile, path: &PathBuf) -> Result<()> {
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    PoWriter::write_file(po, &mut writer)?;
+    Ok(())
+}
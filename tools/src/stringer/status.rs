@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use rust_alert::csf::{CsfCoverage, CsfCoverageCount};
+
+use crate::{
+    utils::{get_fingerprints, language_key, read_csf, read_fingerprints},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct StatusCommand {
+    /// Path to a CSF in the base language.
+    base: PathBuf,
+    /// Paths to translated CSFs.
+    #[arg(required = true)]
+    translations: Vec<PathBuf>,
+    /// Path to a fingerprint file. Translated labels whose base strings changed don't count as complete.
+    #[arg(short, long)]
+    fingerprints: Option<PathBuf>,
+}
+
+impl RunCommand for StatusCommand {
+    fn run(self) -> Result<()> {
+        let base = read_csf(&self.base)?;
+        let stored = match &self.fingerprints {
+            Some(path) => read_fingerprints(path)?,
+            None => Default::default(),
+        };
+        for path in &self.translations {
+            let translation = read_csf(path)?;
+            let fp = get_fingerprints(&stored, translation.language)?;
+            let coverage = CsfCoverage::new(&base, &translation, Some(&fp));
+            println!(
+                "{} ({}):",
+                path.display(),
+                language_key(translation.language)
+            );
+            print_count("Total", &coverage.total);
+            for (category, count) in &coverage.categories {
+                let category = match category.as_str() {
+                    "" => "(no category)",
+                    x => x,
+                };
+                print_count(&format!("  {category}"), count);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_count(name: &str, count: &CsfCoverageCount) {
+    println!(
+        "{name:<24} {:>6.2}% {:>6}/{:<6} {:>6} changed",
+        count.percentage(),
+        count.complete(),
+        count.total,
+        count.changed
+    );
+}
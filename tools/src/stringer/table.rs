@@ -0,0 +1,116 @@
+use std::{
+    fs::{create_dir_all, OpenOptions},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+
+use rust_alert::{
+    converters::{csf2table, table2csf},
+    csf::io::{CsfReader, CsfWrite},
+    table::{
+        io::{TableReader, TableWriter},
+        TableFormatEnum,
+    },
+};
+
+use crate::{
+    utils::{language_key, read_csf},
+    Result, RunCommand,
+};
+
+#[derive(clap::Args)]
+pub struct TableCommand {
+    /// Mode of operation.
+    #[command(subcommand)]
+    mode: TableMode,
+}
+
+#[derive(Subcommand)]
+enum TableMode {
+    /// Write a table with one row per string and one column per CSF.
+    Export(ExportArgs),
+    /// Build one CSF per language column of a table.
+    Import(ImportArgs),
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    /// Path to an output table file.
+    output: PathBuf,
+    /// Paths to input CSF files, each in a different language.
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Table format. Defaults to TSV for .tsv and .tab files, and CSV otherwise.
+    #[arg(short, long, value_enum)]
+    format: Option<TableFormatEnum>,
+}
+
+#[derive(clap::Args)]
+struct ImportArgs {
+    /// Path to an input table file.
+    input: PathBuf,
+    /// Path to an output directory. CSF files are named after their language.
+    output: PathBuf,
+    /// Table format. Defaults to TSV for .tsv and .tab files, and CSV otherwise.
+    #[arg(short, long, value_enum)]
+    format: Option<TableFormatEnum>,
+}
+
+impl RunCommand for TableCommand {
+    fn run(self) -> Result<()> {
+        match self.mode {
+            TableMode::Export(x) => export(x),
+            TableMode::Import(x) => import(x),
+        }
+    }
+}
+
+fn export(args: ExportArgs) -> Result<()> {
+    let csfs = args
+        .inputs
+        .iter()
+        .map(read_csf)
+        .collect::<Result<Vec<_>>>()?;
+    let table = csf2table(&csfs)?;
+    let writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&args.output)?;
+    let format = args.format.unwrap_or_else(|| guess_format(&args.output));
+    TableWriter::write_file(&table, &mut BufWriter::new(writer), format)?;
+    Ok(())
+}
+
+fn import(args: ImportArgs) -> Result<()> {
+    let reader = OpenOptions::new().read(true).open(&args.input)?;
+    let format = args.format.unwrap_or_else(|| guess_format(&args.input));
+    let table = TableReader::read_file(BufReader::new(reader), format)?;
+    let csfs = table2csf(&table)?;
+    create_dir_all(&args.output)?;
+    for csf in &csfs {
+        let path = args
+            .output
+            .join(format!("{}.csf", language_key(csf.language)));
+        let mut writer = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        CsfReader::new().write(csf, &mut writer)?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Pick a table format by file extension.
+fn guess_format(path: &Path) -> TableFormatEnum {
+    match path.extension().and_then(|x| x.to_str()) {
+        Some(x) if x.eq_ignore_ascii_case("tsv") || x.eq_ignore_ascii_case("tab") => {
+            TableFormatEnum::Tsv
+        }
+        _ => TableFormatEnum::Csv,
+    }
+}
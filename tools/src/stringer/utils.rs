@@ -0,0 +1,79 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufReader, ErrorKind},
+    path::PathBuf,
+};
+
+use rust_alert::{
+    converters::{escape_key, unescape_key},
+    csf::{
+        io::{CsfRead, CsfReader},
+        CsfFingerprints, CsfLanguageEnum, CsfStringtable,
+    },
+    ini::{
+        io::{IniReader, IniWriter},
+        IniFile, IniSection,
+    },
+};
+
+use crate::{Error, Result};
+
+pub fn read_csf(path: &PathBuf) -> Result<CsfStringtable> {
+    let mut reader = OpenOptions::new().read(true).open(path)?;
+    Ok(CsfReader::new().read(&mut reader)?)
+}
+
+/// Read a fingerprint file, an INI file with a section of label fingerprints for every language.
+/// Label names are escaped like in [`rust_alert::converters::csf2ini`]. A missing file has
+/// no fingerprints.
+pub fn read_fingerprints(path: &PathBuf) -> Result<IniFile> {
+    match OpenOptions::new().read(true).open(path) {
+        Ok(reader) => Ok(IniReader::read_file(BufReader::new(reader))?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(IniFile::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn write_fingerprints(ini: &IniFile, path: &PathBuf) -> Result<()> {
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    IniWriter::write_file(ini, &mut writer)?;
+    Ok(())
+}
+
+/// Get the fingerprints stored for a language.
+pub fn get_fingerprints(ini: &IniFile, language: CsfLanguageEnum) -> Result<CsfFingerprints> {
+    let Some(section) = ini.get_section(language_key(language)) else {
+        return Ok(CsfFingerprints::default());
+    };
+    section
+        .iter()
+        .map(|(key, entry)| match u32::from_str_radix(&entry.value, 16) {
+            Ok(x) => Ok((unescape_key(key), x)),
+            Err(_) => Err(Error::InvalidFingerprint(
+                unescape_key(key),
+                entry.value.to_string(),
+            )),
+        })
+        .collect()
+}
+
+/// Replace the fingerprints stored for a language.
+pub fn set_fingerprints(ini: &mut IniFile, language: CsfLanguageEnum, fp: &CsfFingerprints) {
+    let mut section = IniSection::new(language_key(language));
+    for (name, fingerprint) in fp {
+        section.create_entry(escape_key(name), format!("{fingerprint:08X}"));
+    }
+    ini.add_section(section);
+}
+
+/// Name of a language in fingerprint files and reports.
+pub fn language_key(language: CsfLanguageEnum) -> String {
+    match language.code() {
+        Some(code) => code.to_string(),
+        None => u32::from(language).to_string(),
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+
+use clap::Subcommand;
+
+use rust_alert::{
+    converters::{csf2xliff, xliff2csf},
+    csf::io::{CsfReader, CsfWrite},
+    xliff::{
+        io::{XliffReader, XliffWriter},
+        XliffVersionEnum,
+    },
+};
+
+use crate::{utils::read_csf, Result, RunCommand};
+
+#[derive(clap::Args)]
+pub struct XliffCommand {
+    /// Mode of operation.
+    #[command(subcommand)]
+    mode: XliffMode,
+}
+
+#[derive(Subcommand)]
+enum XliffMode {
+    /// Write an XLIFF file from a base CSF and, optionally, its translation.
+    Export(ExportArgs),
+    /// Merge translated units of an XLIFF file into a CSF.
+    Import(ImportArgs),
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    /// Path to a CSF in the base language.
+    base: PathBuf,
+    /// Path to an output XLIFF file.
+    output: PathBuf,
+    /// Path to a translated CSF, used for targets.
+    #[arg(short, long)]
+    translation: Option<PathBuf>,
+    /// XLIFF format version.
+    #[arg(short = 'x', long, value_enum, default_value_t = XliffVersionEnum::V20)]
+    xliff_version: XliffVersionEnum,
+}
+
+#[derive(clap::Args)]
+struct ImportArgs {
+    /// Path to an input XLIFF file.
+    input: PathBuf,
+    /// Path to the CSF in the base language the XLIFF file was exported from.
+    base: PathBuf,
+    /// Path to an output CSF file.
+    output: PathBuf,
+    /// Path to a translated CSF to merge into, instead of a copy of the base CSF.
+    #[arg(short, long)]
+    into: Option<PathBuf>,
+}
+
+impl RunCommand for XliffCommand {
+    fn run(self) -> Result<()> {
+        match self.mode {
+            XliffMode::Export(x) => export(x),
+            XliffMode::Import(x) => import(x),
+        }
+    }
+}
+
+fn export(args: ExportArgs) -> Result<()> {
+    let base = read_csf(&args.base)?;
+    let translation = args.translation.as_ref().map(read_csf).transpose()?;
+    let mut xliff = csf2xliff(&base, translation.as_ref(), args.xliff_version);
+    xliff.original = args
+        .base
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&args.output)?;
+    XliffWriter::write_file(&xliff, &mut BufWriter::new(writer))?;
+    Ok(())
+}
+
+fn import(args: ImportArgs) -> Result<()> {
+    let reader = OpenOptions::new().read(true).open(&args.input)?;
+    let xliff = XliffReader::read_file(BufReader::new(reader))?;
+    let base = read_csf(&args.base)?;
+    let into = args.into.as_ref().map(read_csf).transpose()?;
+    let (csf, report) = xliff2csf(&xliff, &base, into)?;
+    let mut writer = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&args.output)?;
+    CsfReader::new().write(&csf, &mut writer)?;
+
+    for name in &report.changed {
+        println!("Source changed: {name}");
+    }
+    for name in &report.unknown {
+        println!("Unknown unit: {name}");
+    }
+    println!("# of changed sources:      {}", report.changed.len());
+    println!("# of untranslated strings: {}", report.untranslated.len());
+    Ok(())
+}